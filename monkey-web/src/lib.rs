@@ -5,6 +5,9 @@ extern crate wasm_bindgen;
 mod utils;
 
 use cfg_if::cfg_if;
+use monkey::input;
+use monkey::module::MapResolver;
+use monkey::output;
 use wasm_bindgen::prelude::*;
 
 cfg_if! {
@@ -18,21 +21,59 @@ cfg_if! {
 }
 
 #[wasm_bindgen]
-pub struct Interpreter(monkey::Interpreter);
+extern "C" {
+    // A JS function which is given each line printed by the program.
+    pub type PrintCallback;
+    #[wasm_bindgen(method, js_name = call)]
+    fn call(this: &PrintCallback, context: &JsValue, line: &str);
+
+    // A JS function which is given the prompt of `input()`, and returns the line read, or `null`
+    // or `undefined` at the end of the input.
+    pub type InputCallback;
+    #[wasm_bindgen(method, js_name = call)]
+    fn call(this: &InputCallback, context: &JsValue, message: &str) -> Option<String>;
+}
+
+// There is no filesystem in the browser, so modules have to be added with `add_module` before
+// they can be imported.
+#[wasm_bindgen]
+pub struct Interpreter {
+    interpreter: monkey::Interpreter,
+    modules: MapResolver,
+}
 
 #[wasm_bindgen]
 impl Interpreter {
-    pub fn new() -> Self {
-        Interpreter(monkey::Interpreter::new())
+    // `on_print` is called as the program runs, so printed lines show up before it finishes.
+    // `on_input` is called when the program reads a line, e.g. with `prompt` in the browser.
+    pub fn new(on_print: PrintCallback, on_input: InputCallback) -> Self {
+        let modules = MapResolver::new();
+        let mut interpreter = monkey::Interpreter::new();
+        interpreter.set_module_resolver(modules.clone());
+        interpreter.set_output(output::Callback(move |line: &str| {
+            on_print.call(&JsValue::NULL, line)
+        }));
+        interpreter.set_input(input::Callback(move |message: Option<&str>| {
+            on_input.call(&JsValue::NULL, message.unwrap_or(""))
+        }));
+
+        Interpreter {
+            interpreter,
+            modules,
+        }
+    }
+
+    pub fn add_module(&mut self, path: String, source: String) {
+        self.modules.insert(path, source);
+        self.interpreter.set_module_resolver(self.modules.clone());
     }
 
     pub fn evaluate(&mut self, s: String) -> Result<String, JsValue> {
-        let monkey::InterpreterResult { stdout, result } = self.0.evaluate(s);
+        let monkey::InterpreterResult { result, .. } = self.interpreter.evaluate(&s);
 
-        // TODO figure out how to pass structs to JS instead of using this hacky delimiter.
         match result {
-            Ok(x) => Ok(format!("{}|{}", stdout, x)),
-            Err(x) => Err(JsValue::from_str(&format!("{}|{}", stdout, x))),
+            Ok(x) => Ok(x.to_string()),
+            Err(x) => Err(JsValue::from_str(&x.to_string())),
         }
     }
 }
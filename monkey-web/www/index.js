@@ -2,26 +2,26 @@ import { Interpreter } from 'monkey-web';
 
 const PROMPT = '>>>';
 
-const interpreter = Interpreter.new();
-
 const inputField = document.getElementById('input');
 
 const stdoutContainer = document.getElementById('stdout');
 
+// Printed lines are shown as the program runs, and input is read in a dialog, which gives `null`
+// when it is cancelled.
+const interpreter = Interpreter.new(
+  line => appendOutput('history-output-ok', line),
+  message => prompt(message)
+);
+
 inputField.addEventListener('keyup', element => {
   if (event.key === 'Enter') {
     const input = element.target.value;
     appendOutput('history-input', `${PROMPT} ${input}`);
 
     try {
-      const output = interpreter.evaluate(input);
-      const { stdout, result } = parseOutput(output);
-      appendOutput('history-output-ok', stdout);
-      appendOutput('history-output-ok', result);
+      appendOutput('history-output-ok', interpreter.evaluate(input));
     } catch (error) {
-      const { stdout, result } = parseOutput(error);
-      appendOutput('history-output-ok', stdout);
-      appendOutput('history-output-err', result);
+      appendOutput('history-output-err', error);
     }
 
     element.target.value = '';
@@ -48,17 +48,3 @@ const terminal = document.getElementById('terminal');
 terminal.addEventListener('click', _ => {
   inputField.focus();
 });
-
-// TODO Better way to pass structs from WASM to JS.
-const parseOutput = (output) => {
-  console.info(output);
-  const splitted = output.split("|");
-  if (splitted.length !== 2) {
-    throw new Error("Unexpected number of elements after parsing string from WASM")
-  }
-
-  return {
-    stdout: splitted[0],
-    result: splitted[1],
-  };
-}
@@ -1,3 +1,4 @@
+use crate::bigint::BigInt;
 use std::fmt;
 
 #[cfg(test)]
@@ -20,7 +21,11 @@ pub struct Program {
 pub enum Expression {
     Identifier(String),
     IntegerLiteral(usize),
+    BigIntegerLiteral(BigInt),
+    FloatLiteral(f64),
     StringLiteral(String),
+    // A string with `${}` interpolations, e.g. `"total: ${a + b}"`.
+    Interpolation(Vec<InterpolationPart>),
     ListLiteral(Vec<Expression>),
     Prefix {
         operator: Operator,
@@ -46,6 +51,30 @@ pub enum Expression {
         left: Box<Expression>,
         index: Box<Expression>,
     },
+    // The target is an identifier, or index expressions on an identifier, e.g. `xs[0][1]`.
+    // `operator` is set for compound assignments like `x += 1`.
+    Assign {
+        target: Box<Expression>,
+        operator: Option<Operator>,
+        value: Box<Expression>,
+    },
+    // Evaluates to the value of `body`, or of `handler` if an error is thrown in `body`. The
+    // error is bound to `name` in the handler.
+    Try {
+        body: Statements,
+        name: String,
+        handler: Statements,
+    },
+    // `module.name`, to access the exports of a module, or the fields of an error.
+    Member {
+        object: Box<Expression>,
+        name: String,
+    },
+    // Evaluates the body of the first arm whose pattern matches the value.
+    Match {
+        value: Box<Expression>,
+        arms: Vec<MatchArm>,
+    },
 }
 
 impl Display for Expression {
@@ -53,7 +82,16 @@ impl Display for Expression {
         let string_val: String = match *self {
             Identifier(ref name) => name.to_string(),
             IntegerLiteral(ref val) => val.to_string(),
+            BigIntegerLiteral(ref val) => val.to_string(),
+            FloatLiteral(ref val) => format!("{:?}", val),
             StringLiteral(ref val) => format!(r#""{}""#, val.to_string()),
+            Interpolation(ref parts) => format!(
+                r#""{}""#,
+                parts
+                    .iter()
+                    .map(|part| part.to_string())
+                    .collect::<String>()
+            ),
             ListLiteral(ref vals) => format!("[{}]", format_vec(vals)),
             Prefix {
                 ref operator,
@@ -74,32 +112,82 @@ impl Display for Expression {
                 ref left,
                 ref index,
             } => format!("({}[{}])", left, index),
+            Assign {
+                ref target,
+                ref operator,
+                ref value,
+            } => match operator {
+                Some(operator) => format!("({} {}= {})", target, operator, value),
+                None => format!("({} = {})", target, value),
+            },
+            Try {
+                ref body,
+                ref name,
+                ref handler,
+            } => format!(
+                "try {{ {} }} catch ({}) {{ {} }}",
+                format_vec(body),
+                name,
+                format_vec(handler)
+            ),
+            Member {
+                ref object,
+                ref name,
+            } => format!("({}.{})", object, name),
+            Match {
+                ref value,
+                ref arms,
+            } => format!("match ({}) {{ {} }}", value, format_vec(arms)),
             ref x => unimplemented!("Token: {:?}", x),
         };
         write!(f, "{}", string_val)
     }
 }
 
-pub fn format_vec<T: Display>(vec: &Vec<T>) -> String {
+pub fn format_vec<T: Display>(vec: &[T]) -> String {
     vec.iter()
         .map(|val| val.to_string())
         .collect::<Vec<String>>()
         .join(", ")
 }
 
+#[derive(PartialEq, Debug, Clone)]
+pub enum InterpolationPart {
+    Str(String),
+    // Converted to a string with the `Display` of the object it evaluates to.
+    Expression(Expression),
+}
+
+impl Display for InterpolationPart {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            InterpolationPart::Str(string) => write!(f, "{}", string),
+            InterpolationPart::Expression(expr) => write!(f, "${{{}}}", expr),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Statement {
-    Let(String, Expression),
+    Let(Pattern, Expression),
     Return(Expression),
     Expression(Expression),
+    Throw(Expression),
+    // `import "path" as name;`
+    Import { path: String, name: String },
+    // `export let pattern = value;`, which is also importable from other modules.
+    Export(Pattern, Expression),
 }
 
 impl Display for Statement {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let string_val: String = match *self {
-            Let(ref name, ref expr) => format!("let {} = {}", name, expr.to_string()),
+            Let(ref pattern, ref expr) => format!("let {} = {}", pattern, expr.to_string()),
             Return(ref expr) => expr.to_string(),
             Expression(ref expr) => expr.to_string(),
+            Throw(ref expr) => format!("throw {}", expr),
+            Import { ref path, ref name } => format!(r#"import "{}" as {}"#, path, name),
+            Export(ref pattern, ref expr) => format!("export let {} = {}", pattern, expr),
         };
         write!(f, "{}", string_val)
     }
@@ -111,11 +199,17 @@ pub enum Operator {
     Minus,
     Multiply,
     Divide,
+    Modulo,
     Not,
     LessThan,
     GreaterThan,
+    LessThanEqual,
+    GreaterThanEqual,
     Equal,
     NotEqual,
+    // Short-circuiting, so the right side is only evaluated when it decides the result.
+    And,
+    Or,
 }
 
 impl Display for Operator {
@@ -125,19 +219,99 @@ impl Display for Operator {
             Minus => "-",
             Multiply => "*",
             Divide => "/",
+            Modulo => "%",
             Not => "!",
             LessThan => "<",
             GreaterThan => ">",
+            LessThanEqual => "<=",
+            GreaterThanEqual => ">=",
             Equal => "==",
             NotEqual => "!=",
+            And => "&&",
+            Or => "||",
         };
         write!(f, "{}", str_val)
     }
 }
 
+// Binds names to the parts of a value in `let` statements, function parameters and match arms.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Pattern {
+    Identifier(String),
+    // `_`, which matches anything without binding it.
+    Wildcard,
+    // Only in match arms, where it matches values equal to the literal.
+    Literal(Expression),
+    // `[a, b, ...rest]`, where `rest` is bound to a list of the elements after `b`.
+    List {
+        elements: Vec<Pattern>,
+        rest: Option<String>,
+    },
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Pattern::Identifier(name) => write!(f, "{}", name),
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Literal(literal) => write!(f, "{}", literal),
+            Pattern::List {
+                elements,
+                rest: None,
+            } => write!(f, "[{}]", format_vec(elements)),
+            Pattern::List {
+                elements,
+                rest: Some(rest),
+            } if elements.is_empty() => write!(f, "[...{}]", rest),
+            Pattern::List {
+                elements,
+                rest: Some(rest),
+            } => write!(f, "[{}, ...{}]", format_vec(elements), rest),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    // The arm is skipped when the guard after `if` isn't truthy.
+    pub guard: Option<Expression>,
+    pub body: Expression,
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.guard {
+            Some(guard) => write!(f, "{} if {} => {}", self.pattern, guard, self.body),
+            None => write!(f, "{} => {}", self.pattern, self.body),
+        }
+    }
+}
+
+// Parameters with defaults come after the required ones, and a variadic parameter can only be
+// the last one, e.g. `fn(a, b = 1, ...rest)`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Param {
+    Required(Pattern),
+    // The default is evaluated when the function is called without the argument.
+    Optional(Pattern, Expression),
+    // Bound to a list of the remaining arguments.
+    Variadic(String),
+}
+
+impl Display for Param {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Param::Required(pattern) => write!(f, "{}", pattern),
+            Param::Optional(pattern, default) => write!(f, "{} = {}", pattern, default),
+            Param::Variadic(name) => write!(f, "...{}", name),
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub struct Function {
-    pub params: Vec<String>,
+    pub params: Vec<Param>,
     pub body: Statements,
 }
 
@@ -156,6 +330,8 @@ impl Display for Function {
 pub enum CallFunctionExpression {
     Identifier(String),
     Literal(Function),
+    // Any other expression evaluating to a function, e.g. `module.name`.
+    Expression(Box<Expression>),
 }
 
 impl Display for CallFunctionExpression {
@@ -163,6 +339,7 @@ impl Display for CallFunctionExpression {
         let string_val: String = match self {
             CallFunctionExpression::Identifier(ref name) => name.to_string(),
             CallFunctionExpression::Literal(func) => format!("{}", func),
+            CallFunctionExpression::Expression(expr) => format!("{}", expr),
         };
         write!(f, "{}", string_val)
     }
@@ -1,4 +1,6 @@
-use crate::ast::{CallFunctionExpression, Expression, Function, Operator, Statement};
+use crate::ast::{
+    CallFunctionExpression, Expression, Function, Operator, Param, Pattern, Statement,
+};
 
 #[test]
 fn test_display() {
@@ -38,7 +40,10 @@ fn test_display() {
         ),
         (
             Expression::FunctionLiteral(Function {
-                params: vec!["x".to_string(), "y".to_string()],
+                params: vec![
+                    Param::Required(Pattern::Identifier("x".to_string())),
+                    Param::Required(Pattern::Identifier("y".to_string())),
+                ],
                 body: vec![
                     Statement::Expression(Expression::Infix {
                         operator: Operator::Plus,
@@ -53,7 +58,10 @@ fn test_display() {
         (
             Expression::Call {
                 function: CallFunctionExpression::Literal(Function {
-                    params: vec!["x".to_string(), "y".to_string()],
+                    params: vec![
+                        Param::Required(Pattern::Identifier("x".to_string())),
+                        Param::Required(Pattern::Identifier("y".to_string())),
+                    ],
                     body: vec![
                         Statement::Expression(Expression::Infix {
                             operator: Operator::Plus,
@@ -100,3 +108,43 @@ fn test_display() {
         assert_eq!(expected, received);
     }
 }
+
+#[test]
+fn test_pattern_display() {
+    let identifier = |name: &str| Pattern::Identifier(name.to_string());
+
+    let cases = vec![
+        (identifier("a"), "a"),
+        (
+            Pattern::List {
+                elements: vec![],
+                rest: None,
+            },
+            "[]",
+        ),
+        (
+            Pattern::List {
+                elements: vec![],
+                rest: Some("rest".to_string()),
+            },
+            "[...rest]",
+        ),
+        (
+            Pattern::List {
+                elements: vec![
+                    identifier("a"),
+                    Pattern::List {
+                        elements: vec![identifier("b")],
+                        rest: None,
+                    },
+                ],
+                rest: Some("rest".to_string()),
+            },
+            "[a, [b], ...rest]",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        assert_eq!(expected, format!("{}", inp));
+    }
+}
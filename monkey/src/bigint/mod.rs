@@ -0,0 +1,410 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use std::{fmt, ops};
+
+#[cfg(test)]
+mod tests;
+
+// Each digit is stored in base 2^32, so that the product of two digits fits in a u64.
+const DIGIT_BITS: u32 = 32;
+const BASE: f64 = 4_294_967_296.0;
+
+// Largest power of 10 which fits in a digit, used when converting to and from decimal strings.
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_BASE_DIGITS: usize = 9;
+
+// Arbitrary precision integer stored as a sign and a magnitude.
+//
+// Digits are little-endian and never have trailing zeros, and zero is never negative, so each value
+// has exactly one representation and equality can be derived.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {}
+
+impl BigInt {
+    fn new(negative: bool, mut digits: Vec<u32>) -> Self {
+        trim(&mut digits);
+
+        Self {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // Number of bytes used to store the magnitude.
+    pub fn size(&self) -> usize {
+        self.digits.len() * std::mem::size_of::<u32>()
+    }
+
+    pub fn to_isize(&self) -> Option<isize> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+
+        let magnitude = self.digits.iter().rev().fold(0_i128, |acc, &digit| {
+            (acc << DIGIT_BITS) | i128::from(digit)
+        });
+
+        isize::try_from(if self.negative { -magnitude } else { magnitude }).ok()
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.negative || self.digits.len() > 2 {
+            return None;
+        }
+
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0_u64, |acc, &digit| (acc << DIGIT_BITS) | u64::from(digit)),
+        )
+    }
+
+    // Nearest float, which may lose precision or be infinite for very large values.
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0_f64, |acc, &digit| acc * BASE + f64::from(digit));
+
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    // Truncates the fractional part of a float. Returns `None` if the float is infinite or NaN.
+    pub fn from_f64(val: f64) -> Option<Self> {
+        if !val.is_finite() {
+            return None;
+        }
+
+        let mut magnitude = val.abs().trunc();
+        let mut digits = Vec::new();
+        while magnitude >= 1.0 {
+            digits.push((magnitude % BASE) as u32);
+            magnitude = (magnitude / BASE).trunc();
+        }
+
+        Some(Self::new(val < 0.0, digits))
+    }
+
+    // Division truncating towards zero, like the primitive integer types. Returns `None` when
+    // dividing by zero.
+    pub fn checked_div(&self, other: &Self) -> Option<Self> {
+        self.checked_div_rem(other).map(|(quotient, _)| quotient)
+    }
+
+    // Remainder with the sign of the dividend, like the primitive integer types. Returns `None`
+    // when dividing by zero.
+    pub fn checked_rem(&self, other: &Self) -> Option<Self> {
+        self.checked_div_rem(other).map(|(_, remainder)| remainder)
+    }
+
+    fn checked_div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = div_rem(&self.digits, &other.digits);
+
+        Some((
+            Self::new(self.negative != other.negative, quotient),
+            Self::new(self.negative, remainder),
+        ))
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(val: u64) -> Self {
+        Self::new(false, vec![val as u32, (val >> DIGIT_BITS) as u32])
+    }
+}
+
+impl From<usize> for BigInt {
+    fn from(val: usize) -> Self {
+        Self::from(val as u64)
+    }
+}
+
+impl From<isize> for BigInt {
+    fn from(val: isize) -> Self {
+        let magnitude = Self::from(val.unsigned_abs());
+
+        Self::new(val < 0, magnitude.digits)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        if s.is_empty() || !s.chars().all(|ch| ch.is_ascii_digit()) {
+            return Err(ParseError {});
+        }
+
+        // The first chunk is the shortest, so that the rest are exactly `DECIMAL_BASE_DIGITS` long.
+        let first_len = match s.len() % DECIMAL_BASE_DIGITS {
+            0 => DECIMAL_BASE_DIGITS,
+            len => len,
+        }
+        .min(s.len());
+
+        let chunks = std::iter::once(&s[..first_len]).chain(
+            s.as_bytes()[first_len..]
+                .chunks(DECIMAL_BASE_DIGITS)
+                // Chunks are ASCII digits, so they are always valid UTF-8.
+                .map(|chunk| std::str::from_utf8(chunk).unwrap()),
+        );
+
+        let digits = chunks.fold(Vec::new(), |digits, chunk| {
+            let chunk: u32 = chunk.parse().unwrap();
+            mul_add_digit(&digits, DECIMAL_BASE, chunk)
+        });
+
+        Ok(Self::new(negative, digits))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // Repeatedly divide by the decimal base to get chunks of decimal digits, least significant
+        // first.
+        let mut chunks = Vec::new();
+        let mut digits = self.digits.clone();
+        while !digits.is_empty() {
+            let (quotient, remainder) = div_rem_digit(&digits, DECIMAL_BASE);
+            chunks.push(remainder);
+            digits = quotient;
+        }
+
+        let mut chunks = chunks.into_iter().rev();
+        let first = chunks.next().unwrap_or(0);
+        let rest: String = chunks
+            .map(|chunk| format!("{:0width$}", chunk, width = DECIMAL_BASE_DIGITS))
+            .collect();
+
+        write!(
+            f,
+            "{}{}{}",
+            if self.negative { "-" } else { "" },
+            first,
+            rest
+        )
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.digits, &other.digits),
+            (true, true) => cmp_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        Self::new(!self.negative, self.digits)
+    }
+}
+
+impl ops::Add for BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return Self::new(self.negative, add(&self.digits, &other.digits));
+        }
+
+        // Signs differ, so subtract the smaller magnitude from the larger one.
+        match cmp_magnitude(&self.digits, &other.digits) {
+            Ordering::Less => Self::new(other.negative, sub(&other.digits, &self.digits)),
+            _ => Self::new(self.negative, sub(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl ops::Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: BigInt) -> BigInt {
+        self + -other
+    }
+}
+
+impl ops::Mul for BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: BigInt) -> BigInt {
+        Self::new(
+            self.negative != other.negative,
+            mul(&self.digits, &other.digits),
+        )
+    }
+}
+
+// The functions below operate on magnitudes only.
+
+fn trim(digits: &mut Vec<u32>) {
+    while let Some(&0) = digits.last() {
+        digits.pop();
+    }
+}
+
+fn cmp_magnitude(left: &[u32], right: &[u32]) -> Ordering {
+    left.len()
+        .cmp(&right.len())
+        .then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+fn add(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let len = left.len().max(right.len());
+    let mut result = Vec::with_capacity(len + 1);
+    let mut carry = 0_u64;
+
+    for i in 0..len {
+        let sum =
+            u64::from(*left.get(i).unwrap_or(&0)) + u64::from(*right.get(i).unwrap_or(&0)) + carry;
+        result.push(sum as u32);
+        carry = sum >> DIGIT_BITS;
+    }
+    result.push(carry as u32);
+
+    trim(&mut result);
+    result
+}
+
+// Expects the magnitude of `left` to be at least that of `right`.
+fn sub(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(left.len());
+    let mut borrow = 0_i64;
+
+    for (i, &digit) in left.iter().enumerate() {
+        let mut diff = i64::from(digit) - i64::from(*right.get(i).unwrap_or(&0)) - borrow;
+        borrow = if diff < 0 {
+            diff += 1 << DIGIT_BITS;
+            1
+        } else {
+            0
+        };
+        result.push(diff as u32);
+    }
+
+    trim(&mut result);
+    result
+}
+
+fn mul(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = vec![0_u32; left.len() + right.len()];
+
+    for (i, &l) in left.iter().enumerate() {
+        let mut carry = 0_u64;
+        for (j, &r) in right.iter().enumerate() {
+            let product = u64::from(l) * u64::from(r) + u64::from(result[i + j]) + carry;
+            result[i + j] = product as u32;
+            carry = product >> DIGIT_BITS;
+        }
+        result[i + right.len()] = carry as u32;
+    }
+
+    trim(&mut result);
+    result
+}
+
+// Computes `digits * multiplier + addend`.
+fn mul_add_digit(digits: &[u32], multiplier: u32, addend: u32) -> Vec<u32> {
+    let mut result = Vec::with_capacity(digits.len() + 1);
+    let mut carry = u64::from(addend);
+
+    for &digit in digits {
+        let product = u64::from(digit) * u64::from(multiplier) + carry;
+        result.push(product as u32);
+        carry = product >> DIGIT_BITS;
+    }
+    result.push(carry as u32);
+
+    trim(&mut result);
+    result
+}
+
+fn div_rem_digit(digits: &[u32], divisor: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0_u32; digits.len()];
+    let mut remainder = 0_u64;
+
+    for (i, &digit) in digits.iter().enumerate().rev() {
+        let current = (remainder << DIGIT_BITS) | u64::from(digit);
+        quotient[i] = (current / u64::from(divisor)) as u32;
+        remainder = current % u64::from(divisor);
+    }
+
+    trim(&mut quotient);
+    (quotient, remainder as u32)
+}
+
+// Expects `divisor` to be non-zero.
+fn div_rem(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [digit] = divisor {
+        let (quotient, remainder) = div_rem_digit(dividend, *digit);
+        return (quotient, vec![remainder]);
+    }
+
+    // Schoolbook long division, one bit at a time. This is slow but simple, and dividing two large
+    // numbers is rare enough in scripts that it doesn't matter.
+    let mut quotient = vec![0_u32; dividend.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for i in (0..dividend.len()).rev() {
+        for bit in (0..DIGIT_BITS).rev() {
+            remainder = mul_add_digit(&remainder, 2, (dividend[i] >> bit) & 1);
+
+            if cmp_magnitude(&remainder, divisor) != Ordering::Less {
+                remainder = sub(&remainder, divisor);
+                quotient[i] |= 1 << bit;
+            }
+        }
+    }
+
+    trim(&mut quotient);
+    (quotient, remainder)
+}
@@ -0,0 +1,197 @@
+use super::*;
+
+fn big(s: &str) -> BigInt {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_parse_and_display() {
+    let tests = vec![
+        "0",
+        "1",
+        "-1",
+        "4294967295",
+        "4294967296",
+        "1000000000",
+        "999999999999999999",
+        "-9223372036854775808",
+        "18446744073709551616",
+        "2432902008176640000",
+        "30414093201713378043612608166064768844377641568960512000000000000",
+    ];
+
+    for s in tests {
+        assert_eq!(s, format!("{}", big(s)));
+    }
+}
+
+#[test]
+fn test_parse_error() {
+    let tests = vec!["", "-", "1a", "1.0", " 1", "--1"];
+
+    for s in tests {
+        assert_eq!(Err(ParseError {}), s.parse::<BigInt>());
+    }
+}
+
+#[test]
+fn test_normalized() {
+    assert_eq!(BigInt::zero(), big("-0"));
+    assert_eq!(BigInt::zero(), big("000"));
+    assert_eq!(big("1"), big("-1") + big("2"));
+    assert_eq!(
+        BigInt::zero(),
+        big("18446744073709551616") - big("18446744073709551616")
+    );
+}
+
+#[test]
+fn test_from_primitive() {
+    let tests = vec![
+        (BigInt::from(0_isize), "0"),
+        (BigInt::from(-5_isize), "-5"),
+        (BigInt::from(isize::MAX), "9223372036854775807"),
+        (BigInt::from(isize::MIN), "-9223372036854775808"),
+        (BigInt::from(usize::MAX), "18446744073709551615"),
+    ];
+
+    for (val, expected) in tests {
+        assert_eq!(expected, format!("{}", val));
+    }
+}
+
+#[test]
+fn test_to_isize() {
+    let tests = vec![
+        ("0", Some(0)),
+        ("-5", Some(-5)),
+        ("9223372036854775807", Some(isize::MAX)),
+        ("-9223372036854775808", Some(isize::MIN)),
+        ("9223372036854775808", None),
+        ("-9223372036854775809", None),
+        ("18446744073709551616", None),
+    ];
+
+    for (s, expected) in tests {
+        assert_eq!(expected, big(s).to_isize());
+    }
+}
+
+#[test]
+fn test_to_u64() {
+    let tests = vec![
+        ("0", Some(0)),
+        ("9223372036854775808", Some(1 << 63)),
+        ("18446744073709551615", Some(u64::MAX)),
+        ("18446744073709551616", None),
+        ("-1", None),
+    ];
+
+    for (s, expected) in tests {
+        assert_eq!(expected, big(s).to_u64());
+    }
+}
+
+#[test]
+fn test_arithmetic() {
+    let tests = vec![
+        (big("9223372036854775807") + big("1"), "9223372036854775808"),
+        (
+            big("-9223372036854775808") - big("1"),
+            "-9223372036854775809",
+        ),
+        (
+            big("1") - big("18446744073709551616"),
+            "-18446744073709551615",
+        ),
+        (big("-5") + big("3"), "-2"),
+        (big("5") + big("-3"), "2"),
+        (big("-5") - big("-5"), "0"),
+        (
+            big("4294967296") * big("4294967296"),
+            "18446744073709551616",
+        ),
+        (
+            big("-123456789012345678901234567890") * big("987654321"),
+            "-121932631124828532112482853211126352690",
+        ),
+        (big("0") * big("-5"), "0"),
+    ];
+
+    for (val, expected) in tests {
+        assert_eq!(expected, format!("{}", val));
+    }
+}
+
+#[test]
+fn test_div_rem() {
+    let tests = vec![
+        ("7", "2", "3", "1"),
+        ("-7", "2", "-3", "-1"),
+        ("7", "-2", "-3", "1"),
+        ("-7", "-2", "3", "-1"),
+        (
+            "121932631124828532112482853211126352690",
+            "987654321",
+            "123456789012345678901234567890",
+            "0",
+        ),
+        (
+            "121932631124828532112482853211126352691",
+            "123456789012345678901234567890",
+            "987654321",
+            "1",
+        ),
+        (
+            "18446744073709551616",
+            "18446744073709551617",
+            "0",
+            "18446744073709551616",
+        ),
+    ];
+
+    for (left, right, quotient, remainder) in tests {
+        assert_eq!(Some(big(quotient)), big(left).checked_div(&big(right)));
+        assert_eq!(Some(big(remainder)), big(left).checked_rem(&big(right)));
+    }
+
+    assert_eq!(None, big("1").checked_div(&BigInt::zero()));
+    assert_eq!(None, big("1").checked_rem(&BigInt::zero()));
+}
+
+#[test]
+fn test_cmp() {
+    let tests = vec![
+        ("1", "2", Ordering::Less),
+        ("-1", "-2", Ordering::Greater),
+        ("-1", "1", Ordering::Less),
+        ("18446744073709551616", "4294967296", Ordering::Greater),
+        ("-18446744073709551616", "4294967296", Ordering::Less),
+        ("-18446744073709551616", "-4294967296", Ordering::Less),
+        ("4294967296", "4294967296", Ordering::Equal),
+    ];
+
+    for (left, right, expected) in tests {
+        assert_eq!(expected, big(left).cmp(&big(right)));
+    }
+}
+
+#[test]
+fn test_f64() {
+    let tests = vec![
+        ("0", 0.0),
+        ("-5", -5.0),
+        ("4294967296", 4294967296.0),
+        ("-18446744073709551616", -18446744073709551616.0),
+    ];
+
+    for (s, expected) in tests {
+        assert_eq!(expected, big(s).to_f64());
+        assert_eq!(Some(big(s)), BigInt::from_f64(expected));
+    }
+
+    assert_eq!(Some(big("-1")), BigInt::from_f64(-1.9));
+    assert_eq!(Some(big("100000000000000000000")), BigInt::from_f64(1e20));
+    assert_eq!(None, BigInt::from_f64(f64::INFINITY));
+    assert_eq!(None, BigInt::from_f64(f64::NAN));
+}
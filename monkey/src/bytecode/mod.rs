@@ -22,9 +22,13 @@ pub enum Instruction {
     OpSub,
     OpMul,
     OpDiv,
+    OpMod,
     OpTrue,
     OpFalse,
     OpGreaterThan,
+    OpLessThan,
+    OpGreaterThanEqual,
+    OpLessThanEqual,
     OpEqual,
     OpNotEqual,
     OpNeg,
@@ -32,6 +36,30 @@ pub enum Instruction {
     OpJump(u16),
     OpJumpNotTruthy(u16),
     OpNull,
+    OpGetGlobal(u16),
+    OpSetGlobal(u16),
+    OpArray(u16),
+    OpIndex,
+    OpPeekIndex(u16),
+    OpSetIndex(u16),
+    OpNoMatch,
+    OpInterpolate(u16),
+    OpThrow,
+    OpGetLocal(u16),
+    OpSetLocal(u16),
+    OpGetOuter(u16, u16),
+    OpSetOuter(u16, u16),
+    OpGetName(u16),
+    OpClosure(u16),
+    OpCall(u16),
+    OpReturnValue,
+    OpJumpArgGiven(u16, u16),
+    OpGetMember(u16),
+    OpJumpTable(u16),
+    OpMatchList(u16),
+    OpCheckList(u16),
+    OpSliceFrom(u16),
+    OpCallMethod(u16, u16),
 }
 
 impl Instruction {
@@ -39,6 +67,24 @@ impl Instruction {
         let definition: Definition = self.into();
         definition.size
     }
+
+    // How many objects the instruction adds to the stack, or removes when negative.
+    pub fn stack_effect(&self) -> isize {
+        match *self {
+            OpConstant(_) | OpNull | OpTrue | OpFalse | OpGetGlobal(_) | OpGetLocal(_)
+            | OpGetOuter(..) | OpGetName(_) | OpClosure(_) | OpPeekIndex(_) => 1,
+            OpPop | OpAdd | OpSub | OpMul | OpDiv | OpMod | OpGreaterThan | OpLessThan
+            | OpGreaterThanEqual | OpLessThanEqual | OpEqual | OpNotEqual | OpJumpNotTruthy(_)
+            | OpSetGlobal(_) | OpSetLocal(_) | OpSetOuter(..) | OpIndex | OpNoMatch | OpThrow
+            | OpReturnValue | OpJumpTable(_) | OpCheckList(_) => -1,
+            OpNeg | OpNot | OpJump(_) | OpJumpArgGiven(..) | OpGetMember(_) | OpMatchList(_)
+            | OpSliceFrom(_) => 0,
+            OpArray(len) | OpInterpolate(len) => 1 - len as isize,
+            OpSetIndex(depth) => -(depth as isize),
+            // The function is replaced by the result.
+            OpCall(len) | OpCallMethod(_, len) => -(len as isize),
+        }
+    }
 }
 
 impl fmt::Display for Instruction {
@@ -19,6 +19,34 @@ pub const OP_NOT: OpCode = 13;
 pub const OP_JUMP: OpCode = 14;
 pub const OP_JUMP_NOT_TRUTHY: OpCode = 15;
 pub const OP_NULL: OpCode = 16;
+pub const OP_MOD: OpCode = 17;
+pub const OP_LESS_THAN: OpCode = 18;
+pub const OP_GREATER_THAN_EQUAL: OpCode = 19;
+pub const OP_LESS_THAN_EQUAL: OpCode = 20;
+pub const OP_GET_GLOBAL: OpCode = 21;
+pub const OP_SET_GLOBAL: OpCode = 22;
+pub const OP_ARRAY: OpCode = 23;
+pub const OP_INDEX: OpCode = 24;
+pub const OP_PEEK_INDEX: OpCode = 25;
+pub const OP_SET_INDEX: OpCode = 26;
+pub const OP_NO_MATCH: OpCode = 27;
+pub const OP_INTERPOLATE: OpCode = 28;
+pub const OP_THROW: OpCode = 31;
+pub const OP_GET_LOCAL: OpCode = 32;
+pub const OP_SET_LOCAL: OpCode = 33;
+pub const OP_GET_OUTER: OpCode = 34;
+pub const OP_SET_OUTER: OpCode = 35;
+pub const OP_GET_NAME: OpCode = 36;
+pub const OP_CLOSURE: OpCode = 37;
+pub const OP_CALL: OpCode = 38;
+pub const OP_RETURN_VALUE: OpCode = 39;
+pub const OP_JUMP_ARG_GIVEN: OpCode = 40;
+pub const OP_GET_MEMBER: OpCode = 41;
+pub const OP_JUMP_TABLE: OpCode = 42;
+pub const OP_MATCH_LIST: OpCode = 43;
+pub const OP_CHECK_LIST: OpCode = 44;
+pub const OP_SLICE_FROM: OpCode = 45;
+pub const OP_CALL_METHOD: OpCode = 46;
 
 pub struct Definition {
     pub code: OpCode,
@@ -71,11 +99,31 @@ impl From<&Instruction> for Definition {
                 code: OP_DIV,
                 size: 1,
             },
+            OpMod => Self {
+                name: "OpMod",
+                code: OP_MOD,
+                size: 1,
+            },
             OpGreaterThan => Self {
                 name: "OpGreaterThan",
                 code: OP_GREATER_THAN,
                 size: 1,
             },
+            OpLessThan => Self {
+                name: "OpLessThan",
+                code: OP_LESS_THAN,
+                size: 1,
+            },
+            OpGreaterThanEqual => Self {
+                name: "OpGreaterThanEqual",
+                code: OP_GREATER_THAN_EQUAL,
+                size: 1,
+            },
+            OpLessThanEqual => Self {
+                name: "OpLessThanEqual",
+                code: OP_LESS_THAN_EQUAL,
+                size: 1,
+            },
             OpEqual => Self {
                 name: "OpEqual",
                 code: OP_EQUAL,
@@ -111,6 +159,126 @@ impl From<&Instruction> for Definition {
                 code: OP_NULL,
                 size: 1,
             },
+            OpGetGlobal(_) => Self {
+                name: "OpGetGlobal",
+                code: OP_GET_GLOBAL,
+                size: 1 + 2,
+            },
+            OpSetGlobal(_) => Self {
+                name: "OpSetGlobal",
+                code: OP_SET_GLOBAL,
+                size: 1 + 2,
+            },
+            OpArray(_) => Self {
+                name: "OpArray",
+                code: OP_ARRAY,
+                size: 1 + 2,
+            },
+            OpIndex => Self {
+                name: "OpIndex",
+                code: OP_INDEX,
+                size: 1,
+            },
+            OpPeekIndex(_) => Self {
+                name: "OpPeekIndex",
+                code: OP_PEEK_INDEX,
+                size: 1 + 2,
+            },
+            OpSetIndex(_) => Self {
+                name: "OpSetIndex",
+                code: OP_SET_INDEX,
+                size: 1 + 2,
+            },
+            OpNoMatch => Self {
+                name: "OpNoMatch",
+                code: OP_NO_MATCH,
+                size: 1,
+            },
+            OpInterpolate(_) => Self {
+                name: "OpInterpolate",
+                code: OP_INTERPOLATE,
+                size: 1 + 2,
+            },
+            OpThrow => Self {
+                name: "OpThrow",
+                code: OP_THROW,
+                size: 1,
+            },
+            OpGetLocal(_) => Self {
+                name: "OpGetLocal",
+                code: OP_GET_LOCAL,
+                size: 1 + 2,
+            },
+            OpSetLocal(_) => Self {
+                name: "OpSetLocal",
+                code: OP_SET_LOCAL,
+                size: 1 + 2,
+            },
+            OpGetOuter(..) => Self {
+                name: "OpGetOuter",
+                code: OP_GET_OUTER,
+                size: 1 + 2 + 2,
+            },
+            OpSetOuter(..) => Self {
+                name: "OpSetOuter",
+                code: OP_SET_OUTER,
+                size: 1 + 2 + 2,
+            },
+            OpGetName(_) => Self {
+                name: "OpGetName",
+                code: OP_GET_NAME,
+                size: 1 + 2,
+            },
+            OpClosure(_) => Self {
+                name: "OpClosure",
+                code: OP_CLOSURE,
+                size: 1 + 2,
+            },
+            OpCall(_) => Self {
+                name: "OpCall",
+                code: OP_CALL,
+                size: 1 + 2,
+            },
+            OpReturnValue => Self {
+                name: "OpReturnValue",
+                code: OP_RETURN_VALUE,
+                size: 1,
+            },
+            OpJumpArgGiven(..) => Self {
+                name: "OpJumpArgGiven",
+                code: OP_JUMP_ARG_GIVEN,
+                size: 1 + 2 + 2,
+            },
+            OpGetMember(_) => Self {
+                name: "OpGetMember",
+                code: OP_GET_MEMBER,
+                size: 1 + 2,
+            },
+            OpJumpTable(_) => Self {
+                name: "OpJumpTable",
+                code: OP_JUMP_TABLE,
+                size: 1 + 2,
+            },
+            OpMatchList(_) => Self {
+                name: "OpMatchList",
+                code: OP_MATCH_LIST,
+                size: 1 + 2,
+            },
+            OpCheckList(_) => Self {
+                name: "OpCheckList",
+                code: OP_CHECK_LIST,
+                size: 1 + 2,
+            },
+            OpSliceFrom(_) => Self {
+                name: "OpSliceFrom",
+                code: OP_SLICE_FROM,
+                size: 1 + 2,
+            },
+            OpCallMethod(..) => Self {
+                name: "OpCallMethod",
+                code: OP_CALL_METHOD,
+                size: 1 + 2 + 2,
+            },
         }
     }
 }
@@ -1,9 +1,18 @@
+use crate::ast::Pattern;
 use std::{fmt, num};
 
 #[derive(Debug)]
 pub enum Error {
-    // TODO: Add variants.
     Overflow,
+    // The bytecode doesn't fit in the 16-bit offsets and operands of the instructions.
+    ProgramTooLarge,
+    UndefinedVariable(String),
+    // Only identifiers and index expressions on them can be assigned to.
+    InvalidAssignmentTarget,
+    // A literal pattern outside of a match arm.
+    UnsupportedPattern(Pattern),
+    // Other parts of the language which only `eval` supports for now.
+    Unsupported(String),
 }
 
 impl fmt::Display for Error {
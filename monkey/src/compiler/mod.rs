@@ -2,12 +2,17 @@ use crate::ast;
 use crate::bytecode;
 use crate::object::Object;
 pub use error::Error;
-use std::{iter, vec};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+use std::{mem, vec};
+use symbol_table::{Symbol, SymbolTable};
 
 #[cfg(test)]
 mod tests;
 
 mod error;
+mod symbol_table;
 
 #[derive(Debug)]
 pub enum CompileInstruction {
@@ -17,9 +22,13 @@ pub enum CompileInstruction {
     Sub,
     Mul,
     Div,
+    Mod,
     True,
     False,
     GreaterThan,
+    LessThan,
+    GreaterThanEqual,
+    LessThanEqual,
     Equal,
     NotEqual,
     Neg,
@@ -29,66 +38,221 @@ pub enum CompileInstruction {
     Jump(u16),
     JumpNotTruthy(u16),
     Null,
+    // The field is the index of the global in the symbol table.
+    GetGlobal(u16),
+    SetGlobal(u16),
+    // The field is the index of the local in the function being run.
+    GetLocal(u16),
+    SetLocal(u16),
+    // A local of an enclosing function, where the fields are how many functions out it is and its
+    // index there.
+    GetOuter(u16, u16),
+    SetOuter(u16, u16),
+    // A binding which wasn't defined where it was used, like a built-in or a global defined later,
+    // which is looked up by name when it runs.
+    GetName(String),
+    Closure(Function),
+    // Calls the function under the given number of arguments on the stack.
+    Call(u16),
+    // Like `Call`, but for `object.name(...)` with the object under the arguments. Like in `eval`,
+    // native objects have the method called, and other objects have their member called.
+    CallMethod(String, u16),
+    ReturnValue,
+    // Jumps when the argument at the index was given, like `Jump` for the second field. Used to skip
+    // the defaults of parameters.
+    JumpArgGiven(u16, u16),
+    // The field is the number of elements to take from the stack.
+    Array(u16),
+    Index,
+    // Index instructions for assignment, where the field is the number of indices on the stack
+    // after the object being indexed.
+    PeekIndex(u16),
+    SetIndex(u16),
+    // Fails with the value on the stack when no match arm matched it.
+    NoMatch,
+    // Jumps to the match arm for the value on the stack, where the fields are the literal of each
+    // arm with its offset, and the offset for values matching none of them, like for `Jump`.
+    JumpTable(Vec<(Object, u16)>, u16),
+    // Replaces the value on the stack with whether it is a list of the length of the list pattern.
+    MatchList(ast::Pattern),
+    // Like `MatchList`, but fails when the value doesn't match, and leaves nothing on the stack.
+    CheckList(ast::Pattern),
+    // Replaces the list on the stack with a list of its elements from the index on.
+    SliceFrom(u16),
+    // Joins the given number of objects from the stack into a string, using their `Display`.
+    Interpolate(u16),
+    // Marks the start of a try block, where the field is its length in bytes. It takes no space,
+    // but adds the block to the handler table, so that errors in it continue just past it.
+    Try(u16),
+    // Fails with the error object on the stack.
+    Throw,
+    // Replaces the object on the stack with its member of the given name, e.g. the `message` of an
+    // error.
+    GetMember(String),
 }
 
 type CompileInstructions = Vec<CompileInstruction>;
 type Result<T> = std::result::Result<T, Error>;
 
 pub fn compile(program: ast::Program) -> Result<Output> {
-    program.statements.into_iter().collect()
-}
+    let mut symbols = SymbolTable::new();
 
-impl iter::FromIterator<ast::Statement> for Result<Output> {
-    fn from_iter<I: IntoIterator<Item = ast::Statement>>(statements: I) -> Self {
-        let compiled_instructions = statements
-            .into_iter()
-            .collect::<Result<CompileInstructions>>()?;
+    let mut output = assemble(compile_statements(program.statements, &mut symbols)?)?;
+    output.names = symbols.names();
+    output.scope = symbols.scope();
 
-        let output = compiled_instructions
-            .into_iter()
-            .fold(Output::new(), |bytecode, ins| bytecode.add_instruction(ins));
+    Ok(output)
+}
 
-        Ok(output)
-    }
+// A function literal, compiled to run in a frame of its own.
+#[derive(Debug)]
+pub struct Function {
+    pub output: Output,
+    // Kept for errors about the number of arguments, and to display the function.
+    pub params: Rc<Vec<ast::Param>>,
+    pub body: Rc<ast::Statements>,
 }
 
-impl iter::FromIterator<ast::Statement> for Result<CompileInstructions> {
-    fn from_iter<I: IntoIterator<Item = ast::Statement>>(statements: I) -> Self {
-        // TODO figure out a way to avoid two collects(). Maybe flat_map ?
-        let nested_instructions = statements
-            .into_iter()
-            .map(compile_statement)
-            .collect::<Result<Vec<CompileInstructions>>>()?;
+// The arguments are the first locals of a call. Like in `eval`, each parameter is bound after
+// evaluating its default, so a default only sees the parameters before it.
+fn compile_function(func: ast::Function, symbols: &mut SymbolTable) -> Result<Function> {
+    let ast::Function { params, body } = func;
+    let mut function_symbols = SymbolTable::new_enclosed(mem::take(symbols));
 
-        let ins = nested_instructions
-            .into_iter()
-            .flatten()
-            .collect::<CompileInstructions>();
+    for _ in &params {
+        function_symbols.define_temporary()?;
+    }
 
-        let ins = if ins.is_empty() {
-            vec![CompileInstruction::Null, CompileInstruction::Pop]
-        } else {
-            ins
+    let mut result = CompileInstructions::new();
+    for (index, param) in params.iter().enumerate() {
+        let index = u16::try_from(index)?;
+        let pattern = match param {
+            ast::Param::Required(pattern) => pattern,
+            ast::Param::Optional(pattern, default) => {
+                let default = concat(
+                    compile_expr(default.clone(), &mut function_symbols)?,
+                    vec![CompileInstruction::SetLocal(index)],
+                    Vec::new(),
+                );
+                result.push(CompileInstruction::JumpArgGiven(index, ins_len(&default)?));
+                result.extend(default);
+                pattern
+            }
+            // The VM collects the remaining arguments.
+            ast::Param::Variadic(name) => {
+                function_symbols.define_at(name.to_string(), index);
+                continue;
+            }
         };
 
-        Ok(ins)
+        match pattern {
+            ast::Pattern::Identifier(name) => function_symbols.define_at(name.to_string(), index),
+            // The argument stays in its slot while its parts are bound.
+            pattern => {
+                let (tests, binding) = compile_pattern(
+                    pattern.clone(),
+                    Symbol::Local(index),
+                    &[],
+                    false,
+                    &mut function_symbols,
+                )?;
+                result.extend(tests.into_iter().flatten());
+                result.extend(binding);
+            }
+        }
     }
+    result.extend(compile_if_block(body.clone(), &mut function_symbols)?);
+    result.push(CompileInstruction::ReturnValue);
+
+    let mut output = assemble(result)?;
+    output.names = function_symbols.names();
+    if let Some(outer) = function_symbols.into_outer() {
+        *symbols = outer;
+    }
+
+    Ok(Function {
+        output,
+        params: Rc::new(params),
+        body: Rc::new(body),
+    })
 }
 
-fn compile_statement(statement: ast::Statement) -> Result<CompileInstructions> {
+fn compile_statements(
+    statements: ast::Statements,
+    symbols: &mut SymbolTable,
+) -> Result<CompileInstructions> {
+    let mut ins = CompileInstructions::new();
+    for statement in statements {
+        ins.extend(compile_statement(statement, symbols)?);
+    }
+
+    let ins = if ins.is_empty() {
+        vec![CompileInstruction::Null, CompileInstruction::Pop]
+    } else {
+        ins
+    };
+
+    Ok(ins)
+}
+
+fn compile_statement(
+    statement: ast::Statement,
+    symbols: &mut SymbolTable,
+) -> Result<CompileInstructions> {
     match statement {
-        ast::Statement::Expression(expression) => compile_expr(expression).map(|mut ins| {
-            ins.push(CompileInstruction::Pop);
-            ins
-        }),
-        _ => unimplemented!(),
+        ast::Statement::Expression(expression) => {
+            compile_expr(expression, symbols).map(|mut ins| {
+                ins.push(CompileInstruction::Pop);
+                ins
+            })
+        }
+        // A function only runs once it is bound, so it can refer to itself, e.g. to recurse.
+        ast::Statement::Let(
+            ast::Pattern::Identifier(name),
+            ast::Expression::FunctionLiteral(func),
+        ) => {
+            let symbol = symbols.define(name)?;
+            Ok(vec![
+                CompileInstruction::Closure(compile_function(func, symbols)?),
+                set_symbol(symbol),
+            ])
+        }
+        ast::Statement::Let(ast::Pattern::Identifier(name), expression) => {
+            let mut ins = compile_expr(expression, symbols)?;
+            // Define after compiling the value, so that the value can't refer to the new binding.
+            ins.push(set_symbol(symbols.define(name)?));
+            Ok(ins)
+        }
+        // The value is kept in a temporary while its parts are bound.
+        ast::Statement::Let(pattern, expression) => {
+            let mut ins = compile_expr(expression, symbols)?;
+            let temporary = symbols.define_temporary()?;
+            ins.push(set_symbol(temporary));
+
+            let (tests, binding) = compile_pattern(pattern, temporary, &[], false, symbols)?;
+            ins.extend(tests.into_iter().flatten());
+            ins.extend(binding);
+            Ok(ins)
+        }
+        ast::Statement::Throw(expression) => {
+            let mut ins = compile_expr(expression, symbols)?;
+            ins.push(CompileInstruction::Throw);
+            Ok(ins)
+        }
+        ast::Statement::Return(expression) => {
+            let mut ins = compile_expr(expression, symbols)?;
+            ins.push(CompileInstruction::ReturnValue);
+            Ok(ins)
+        }
+        ast::Statement::Import { .. } => Err(Error::Unsupported("imports".to_string())),
+        ast::Statement::Export(..) => Err(Error::Unsupported("exports".to_string())),
     }
 }
 
-fn compile_expr(expr: ast::Expression) -> Result<CompileInstructions> {
+fn compile_expr(expr: ast::Expression, symbols: &mut SymbolTable) -> Result<CompileInstructions> {
     match expr {
         ast::Expression::Prefix { operator, right } => {
-            let right_result = compile_expr(*right)?;
+            let right_result = compile_expr(*right, symbols)?;
 
             let mut result_operator = operator.into();
 
@@ -105,33 +269,89 @@ fn compile_expr(expr: ast::Expression) -> Result<CompileInstructions> {
 
             Ok(result)
         }
+        ast::Expression::Infix {
+            operator: operator @ (ast::Operator::And | ast::Operator::Or),
+            left,
+            right,
+        } => compile_logical_expr(operator, *left, *right, symbols),
         ast::Expression::Infix {
             operator,
             left,
             right,
         } => {
-            let left_result = compile_expr(*left)?;
-            let right_result = compile_expr(*right)?;
+            let left_result = compile_expr(*left, symbols)?;
+            let right_result = compile_expr(*right, symbols)?;
 
             // TODO maybe this is not so efficient.
-            let result = left_result.into_iter().chain(right_result);
-
-            // We don't have a OpLessThan, so do this instead.
-            let result: Vec<CompileInstruction> = if let ast::Operator::LessThan = operator {
-                result.rev().collect()
-            } else {
-                result.collect()
-            };
-
-            let result = result.into_iter().chain(vec![operator.into()]).collect();
+            let result = left_result
+                .into_iter()
+                .chain(right_result)
+                .chain(vec![operator.into()])
+                .collect();
 
             Ok(result)
         }
         ast::Expression::IntegerLiteral(value) => {
-            let object = Object::Integer(value as isize);
+            let object = Object::from_unsigned(value);
 
             Ok(vec![CompileInstruction::Constant(object)])
         }
+        ast::Expression::BigIntegerLiteral(value) => {
+            Ok(vec![CompileInstruction::Constant(Object::from(value))])
+        }
+        ast::Expression::FloatLiteral(value) => {
+            Ok(vec![CompileInstruction::Constant(Object::Float(value))])
+        }
+        ast::Expression::StringLiteral(value) => {
+            Ok(vec![CompileInstruction::Constant(Object::Str(value))])
+        }
+        ast::Expression::Interpolation(parts) => {
+            let len = u16::try_from(parts.len())?;
+
+            let mut result = CompileInstructions::new();
+            for part in parts {
+                match part {
+                    ast::InterpolationPart::Str(part) => {
+                        result.push(CompileInstruction::Constant(Object::Str(part)))
+                    }
+                    ast::InterpolationPart::Expression(expr) => {
+                        result.extend(compile_expr(expr, symbols)?)
+                    }
+                }
+            }
+            result.push(CompileInstruction::Interpolate(len));
+
+            Ok(result)
+        }
+        ast::Expression::Identifier(name) => match symbols.resolve(&name) {
+            Some(symbol) => Ok(vec![get_symbol(symbol)]),
+            None => Ok(vec![CompileInstruction::GetName(name)]),
+        },
+        ast::Expression::ListLiteral(values) => {
+            let len = u16::try_from(values.len())?;
+
+            let mut result = CompileInstructions::new();
+            for value in values {
+                result.extend(compile_expr(value, symbols)?);
+            }
+            result.push(CompileInstruction::Array(len));
+
+            Ok(result)
+        }
+        ast::Expression::Index { left, index } => {
+            let result = compile_expr(*left, symbols)?
+                .into_iter()
+                .chain(compile_expr(*index, symbols)?)
+                .chain(vec![CompileInstruction::Index])
+                .collect();
+
+            Ok(result)
+        }
+        ast::Expression::Assign {
+            target,
+            operator,
+            value,
+        } => compile_assign_expr(*target, operator, *value, symbols),
         ast::Expression::Boolean(value) => Ok(vec![if value {
             CompileInstruction::True
         } else {
@@ -144,16 +364,16 @@ fn compile_expr(expr: ast::Expression) -> Result<CompileInstructions> {
         } => {
             // Reverse order of compilation.
 
-            let alternative_result = compile_if_block(alternative)?;
-            let alternative_len = ins_len(&alternative_result);
+            let alternative_result = compile_if_block(alternative, symbols)?;
+            let alternative_len = ins_len(&alternative_result)?;
 
-            let consequence_result = compile_if_block(consequence)?
+            let consequence_result = compile_if_block(consequence, symbols)?
                 .into_iter()
                 .chain(vec![CompileInstruction::Jump(alternative_len)])
                 .collect::<CompileInstructions>();
 
-            let condition_result = compile_expr(*condition)?.into_iter().chain(vec![
-                CompileInstruction::JumpNotTruthy(ins_len(&consequence_result)),
+            let condition_result = compile_expr(*condition, symbols)?.into_iter().chain(vec![
+                CompileInstruction::JumpNotTruthy(ins_len(&consequence_result)?),
             ]);
 
             let result = condition_result
@@ -163,31 +383,509 @@ fn compile_expr(expr: ast::Expression) -> Result<CompileInstructions> {
 
             Ok(result)
         }
-        _ => unimplemented!(),
+        ast::Expression::Match { value, arms } => compile_match_expr(*value, arms, symbols),
+        ast::Expression::Try {
+            body,
+            name,
+            handler,
+        } => {
+            // Like in `eval`, the bindings of the body and of the handler are only in scope there.
+            symbols.begin_block();
+            let body_result = compile_if_block(body, symbols)?;
+            symbols.end_block();
+
+            // The VM pushes the caught error before continuing at the handler.
+            symbols.begin_block();
+            let handler_result = concat(
+                vec![set_symbol(symbols.define(name)?)],
+                compile_if_block(handler, symbols)?,
+                Vec::new(),
+            );
+            symbols.end_block();
+
+            let body_result = concat(
+                body_result,
+                vec![CompileInstruction::Jump(ins_len(&handler_result)?)],
+                Vec::new(),
+            );
+
+            Ok(concat(
+                vec![CompileInstruction::Try(ins_len(&body_result)?)],
+                body_result,
+                handler_result,
+            ))
+        }
+        ast::Expression::FunctionLiteral(func) => Ok(vec![CompileInstruction::Closure(
+            compile_function(func, symbols)?,
+        )]),
+        ast::Expression::Call {
+            function,
+            arguments,
+        } => {
+            let len = u16::try_from(arguments.len())?;
+
+            let mut result = match function {
+                ast::CallFunctionExpression::Identifier(name) => {
+                    compile_expr(ast::Expression::Identifier(name), symbols)?
+                }
+                ast::CallFunctionExpression::Literal(func) => {
+                    vec![CompileInstruction::Closure(compile_function(
+                        func, symbols,
+                    )?)]
+                }
+                // Methods of native objects aren't values, so the object is kept for the call.
+                ast::CallFunctionExpression::Expression(expr) => match *expr {
+                    ast::Expression::Member { object, name } => {
+                        let mut result = compile_expr(*object, symbols)?;
+                        for argument in arguments {
+                            result.extend(compile_expr(argument, symbols)?);
+                        }
+                        result.push(CompileInstruction::CallMethod(name, len));
+
+                        return Ok(result);
+                    }
+                    expr => compile_expr(expr, symbols)?,
+                },
+            };
+            for argument in arguments {
+                result.extend(compile_expr(argument, symbols)?);
+            }
+            result.push(CompileInstruction::Call(len));
+
+            Ok(result)
+        }
+        ast::Expression::Member { object, name } => Ok(concat(
+            compile_expr(*object, symbols)?,
+            vec![CompileInstruction::GetMember(name)],
+            Vec::new(),
+        )),
+    }
+}
+
+// Arms are tried in order, and each failing test jumps to the next arm. Arms with a literal pattern
+// and no guard, like `1 => a, 2 => b`, jump straight to the matching arm through a jump table
+// instead. The value is kept in a temporary, since every arm needs it.
+fn compile_match_expr(
+    value: ast::Expression,
+    arms: Vec<ast::MatchArm>,
+    symbols: &mut SymbolTable,
+) -> Result<CompileInstructions> {
+    let temporary = symbols.define_temporary()?;
+
+    // Compile in order so that bindings are defined before the arms using them, and add the
+    // jumps once the lengths of the later arms are known. Like in `eval`, the bindings of each arm
+    // are only in scope in it.
+    let mut compiled = Vec::new();
+    for arm in arms {
+        symbols.begin_block();
+        let arm = match (table_literal(&arm.pattern), arm.guard) {
+            (Some(literal), None) => {
+                CompiledArm::Literal(literal, compile_expr(arm.body, symbols)?)
+            }
+            (_, guard) => {
+                let (tests, binding) = compile_pattern(arm.pattern, temporary, &[], true, symbols)?;
+                let guard = match guard {
+                    Some(guard) => compile_expr(guard, symbols)?,
+                    None => Vec::new(),
+                };
+                let body = compile_expr(arm.body, symbols)?;
+
+                CompiledArm::Tested {
+                    tests,
+                    binding,
+                    guard,
+                    body,
+                }
+            }
+        };
+        symbols.end_block();
+
+        compiled.push(arm);
+    }
+
+    let mut result = vec![get_symbol(temporary), CompileInstruction::NoMatch];
+    // The literal arms after the arm being added, last first.
+    let mut literals = Vec::new();
+    for arm in compiled.into_iter().rev() {
+        match arm {
+            CompiledArm::Literal(literal, body) => literals.push((literal, body)),
+            CompiledArm::Tested {
+                tests,
+                binding,
+                guard,
+                body,
+            } => {
+                result = compile_literal_arms(mem::take(&mut literals), temporary, result)?;
+                result = compile_tested_arm(tests, binding, guard, body, result)?;
+            }
+        }
+    }
+    result = compile_literal_arms(literals, temporary, result)?;
+
+    Ok(concat(
+        compile_expr(value, symbols)?,
+        vec![set_symbol(temporary)],
+        result,
+    ))
+}
+
+enum CompiledArm {
+    Literal(Object, CompileInstructions),
+    Tested {
+        tests: Vec<CompileInstructions>,
+        binding: CompileInstructions,
+        guard: CompileInstructions,
+        body: CompileInstructions,
+    },
+}
+
+// The literal of a pattern which can be looked up in a jump table.
+fn table_literal(pattern: &ast::Pattern) -> Option<Object> {
+    match pattern {
+        ast::Pattern::Literal(literal) => {
+            literal_object(literal).filter(|literal| TableKey::new(literal).is_some())
+        }
+        _ => None,
+    }
+}
+
+fn literal_object(literal: &ast::Expression) -> Option<Object> {
+    match literal {
+        ast::Expression::IntegerLiteral(value) => Some(Object::from_unsigned(*value)),
+        ast::Expression::StringLiteral(value) => Some(Object::Str(value.clone())),
+        ast::Expression::Boolean(value) => Some(Object::from(*value)),
+        ast::Expression::Prefix {
+            operator: ast::Operator::Minus,
+            right,
+        } => literal_object(right)?
+            .apply_prefix_operator(ast::Operator::Minus)
+            .ok(),
+        _ => None,
+    }
+}
+
+// Adds the literal arms, given last first, before the rest of the match expression. A single arm
+// is tested like other arms, since a jump table wouldn't save anything.
+fn compile_literal_arms(
+    mut arms: Vec<(Object, CompileInstructions)>,
+    temporary: Symbol,
+    rest: CompileInstructions,
+) -> Result<CompileInstructions> {
+    if arms.len() < 2 {
+        return match arms.pop() {
+            Some((literal, body)) => compile_tested_arm(
+                vec![vec![
+                    get_symbol(temporary),
+                    CompileInstruction::Constant(literal.clone()),
+                    CompileInstruction::Equal,
+                ]],
+                Vec::new(),
+                Vec::new(),
+                body,
+                rest,
+            ),
+            None => Ok(rest),
+        };
+    }
+
+    let rest_len = ins_len(&rest)?;
+    let mut result = rest;
+    // The literals with the length from the start of their arm to the end.
+    let mut entries = Vec::new();
+    for (literal, body) in arms {
+        let body = concat(
+            body,
+            vec![CompileInstruction::Jump(ins_len(&result)?)],
+            Vec::new(),
+        );
+        result = concat(body, result, Vec::new());
+        entries.push((literal, ins_len(&result)?));
+    }
+
+    let len = ins_len(&result)?;
+    let entries = entries
+        .into_iter()
+        .rev()
+        .map(|(literal, remaining)| (literal, len - remaining))
+        .collect();
+
+    Ok(concat(
+        vec![
+            get_symbol(temporary),
+            CompileInstruction::JumpTable(entries, len - rest_len),
+        ],
+        result,
+        Vec::new(),
+    ))
+}
+
+// Adds the arm before the rest of the match expression. Each test jumps to the rest when it fails.
+fn compile_tested_arm(
+    tests: Vec<CompileInstructions>,
+    binding: CompileInstructions,
+    guard: CompileInstructions,
+    body: CompileInstructions,
+    rest: CompileInstructions,
+) -> Result<CompileInstructions> {
+    let body = concat(
+        body,
+        vec![CompileInstruction::Jump(ins_len(&rest)?)],
+        Vec::new(),
+    );
+    let guard = if guard.is_empty() {
+        guard
+    } else {
+        concat(
+            guard,
+            vec![CompileInstruction::JumpNotTruthy(ins_len(&body)?)],
+            Vec::new(),
+        )
+    };
+
+    let mut arm = concat(binding, guard, body);
+    for test in tests.into_iter().rev() {
+        arm = concat(
+            test,
+            vec![CompileInstruction::JumpNotTruthy(ins_len(&arm)?)],
+            arm,
+        );
+    }
+
+    Ok(concat(arm, rest, Vec::new()))
+}
+
+// Returns the instructions testing whether the part of the value in the temporary at the path of
+// indices matches the pattern, and the ones binding it. In match arms, each test leaves whether it
+// passed on the stack. Elsewhere, a value which doesn't match is an error, so the tests fail instead.
+fn compile_pattern(
+    pattern: ast::Pattern,
+    temporary: Symbol,
+    path: &[usize],
+    in_match: bool,
+    symbols: &mut SymbolTable,
+) -> Result<(Vec<CompileInstructions>, CompileInstructions)> {
+    match &pattern {
+        ast::Pattern::Wildcard => Ok((Vec::new(), Vec::new())),
+        ast::Pattern::Identifier(name) => Ok((
+            Vec::new(),
+            concat(
+                get_part(temporary, path),
+                vec![set_symbol(symbols.define(name.to_string())?)],
+                Vec::new(),
+            ),
+        )),
+        ast::Pattern::Literal(literal) if in_match => Ok((
+            vec![concat(
+                get_part(temporary, path),
+                compile_expr(literal.clone(), symbols)?,
+                vec![CompileInstruction::Equal],
+            )],
+            Vec::new(),
+        )),
+        ast::Pattern::Literal(_) => Err(Error::UnsupportedPattern(pattern)),
+        ast::Pattern::List { elements, rest } => {
+            let test = if in_match {
+                CompileInstruction::MatchList(pattern.clone())
+            } else {
+                CompileInstruction::CheckList(pattern.clone())
+            };
+            let mut tests = vec![concat(get_part(temporary, path), vec![test], Vec::new())];
+            let mut binding = Vec::new();
+
+            for (index, element) in elements.iter().enumerate() {
+                let path = [path, &[index]].concat();
+                let (element_tests, element_binding) =
+                    compile_pattern(element.clone(), temporary, &path, in_match, symbols)?;
+                tests.extend(element_tests);
+                binding.extend(element_binding);
+            }
+
+            if let Some(rest) = rest {
+                binding.extend(concat(
+                    get_part(temporary, path),
+                    vec![
+                        CompileInstruction::SliceFrom(u16::try_from(elements.len())?),
+                        set_symbol(symbols.define(rest.to_string())?),
+                    ],
+                    Vec::new(),
+                ));
+            }
+
+            Ok((tests, binding))
+        }
     }
 }
 
-fn compile_if_block(statements: ast::Statements) -> Result<CompileInstructions> {
-    let mut ins = statements
+// The instructions getting the part of the value in the temporary at the path of indices.
+fn get_part(temporary: Symbol, path: &[usize]) -> CompileInstructions {
+    let mut ins = vec![get_symbol(temporary)];
+    for &index in path {
+        ins.push(CompileInstruction::Constant(Object::Integer(
+            index as isize,
+        )));
+        ins.push(CompileInstruction::Index);
+    }
+
+    ins
+}
+
+// Like `eval`, `&&` and `||` produce a boolean and skip the right side with jumps when the left
+// side decides the result.
+fn compile_logical_expr(
+    operator: ast::Operator,
+    left: ast::Expression,
+    right: ast::Expression,
+    symbols: &mut SymbolTable,
+) -> Result<CompileInstructions> {
+    // Converts the right side to a boolean, which is the result when it is evaluated.
+    let right_result = compile_expr(right, symbols)?
         .into_iter()
-        .collect::<Result<CompileInstructions>>()?;
+        .chain(vec![
+            CompileInstruction::JumpNotTruthy(4),
+            CompileInstruction::True,
+            CompileInstruction::Jump(1),
+            CompileInstruction::False,
+        ])
+        .collect::<CompileInstructions>();
+    let right_len = ins_len(&right_result)?;
 
-    // Keep the last statement on the stack because if blocks are assignable expressions.
-    match ins.pop() {
-        Some(CompileInstruction::Pop) => (),
-        _ => panic!("Expected last compile instruction of a condition to be OpPop"),
+    let short_circuit = if let ast::Operator::And = operator {
+        // Jump to the trailing `False` of the right side.
+        vec![CompileInstruction::JumpNotTruthy(right_len - 1)]
+    } else {
+        vec![
+            CompileInstruction::JumpNotTruthy(4),
+            CompileInstruction::True,
+            CompileInstruction::Jump(right_len),
+        ]
+    };
+
+    let result = compile_expr(left, symbols)?
+        .into_iter()
+        .chain(short_circuit)
+        .chain(right_result)
+        .collect();
+
+    Ok(result)
+}
+
+// Like `eval`, the indices in the target are evaluated before the value, and the assigned value
+// is left on the stack.
+fn compile_assign_expr(
+    target: ast::Expression,
+    operator: Option<ast::Operator>,
+    value: ast::Expression,
+    symbols: &mut SymbolTable,
+) -> Result<CompileInstructions> {
+    let (name, indices) = split_assign_target(target)?;
+    let symbol = symbols
+        .resolve(&name)
+        .ok_or(Error::UndefinedVariable(name))?;
+
+    let value = compile_expr(value, symbols)?;
+
+    if indices.is_empty() {
+        let value = match operator {
+            Some(operator) => concat(vec![get_symbol(symbol)], value, vec![operator.into()]),
+            None => value,
+        };
+
+        return Ok(concat(
+            value,
+            vec![set_symbol(symbol)],
+            vec![get_symbol(symbol)],
+        ));
+    }
+
+    let depth = u16::try_from(indices.len())?;
+
+    let mut result = vec![get_symbol(symbol)];
+    for index in indices {
+        result.extend(compile_expr(index, symbols)?);
+    }
+
+    // `SetIndex` leaves the value under the updated list, which is stored back in the binding.
+    let value = match operator {
+        Some(operator) => concat(
+            vec![CompileInstruction::PeekIndex(depth)],
+            value,
+            vec![operator.into()],
+        ),
+        None => value,
+    };
+
+    Ok(concat(
+        result,
+        value,
+        vec![CompileInstruction::SetIndex(depth), set_symbol(symbol)],
+    ))
+}
+
+// Splits a target like `xs[0][i]` into the name of the binding and the index expressions.
+fn split_assign_target(target: ast::Expression) -> Result<(String, Vec<ast::Expression>)> {
+    match target {
+        ast::Expression::Identifier(name) => Ok((name, Vec::new())),
+        ast::Expression::Index { left, index } => {
+            let (name, mut indices) = split_assign_target(*left)?;
+            indices.push(*index);
+            Ok((name, indices))
+        }
+        _ => Err(Error::InvalidAssignmentTarget),
+    }
+}
+
+fn get_symbol(symbol: Symbol) -> CompileInstruction {
+    match symbol {
+        Symbol::Global(index) => CompileInstruction::GetGlobal(index),
+        Symbol::Local(index) => CompileInstruction::GetLocal(index),
+        Symbol::Outer { depth, index } => CompileInstruction::GetOuter(depth, index),
+    }
+}
+
+fn set_symbol(symbol: Symbol) -> CompileInstruction {
+    match symbol {
+        Symbol::Global(index) => CompileInstruction::SetGlobal(index),
+        Symbol::Local(index) => CompileInstruction::SetLocal(index),
+        Symbol::Outer { depth, index } => CompileInstruction::SetOuter(depth, index),
+    }
+}
+
+fn concat(
+    first: CompileInstructions,
+    second: CompileInstructions,
+    third: CompileInstructions,
+) -> CompileInstructions {
+    first.into_iter().chain(second).chain(third).collect()
+}
+
+fn compile_if_block(
+    statements: ast::Statements,
+    symbols: &mut SymbolTable,
+) -> Result<CompileInstructions> {
+    let mut ins = compile_statements(statements, symbols)?;
+
+    // Keep the last statement on the stack because if blocks are assignable expressions. Blocks
+    // ending with a `let` evaluate to null.
+    match ins.last() {
+        Some(CompileInstruction::Pop) => {
+            ins.pop();
+        }
+        _ => ins.push(CompileInstruction::Null),
     };
 
     Ok(ins)
 }
 
-fn ins_len(ins: &CompileInstructions) -> u16 {
-    ins.iter()
-        .map(|ins| {
-            let ins: bytecode::Instruction = ins.into();
-            ins.size()
+// Jumps and offsets are 16 bits, so longer code can't be run.
+fn ins_len(ins: &CompileInstructions) -> Result<u16> {
+    let len: usize = ins
+        .iter()
+        .map(|ins| match ins {
+            CompileInstruction::Try(_) => 0,
+            ins => usize::from(bytecode::Instruction::from(ins).size()),
         })
-        .sum()
+        .sum();
+    u16::try_from(len).map_err(|_| Error::ProgramTooLarge)
 }
 
 impl From<ast::Operator> for CompileInstruction {
@@ -197,14 +895,17 @@ impl From<ast::Operator> for CompileInstruction {
             ast::Operator::Minus => CompileInstruction::Sub,
             ast::Operator::Multiply => CompileInstruction::Mul,
             ast::Operator::Divide => CompileInstruction::Div,
+            ast::Operator::Modulo => CompileInstruction::Mod,
             ast::Operator::GreaterThan => CompileInstruction::GreaterThan,
-            // Note that this is the same as GreaterThan. We reverse the
-            // left and right expressions when we encounter this when
-            // compiling.
-            ast::Operator::LessThan => CompileInstruction::GreaterThan,
+            ast::Operator::LessThan => CompileInstruction::LessThan,
+            ast::Operator::GreaterThanEqual => CompileInstruction::GreaterThanEqual,
+            ast::Operator::LessThanEqual => CompileInstruction::LessThanEqual,
             ast::Operator::Equal => CompileInstruction::Equal,
             ast::Operator::NotEqual => CompileInstruction::NotEqual,
             ast::Operator::Not => CompileInstruction::Not,
+            ast::Operator::And | ast::Operator::Or => {
+                panic!("Logical operators are compiled with jumps instead")
+            }
         }
     }
 }
@@ -221,19 +922,48 @@ impl From<&CompileInstruction> for bytecode::Instruction {
             Constant(_) => OpConstant(DUMMY_OPERAND),
             Jump(_) => OpJump(DUMMY_OPERAND),
             JumpNotTruthy(_) => OpJumpNotTruthy(DUMMY_OPERAND),
+            Try(_) => panic!("Try blocks are added to the handler table instead"),
+            GetName(_) => OpGetName(DUMMY_OPERAND),
+            GetMember(_) => OpGetMember(DUMMY_OPERAND),
+            CallMethod(_, len) => OpCallMethod(DUMMY_OPERAND, *len),
+            Closure(_) => OpClosure(DUMMY_OPERAND),
+            JumpArgGiven(index, _) => OpJumpArgGiven(*index, DUMMY_OPERAND),
             Pop => OpPop,
             Add => OpAdd,
             Sub => OpSub,
             Mul => OpMul,
             Div => OpDiv,
+            Mod => OpMod,
             True => OpTrue,
             False => OpFalse,
             GreaterThan => OpGreaterThan,
+            LessThan => OpLessThan,
+            GreaterThanEqual => OpGreaterThanEqual,
+            LessThanEqual => OpLessThanEqual,
             Equal => OpEqual,
             NotEqual => OpNotEqual,
             Neg => OpNeg,
             Not => OpNot,
             Null => OpNull,
+            GetGlobal(index) => OpGetGlobal(*index),
+            SetGlobal(index) => OpSetGlobal(*index),
+            GetLocal(index) => OpGetLocal(*index),
+            SetLocal(index) => OpSetLocal(*index),
+            GetOuter(depth, index) => OpGetOuter(*depth, *index),
+            SetOuter(depth, index) => OpSetOuter(*depth, *index),
+            Call(len) => OpCall(*len),
+            ReturnValue => OpReturnValue,
+            Array(len) => OpArray(*len),
+            Index => OpIndex,
+            PeekIndex(depth) => OpPeekIndex(*depth),
+            SetIndex(depth) => OpSetIndex(*depth),
+            NoMatch => OpNoMatch,
+            JumpTable(..) => OpJumpTable(DUMMY_OPERAND),
+            MatchList(_) => OpMatchList(DUMMY_OPERAND),
+            CheckList(_) => OpCheckList(DUMMY_OPERAND),
+            SliceFrom(len) => OpSliceFrom(*len),
+            Interpolate(len) => OpInterpolate(*len),
+            Throw => OpThrow,
         }
     }
 }
@@ -242,50 +972,250 @@ impl From<&CompileInstruction> for bytecode::Instruction {
 pub struct Output {
     pub instructions: Vec<bytecode::Instruction>,
     pub constants: Vec<Object>,
+    // The functions created by `OpClosure`.
+    pub(crate) functions: Vec<Rc<Function>>,
+    // The offset of each instruction in bytes, to find the instruction a jump points to.
+    pub(crate) offsets: Vec<u16>,
+    // The name of each global, or of each local in a function.
+    pub(crate) names: Vec<String>,
+    // The globals still in scope at the end of the program, by name, for `OpGetName`.
+    pub(crate) scope: HashMap<String, u16>,
+    // The try blocks, where an inner block comes after the blocks around it.
+    pub(crate) handlers: Vec<Handler>,
+    // The jump tables of `OpJumpTable`.
+    pub(crate) tables: Vec<JumpTable>,
+    // The list patterns of `OpMatchList` and `OpCheckList`.
+    pub(crate) patterns: Vec<ast::Pattern>,
     // Index to place the next instruction, in number of bytes.
     index: u16,
 }
 
+// Errors in the instructions from offset `start` to `end` continue at `end`, with the error on the
+// stack.
+#[derive(Debug)]
+pub(crate) struct Handler {
+    pub start: u16,
+    pub end: u16,
+    // The number of objects on the stack when the block starts, which are kept.
+    pub depth: usize,
+}
+
+// The arms of a match expression with literal patterns, by the offset they start at.
+#[derive(Debug)]
+pub(crate) struct JumpTable {
+    // In the order of the arms, for values which aren't a `TableKey`.
+    pub arms: Vec<(Object, u16)>,
+    // The first arm for each literal.
+    pub targets: HashMap<TableKey, u16>,
+    // Where to continue when no arm matches.
+    pub default: u16,
+}
+
+// The values which are only equal to literals of the same type, so that they can be looked up.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub(crate) enum TableKey {
+    Integer(isize),
+    Str(String),
+    Boolean(bool),
+}
+
+impl TableKey {
+    pub fn new(object: &Object) -> Option<Self> {
+        match object {
+            Object::Integer(value) => Some(TableKey::Integer(*value)),
+            Object::Str(value) => Some(TableKey::Str(value.clone())),
+            Object::Boolean(value) => Some(TableKey::Boolean(*value)),
+            _ => None,
+        }
+    }
+}
+
+fn address(start: u16, lengths: &[u16]) -> Result<u16> {
+    lengths.iter().try_fold(start, |address, &len| {
+        address.checked_add(len).ok_or(Error::ProgramTooLarge)
+    })
+}
+
+// The operand for the index of a constant, function, table or pattern.
+fn operand(index: usize) -> Result<u16> {
+    u16::try_from(index).map_err(|_| Error::ProgramTooLarge)
+}
+
+fn assemble(ins: CompileInstructions) -> Result<Output> {
+    let mut output = ins
+        .into_iter()
+        .try_fold(Output::new(), Output::add_instruction)?;
+    output.set_handler_depths();
+    Ok(output)
+}
+
 impl Output {
     fn new() -> Self {
         Self {
             instructions: Vec::new(),
             constants: Vec::new(),
+            functions: Vec::new(),
+            offsets: Vec::new(),
+            names: Vec::new(),
+            scope: HashMap::new(),
+            handlers: Vec::new(),
+            tables: Vec::new(),
+            patterns: Vec::new(),
             index: 0,
         }
     }
 
-    fn add_instruction(mut self, ins: CompileInstruction) -> Self {
+    fn add_instruction(mut self, ins: CompileInstruction) -> Result<Self> {
         // TODO: This is extremelty verbose, clean it up.
         let bytecode_ins = match ins {
             CompileInstruction::Constant(object) => {
                 let i = self.constants.len();
                 self.constants.push(object);
 
-                bytecode::Instruction::OpConstant(i as u16)
+                bytecode::Instruction::OpConstant(operand(i)?)
             }
             CompileInstruction::Jump(location) => {
                 let current_size = bytecode::Instruction::from(&ins).size();
 
-                let jump_address = self.index + current_size + location;
+                let jump_address = self.address(&[current_size, location])?;
                 bytecode::Instruction::OpJump(jump_address)
             }
             CompileInstruction::JumpNotTruthy(location) => {
                 let current_size = bytecode::Instruction::from(&ins).size();
 
-                let jump_address = self.index + current_size + location;
+                let jump_address = self.address(&[current_size, location])?;
                 bytecode::Instruction::OpJumpNotTruthy(jump_address)
             }
+            CompileInstruction::Try(len) => {
+                self.handlers.push(Handler {
+                    start: self.index,
+                    end: self.address(&[len])?,
+                    depth: 0,
+                });
+                return Ok(self);
+            }
+            CompileInstruction::JumpTable(arms, default) => {
+                let end =
+                    self.address(&[bytecode::Instruction::OpJumpTable(DUMMY_OPERAND).size()])?;
+
+                let arms = arms
+                    .into_iter()
+                    .map(|(literal, location)| Ok((literal, address(end, &[location])?)))
+                    .collect::<Result<Vec<_>>>()?;
+                let mut targets = HashMap::new();
+                for (literal, address) in &arms {
+                    if let Some(key) = TableKey::new(literal) {
+                        targets.entry(key).or_insert(*address);
+                    }
+                }
+
+                let i = self.tables.len();
+                self.tables.push(JumpTable {
+                    arms,
+                    targets,
+                    default: address(end, &[default])?,
+                });
+
+                bytecode::Instruction::OpJumpTable(operand(i)?)
+            }
+            CompileInstruction::MatchList(pattern) => {
+                let i = self.patterns.len();
+                self.patterns.push(pattern);
+
+                bytecode::Instruction::OpMatchList(operand(i)?)
+            }
+            CompileInstruction::CheckList(pattern) => {
+                let i = self.patterns.len();
+                self.patterns.push(pattern);
+
+                bytecode::Instruction::OpCheckList(operand(i)?)
+            }
+            CompileInstruction::JumpArgGiven(index, location) => {
+                let current_size = bytecode::Instruction::from(&ins).size();
+
+                let jump_address = self.address(&[current_size, location])?;
+                bytecode::Instruction::OpJumpArgGiven(index, jump_address)
+            }
+            CompileInstruction::GetName(name) => {
+                let i = self.constants.len();
+                self.constants.push(Object::Str(name));
+
+                bytecode::Instruction::OpGetName(operand(i)?)
+            }
+            CompileInstruction::CallMethod(name, len) => {
+                let i = self.constants.len();
+                self.constants.push(Object::Str(name));
+
+                bytecode::Instruction::OpCallMethod(operand(i)?, len)
+            }
+            CompileInstruction::GetMember(name) => {
+                let i = self.constants.len();
+                self.constants.push(Object::Str(name));
+
+                bytecode::Instruction::OpGetMember(operand(i)?)
+            }
+            CompileInstruction::Closure(func) => {
+                let i = self.functions.len();
+                self.functions.push(Rc::new(func));
+
+                bytecode::Instruction::OpClosure(operand(i)?)
+            }
             // Zero operand instructions.
             ins => (&ins).into(),
         };
 
         let ins_size = bytecode_ins.size();
         self.instructions.push(bytecode_ins);
-        Self {
-            instructions: self.instructions,
-            constants: self.constants,
-            index: self.index + ins_size,
+        self.offsets.push(self.index);
+        Ok(Self {
+            index: self.address(&[ins_size])?,
+            ..self
+        })
+    }
+
+    // The offset the given lengths after the current one.
+    fn address(&self, lengths: &[u16]) -> Result<u16> {
+        address(self.index, lengths)
+    }
+
+    // Finds the depth of the stack at the start of each try block. Jumps only go forward, so the
+    // depth at the target of a jump is known before reaching it.
+    fn set_handler_depths(&mut self) {
+        use bytecode::Instruction::*;
+
+        let mut targets = HashMap::new();
+        let mut depth: isize = 0;
+        for (instruction, offset) in self.instructions.iter().zip(&self.offsets) {
+            if let Some(&target_depth) = targets.get(offset) {
+                depth = target_depth;
+            }
+            for handler in self.handlers.iter_mut().filter(|h| h.start == *offset) {
+                handler.depth = usize::try_from(depth).unwrap_or(0);
+                // The handler starts with the error on the stack.
+                targets.insert(handler.end, depth + 1);
+            }
+
+            depth += instruction.stack_effect();
+            match *instruction {
+                OpJumpNotTruthy(target) | OpJumpArgGiven(_, target) => {
+                    targets.insert(target, depth);
+                }
+                // The next instruction is only reached by jumping to it.
+                OpJump(target) => {
+                    targets.insert(target, depth);
+                    depth = 0;
+                }
+                OpJumpTable(i) => {
+                    let table = &self.tables[i as usize];
+                    for &(_, target) in &table.arms {
+                        targets.insert(target, depth);
+                    }
+                    targets.insert(table.default, depth);
+                    depth = 0;
+                }
+                OpNoMatch | OpThrow | OpReturnValue => depth = 0,
+                _ => (),
+            }
         }
     }
 }
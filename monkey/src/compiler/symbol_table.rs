@@ -0,0 +1,127 @@
+use super::Error;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+// Where the VM keeps a binding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symbol {
+    Global(u16),
+    // A local of the function being compiled.
+    Local(u16),
+    // A local of the function `depth` functions out from the one being compiled.
+    Outer { depth: u16, index: u16 },
+}
+
+// Maps the names of bindings to the index of their slot in the VM. The table of the top level
+// has the globals, and each function being compiled has its own table for its locals, enclosing
+// the table of the code around it.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    store: HashMap<String, u16>,
+    // The name of each slot.
+    names: Vec<String>,
+    // For each block being compiled, innermost last, the slots shadowed by its bindings.
+    blocks: Vec<HashMap<String, Option<u16>>>,
+    outer: Option<Box<SymbolTable>>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn new_enclosed(outer: SymbolTable) -> Self {
+        Self {
+            outer: Some(Box::new(outer)),
+            ..Self::default()
+        }
+    }
+
+    pub fn into_outer(self) -> Option<SymbolTable> {
+        self.outer.map(|outer| *outer)
+    }
+
+    // Redefining a name reuses its slot, unless the slot is from outside of the current block.
+    pub fn define(&mut self, name: String) -> Result<Symbol, Error> {
+        let shadows = match self.blocks.last_mut() {
+            Some(block) if !block.contains_key(&name) => {
+                block.insert(name.clone(), self.store.get(&name).copied());
+                true
+            }
+            _ => false,
+        };
+
+        let index = match self.store.get(&name) {
+            Some(index) if !shadows => *index,
+            _ => self.new_slot(name.clone())?,
+        };
+        self.store.insert(name, index);
+        Ok(self.symbol(index))
+    }
+
+    // Binds the name to a slot which was already defined, like the argument of a parameter.
+    pub fn define_at(&mut self, name: String, index: u16) {
+        if let Some(slot) = self.names.get_mut(index as usize) {
+            slot.clone_from(&name);
+        }
+        self.store.insert(name, index);
+    }
+
+    // Slots for values the compiler needs to keep around, like the value of a match expression.
+    // They don't have a binding, so they can't clash with identifiers.
+    pub fn define_temporary(&mut self) -> Result<Symbol, Error> {
+        let index = self.new_slot(format!("#{}", self.names.len()))?;
+        Ok(self.symbol(index))
+    }
+
+    // Bindings defined until `end_block` get their own slots, and go out of scope at its end.
+    pub fn begin_block(&mut self) {
+        self.blocks.push(HashMap::new());
+    }
+
+    pub fn end_block(&mut self) {
+        for (name, shadowed) in self.blocks.pop().unwrap_or_default() {
+            match shadowed {
+                Some(index) => self.store.insert(name, index),
+                None => self.store.remove(&name),
+            };
+        }
+    }
+
+    pub fn resolve(&self, name: &str) -> Option<Symbol> {
+        match (self.store.get(name), &self.outer) {
+            (Some(index), _) => Some(self.symbol(*index)),
+            (None, Some(outer)) => outer.resolve(name).map(|symbol| match symbol {
+                Symbol::Local(index) => Symbol::Outer { depth: 1, index },
+                Symbol::Outer { depth, index } => Symbol::Outer {
+                    depth: depth + 1,
+                    index,
+                },
+                global => global,
+            }),
+            (None, None) => None,
+        }
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.names.clone()
+    }
+
+    // The slots of the bindings currently in scope.
+    pub fn scope(&self) -> HashMap<String, u16> {
+        self.store.clone()
+    }
+
+    fn new_slot(&mut self, name: String) -> Result<u16, Error> {
+        let index = u16::try_from(self.names.len())?;
+        self.names.push(name);
+        Ok(index)
+    }
+
+    fn symbol(&self, index: u16) -> Symbol {
+        match self.outer {
+            Some(_) => Symbol::Local(index),
+            None => Symbol::Global(index),
+        }
+    }
+}
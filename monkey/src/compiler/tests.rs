@@ -4,6 +4,7 @@ use crate::bytecode::Instruction::*;
 use crate::lexer::Lexer;
 use crate::object::Object::*;
 use crate::parser;
+use error::Error;
 
 #[test]
 fn test_integer_arithmetic() {
@@ -28,6 +29,16 @@ fn test_integer_arithmetic() {
             vec![Integer(2), Integer(1)],
             vec![OpConstant(0), OpConstant(1), OpDiv, OpPop],
         ),
+        (
+            "18446744073709551616",
+            vec![BigInt("18446744073709551616".parse().unwrap())],
+            vec![OpConstant(0), OpPop],
+        ),
+        (
+            "1.5 + 2",
+            vec![Float(1.5), Integer(2)],
+            vec![OpConstant(0), OpConstant(1), OpAdd, OpPop],
+        ),
         ("true", vec![], vec![OpTrue, OpPop]),
         ("false", vec![], vec![OpFalse, OpPop]),
         (
@@ -37,8 +48,71 @@ fn test_integer_arithmetic() {
         ),
         (
             "1 < 2",
-            vec![Integer(2), Integer(1)],
-            vec![OpConstant(0), OpConstant(1), OpGreaterThan, OpPop],
+            vec![Integer(1), Integer(2)],
+            vec![OpConstant(0), OpConstant(1), OpLessThan, OpPop],
+        ),
+        (
+            "1 <= 2",
+            vec![Integer(1), Integer(2)],
+            vec![OpConstant(0), OpConstant(1), OpLessThanEqual, OpPop],
+        ),
+        (
+            "1 >= 2",
+            vec![Integer(1), Integer(2)],
+            vec![OpConstant(0), OpConstant(1), OpGreaterThanEqual, OpPop],
+        ),
+        (
+            "1 % 2",
+            vec![Integer(1), Integer(2)],
+            vec![OpConstant(0), OpConstant(1), OpMod, OpPop],
+        ),
+        (
+            "true && false",
+            vec![],
+            vec![
+                // 0000
+                OpTrue,
+                // 0001
+                OpJumpNotTruthy(12),
+                // 0004
+                OpFalse,
+                // 0005
+                OpJumpNotTruthy(12),
+                // 0008
+                OpTrue,
+                // 0009
+                OpJump(13),
+                // 0012
+                OpFalse,
+                // 0013
+                OpPop,
+            ],
+        ),
+        (
+            "true || false",
+            vec![],
+            vec![
+                // 0000
+                OpTrue,
+                // 0001
+                OpJumpNotTruthy(8),
+                // 0004
+                OpTrue,
+                // 0005
+                OpJump(17),
+                // 0008
+                OpFalse,
+                // 0009
+                OpJumpNotTruthy(16),
+                // 0012
+                OpTrue,
+                // 0013
+                OpJump(17),
+                // 0016
+                OpFalse,
+                // 0017
+                OpPop,
+            ],
         ),
         (
             "1 == 2",
@@ -119,6 +193,481 @@ fn test_integer_arithmetic() {
     }
 }
 
+#[test]
+fn test_globals_and_assignment() {
+    let tests = vec![
+        (
+            "let a = 1; a;",
+            vec![Integer(1)],
+            vec![OpConstant(0), OpSetGlobal(0), OpGetGlobal(0), OpPop],
+        ),
+        (
+            "let a = 1; let b = a; a = 2;",
+            vec![Integer(1), Integer(2)],
+            vec![
+                OpConstant(0),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpSetGlobal(1),
+                OpConstant(1),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpPop,
+            ],
+        ),
+        (
+            "let a = 1; a += 2;",
+            vec![Integer(1), Integer(2)],
+            vec![
+                OpConstant(0),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpConstant(1),
+                OpAdd,
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpPop,
+            ],
+        ),
+        (
+            "let xs = [1, \"a\"]; xs[0];",
+            vec![Integer(1), Str("a".to_string()), Integer(0)],
+            vec![
+                OpConstant(0),
+                OpConstant(1),
+                OpArray(2),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpConstant(2),
+                OpIndex,
+                OpPop,
+            ],
+        ),
+        (
+            "let xs = [[1]]; xs[0][0] *= 2;",
+            vec![Integer(1), Integer(0), Integer(0), Integer(2)],
+            vec![
+                OpConstant(0),
+                OpArray(1),
+                OpArray(1),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpConstant(1),
+                OpConstant(2),
+                OpPeekIndex(2),
+                OpConstant(3),
+                OpMul,
+                OpSetIndex(2),
+                OpSetGlobal(0),
+                OpPop,
+            ],
+        ),
+    ];
+
+    for (input, expected_constants, expected_instructions) in tests {
+        let program = parse(input).unwrap();
+
+        let bytecode = compile(program).unwrap();
+
+        assert_eq!(expected_instructions, bytecode.instructions);
+
+        test_constants(expected_constants, bytecode.constants);
+    }
+}
+
+#[test]
+fn test_interpolation() {
+    let program = parse(r#""a${1 + 2}b""#).unwrap();
+
+    let bytecode = compile(program).unwrap();
+
+    assert_eq!(
+        vec![
+            OpConstant(0),
+            OpConstant(1),
+            OpConstant(2),
+            OpAdd,
+            OpConstant(3),
+            OpInterpolate(3),
+            OpPop,
+        ],
+        bytecode.instructions
+    );
+    test_constants(
+        vec![
+            Str("a".to_string()),
+            Integer(1),
+            Integer(2),
+            Str("b".to_string()),
+        ],
+        bytecode.constants,
+    );
+}
+
+#[test]
+fn test_match_expr() {
+    let tests = vec![(
+        "match (1) { 2 => 3, n if n => n, _ => 4 }",
+        vec![Integer(1), Integer(2), Integer(3), Integer(4)],
+        vec![
+            OpConstant(0),
+            OpSetGlobal(0),
+            // 2 => 3
+            OpGetGlobal(0),
+            OpConstant(1),
+            OpEqual,
+            OpJumpNotTruthy(22),
+            OpConstant(2),
+            OpJump(50),
+            // n if n => n
+            OpGetGlobal(0),
+            OpSetGlobal(1),
+            OpGetGlobal(1),
+            OpJumpNotTruthy(40),
+            OpGetGlobal(1),
+            OpJump(50),
+            // _ => 4
+            OpConstant(3),
+            OpJump(50),
+            OpGetGlobal(0),
+            OpNoMatch,
+            OpPop,
+        ],
+    )];
+
+    for (input, expected_constants, expected_instructions) in tests {
+        let program = parse(input).unwrap();
+
+        let bytecode = compile(program).unwrap();
+
+        assert_eq!(expected_instructions, bytecode.instructions);
+
+        test_constants(expected_constants, bytecode.constants);
+    }
+
+    let program = parse("match ([1]) { [x] => x }").unwrap();
+
+    let bytecode = compile(program).unwrap();
+
+    assert_eq!(
+        vec![
+            OpConstant(0),
+            OpArray(1),
+            OpSetGlobal(0),
+            OpGetGlobal(0),
+            OpMatchList(0),
+            OpJumpNotTruthy(34),
+            // x = value[0]
+            OpGetGlobal(0),
+            OpConstant(1),
+            OpIndex,
+            OpSetGlobal(1),
+            OpGetGlobal(1),
+            OpJump(38),
+            OpGetGlobal(0),
+            OpNoMatch,
+            OpPop,
+        ],
+        bytecode.instructions
+    );
+    test_constants(vec![Integer(1), Integer(0)], bytecode.constants);
+}
+
+#[test]
+fn test_match_jump_table() {
+    let program = parse(r#"match (1) { 1 => 2, 2 => 3, 1 => 4, _ => 5 }"#).unwrap();
+
+    let bytecode = compile(program).unwrap();
+
+    assert_eq!(
+        vec![
+            OpConstant(0),
+            OpSetGlobal(0),
+            OpGetGlobal(0),
+            OpJumpTable(0),
+            OpConstant(1),
+            OpJump(40),
+            OpConstant(2),
+            OpJump(40),
+            OpConstant(3),
+            OpJump(40),
+            // _ => 5
+            OpConstant(4),
+            OpJump(40),
+            OpGetGlobal(0),
+            OpNoMatch,
+            OpPop,
+        ],
+        bytecode.instructions
+    );
+    test_constants(
+        vec![Integer(1), Integer(2), Integer(3), Integer(4), Integer(5)],
+        bytecode.constants,
+    );
+
+    let table = &bytecode.tables[0];
+    assert_eq!(
+        vec![(Integer(1), 12), (Integer(2), 18), (Integer(1), 24)],
+        table.arms
+    );
+    // The first arm for a literal wins.
+    assert_eq!(Some(&12), table.targets.get(&TableKey::Integer(1)));
+    assert_eq!(30, table.default);
+}
+
+#[test]
+fn test_try_expr() {
+    let tests = vec![
+        (
+            "try { 1 } catch (e) { e }",
+            vec![Integer(1)],
+            vec![
+                OpConstant(0),
+                OpJump(12),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpPop,
+            ],
+            vec![(0, 6, 0)],
+        ),
+        // The list being built stays on the stack when the block fails.
+        (
+            "[1, try { try { 2 } catch (e) { e } } catch (e) { 3 }]",
+            vec![Integer(1), Integer(2), Integer(3)],
+            vec![
+                OpConstant(0),
+                OpConstant(1),
+                OpJump(15),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpJump(24),
+                OpSetGlobal(1),
+                OpConstant(2),
+                OpArray(2),
+                OpPop,
+            ],
+            vec![(3, 18, 1), (3, 9, 1)],
+        ),
+        // The catch binding doesn't replace a global of the same name.
+        (
+            "let e = 1; try { let x = 2 } catch (e) { e }; e",
+            vec![Integer(1), Integer(2)],
+            vec![
+                OpConstant(0),
+                OpSetGlobal(0),
+                OpConstant(1),
+                OpSetGlobal(1),
+                OpNull,
+                OpJump(22),
+                OpSetGlobal(2),
+                OpGetGlobal(2),
+                OpPop,
+                OpGetGlobal(0),
+                OpPop,
+            ],
+            vec![(6, 16, 0)],
+        ),
+        (
+            "throw 1",
+            vec![Integer(1)],
+            vec![OpConstant(0), OpThrow],
+            vec![],
+        ),
+    ];
+
+    for (input, expected_constants, expected_instructions, expected_handlers) in tests {
+        let program = parse(input).unwrap();
+
+        let bytecode = compile(program).unwrap();
+
+        assert_eq!(expected_instructions, bytecode.instructions);
+
+        let handlers: Vec<_> = bytecode
+            .handlers
+            .iter()
+            .map(|handler| (handler.start, handler.end, handler.depth))
+            .collect();
+        assert_eq!(expected_handlers, handlers);
+
+        test_constants(expected_constants, bytecode.constants);
+    }
+}
+
+#[test]
+fn test_member() {
+    let program = parse(r#"Error("a").message"#).unwrap();
+
+    let bytecode = compile(program).unwrap();
+
+    assert_eq!(
+        vec![
+            OpGetName(0),
+            OpConstant(1),
+            OpCall(1),
+            OpGetMember(2),
+            OpPop
+        ],
+        bytecode.instructions
+    );
+    test_constants(
+        vec![
+            Str("Error".to_string()),
+            Str("a".to_string()),
+            Str("message".to_string()),
+        ],
+        bytecode.constants,
+    );
+
+    // Method calls keep the object under the arguments.
+    let program = parse("let c = 1; c.add(2)").unwrap();
+
+    let bytecode = compile(program).unwrap();
+
+    assert_eq!(
+        vec![
+            OpConstant(0),
+            OpSetGlobal(0),
+            OpGetGlobal(0),
+            OpConstant(1),
+            OpCallMethod(2, 1),
+            OpPop
+        ],
+        bytecode.instructions
+    );
+    test_constants(
+        vec![Integer(1), Integer(2), Str("add".to_string())],
+        bytecode.constants,
+    );
+}
+
+#[test]
+fn test_functions() {
+    let tests = vec![
+        (
+            "let f = fn(x) { x }; f(1)",
+            vec![
+                OpClosure(0),
+                OpSetGlobal(0),
+                OpGetGlobal(0),
+                OpConstant(0),
+                OpCall(1),
+                OpPop,
+            ],
+            vec![OpGetLocal(0), OpReturnValue],
+        ),
+        (
+            "fn(a) { fn(b) { a + b } }",
+            vec![OpClosure(0), OpPop],
+            vec![OpClosure(0), OpReturnValue],
+        ),
+        (
+            "fn(a, b = 2) { return b; a }",
+            vec![OpClosure(0), OpPop],
+            vec![
+                OpJumpArgGiven(1, 11),
+                OpConstant(0),
+                OpSetLocal(1),
+                OpGetLocal(1),
+                OpReturnValue,
+                OpGetLocal(0),
+                OpReturnValue,
+            ],
+        ),
+        // A function can call itself through its own binding.
+        (
+            "let f = fn() { f() }",
+            vec![OpClosure(0), OpSetGlobal(0)],
+            vec![OpGetGlobal(0), OpCall(0), OpReturnValue],
+        ),
+    ];
+
+    for (input, expected_instructions, expected_function) in tests {
+        let program = parse(input).unwrap();
+
+        let bytecode = compile(program).unwrap();
+
+        assert_eq!(expected_instructions, bytecode.instructions);
+        assert_eq!(expected_function, bytecode.functions[0].output.instructions);
+    }
+
+    let program = parse("fn(a) { fn(b) { a + b } }").unwrap();
+    let bytecode = compile(program).unwrap();
+    assert_eq!(
+        vec![OpGetOuter(1, 0), OpGetLocal(0), OpAdd, OpReturnValue],
+        bytecode.functions[0].output.functions[0]
+            .output
+            .instructions
+    );
+}
+
+#[test]
+fn test_names() {
+    let tests = vec![
+        ("len([])", vec![OpGetName(0), OpArray(0), OpCall(1), OpPop]),
+        // The value is compiled before the binding is defined.
+        ("let b = b", vec![OpGetName(0), OpSetGlobal(0)]),
+    ];
+
+    for (input, expected_instructions) in tests {
+        let program = parse(input).unwrap();
+
+        let bytecode = compile(program).unwrap();
+
+        assert_eq!(expected_instructions, bytecode.instructions);
+    }
+}
+
+#[test]
+fn test_undefined_variable() {
+    let tests = vec![("a = 1", "a"), ("a[0] += 1", "a"), ("len = 3", "len")];
+
+    for (input, name) in tests {
+        let program = parse(input).unwrap();
+
+        match compile(program) {
+            Err(Error::UndefinedVariable(received)) => assert_eq!(name, received),
+            received => panic!("Expected undefined variable, received {:?}", received),
+        }
+    }
+}
+
+#[test]
+fn test_program_too_large() {
+    // Lists rather than a lot of statements keep blocks short enough for the parser.
+    let lists = format!("[{}1]; ", "1, ".repeat(250)).repeat(100);
+    let tests = vec![
+        format!("{}7", "1; ".repeat(25000)),
+        format!("if (true) {{ {}7 }}", lists),
+        format!("let f = fn() {{ {}7 }}", lists),
+    ];
+
+    for input in tests {
+        let program = parse(&input).unwrap();
+
+        match compile(program) {
+            Err(Error::ProgramTooLarge) => {}
+            received => panic!("Expected program too large, received {:?}", received),
+        }
+    }
+}
+
+#[test]
+fn test_unsupported() {
+    let tests = vec![
+        (r#"import "a.mk" as a"#, "imports"),
+        ("export let a = 1", "exports"),
+    ];
+
+    for (input, expected) in tests {
+        let program = parse(input).unwrap();
+
+        match compile(program) {
+            Err(Error::Unsupported(received)) => assert_eq!(expected, received),
+            received => panic!("Expected unsupported {}, received {:?}", expected, received),
+        }
+    }
+}
+
 // TODO testing of type mismatch.
 
 fn test_constants(expected: Vec<Object>, received: Vec<Object>) {
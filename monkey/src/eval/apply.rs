@@ -1,17 +1,33 @@
 use super::error::Error;
-use super::eval::{eval_exprs, Eval, EvalResult};
-use crate::ast::Expression;
-use crate::object::{BuiltIn, Env, Function, Object};
+use super::eval::{eval_exprs, track, Eval, EvalResult, ShortCircuit};
+use super::fs;
+use super::list;
+use super::pattern;
+use super::string;
+use crate::ast::{Expression, Param};
+use crate::object::{BigInt, BuiltIn, Env, ErrorObject, Function, HostFunction, Object, NULL};
+use crate::vm;
+use std::mem;
 
-pub trait Applicable {
-    fn apply(self, env: Env, arguments: Vec<Expression>) -> EvalResult;
+pub trait Applicable: Sized {
+    fn call(self, env: Env, arguments: Vec<Object>) -> EvalResult;
+
+    // Evaluates the arguments in the current env before calling.
+    fn apply(self, env: Env, arguments: Vec<Expression>) -> EvalResult {
+        let arguments = eval_exprs(env.clone(), arguments)?;
+        self.call(env, arguments)
+    }
 }
 
 impl Applicable for Object {
-    fn apply(self, env: Env, arguments: Vec<Expression>) -> EvalResult {
+    fn call(self, env: Env, arguments: Vec<Object>) -> EvalResult {
         match self {
-            Object::Function(func) => func.apply(env, arguments),
-            Object::BuiltIn(built_in) => built_in.apply(env, arguments),
+            Object::Function(func) => func.call(env, arguments),
+            Object::BuiltIn(built_in) => built_in.call(env, arguments),
+            Object::HostFunction(func) => HostFunction::clone(&func).call(env, arguments),
+            Object::Closure(closure) => {
+                vm::call(env, &closure, arguments).map_err(ShortCircuit::from)
+            }
             object => Error::CallExpressionExpectedFunction {
                 received: object.clone(),
             }
@@ -21,87 +37,295 @@ impl Applicable for Object {
 }
 
 impl Applicable for BuiltIn {
-    fn apply(self, env: Env, args: Vec<Expression>) -> EvalResult {
-        let objects = eval_exprs(env.clone(), args)?;
-
+    fn call(self, env: Env, objects: Vec<Object>) -> EvalResult {
         match (self, objects.as_slice()) {
-            (BuiltIn::Len, [Object::Str(val)]) => Ok(Object::Integer(val.len() as isize)),
+            (BuiltIn::Len, [Object::Str(val)]) => Ok(Object::Integer(val.chars().count() as isize)),
             (BuiltIn::Len, [Object::List(vals)]) => Ok(Object::Integer(vals.len() as isize)),
-            (BuiltIn::Len, [wrong_list_type]) => Err(Error::TypeError {
+            (BuiltIn::Len, [wrong_list_type]) => Error::TypeError {
                 message: format!(
                     "object of type '{}' has no len()",
                     wrong_list_type.type_str()
                 ),
-            }),
-            (BuiltIn::Len, wrong_num_args) => Err(Error::TypeError {
+            }
+            .into(),
+            (BuiltIn::Len, wrong_num_args) => Error::TypeError {
                 message: format!(
                     "len() takes exactly one argument ({} given)",
                     wrong_num_args.len()
                 ),
-            }),
-            (BuiltIn::Index, [Object::List(vals), Object::Integer(index)]) => {
-                if index < &0 {
-                    return Error::TypeError {
-                        message: "list indices must be positive".to_string(),
-                    }
-                    .into();
-                }
-                match vals.get(*index as usize) {
-                    Some(val) => Ok(val.clone()),
-                    None => Ok(Object::Null),
-                }
             }
-            (BuiltIn::Index, [Object::List(_), wrong_index_type]) => Err(Error::TypeError {
-                message: format!(
-                    "list indices must be integers, not {}",
-                    wrong_index_type.type_str()
-                ),
-            }),
-            (BuiltIn::Index, [wrong_list_type, _]) => Err(Error::TypeError {
-                message: format!(
-                    "object of type '{}' has no index",
-                    wrong_list_type.type_str()
-                ),
-            }),
-            (BuiltIn::Index, wrong_num_args) => Err(Error::TypeError {
+            .into(),
+            (BuiltIn::Index, [object, index]) => {
+                object.get_index(index).map_err(ShortCircuit::from)
+            }
+            (BuiltIn::Index, wrong_num_args) => Error::TypeError {
                 message: format!(
                     "index() takes exactly two arguments ({} given)",
                     wrong_num_args.len()
                 ),
-            }),
+            }
+            .into(),
             (BuiltIn::Push, [Object::List(old_vals), new_element]) => {
                 let mut new = old_vals.clone();
                 new.push(new_element.clone());
-                Ok(Object::List(new))
+                track(&env, Object::List(new))
             }
-            (BuiltIn::Push, [wrong_list_type, _]) => Err(Error::TypeError {
+            (BuiltIn::Push, [wrong_list_type, _]) => Error::TypeError {
                 message: format!(
                     "object of type '{}' has no push",
                     wrong_list_type.type_str()
                 ),
-            }),
-            (BuiltIn::Push, wrong_num_args) => Err(Error::TypeError {
+            }
+            .into(),
+            (BuiltIn::Push, wrong_num_args) => Error::TypeError {
                 message: format!(
                     "push() takes exactly two arguments ({} given)",
                     wrong_num_args.len()
                 ),
-            }),
-            (BuiltIn::Rest, [Object::List(vals)]) => Ok(match vals.get(1..) {
-                Some(x) => Object::List(x.into()),
-                None => Object::Null,
-            }),
-            (BuiltIn::Rest, [wrong_list_type]) => Err(Error::TypeError {
+            }
+            .into(),
+            (BuiltIn::Rest, [Object::List(vals)]) => match vals.get(1..) {
+                Some(x) => track(&env, Object::List(x.into())),
+                None => Ok(Object::Null),
+            },
+            (BuiltIn::Rest, [wrong_list_type]) => Error::TypeError {
                 message: format!(
                     "object of type '{}' has no rest()",
                     wrong_list_type.type_str()
                 ),
-            }),
-            (BuiltIn::Rest, wrong_num_args) => Err(Error::TypeError {
+            }
+            .into(),
+            (BuiltIn::Rest, wrong_num_args) => Error::TypeError {
                 message: format!(
                     "rest() takes exactly one argument ({} given)",
                     wrong_num_args.len()
                 ),
-            }),
+            }
+            .into(),
+            (BuiltIn::Int, [val @ Object::Integer(_)])
+            | (BuiltIn::Int, [val @ Object::BigInt(_)]) => Ok(val.clone()),
+            (BuiltIn::Int, [Object::Boolean(val)]) => Ok(Object::Integer(*val as isize)),
+            (BuiltIn::Int, [Object::Float(val)]) => {
+                BigInt::from_f64(*val).map(Object::from).ok_or_else(|| {
+                    Error::ValueError {
+                        message: format!("cannot convert float {:?} to integer", val),
+                    }
+                    .into()
+                })
+            }
+            (BuiltIn::Int, [Object::Str(val)]) => {
+                val.trim().parse::<BigInt>().map(Object::from).map_err(|_| {
+                    Error::ValueError {
+                        message: format!("invalid literal for int(): '{}'", val),
+                    }
+                    .into()
+                })
+            }
+            (BuiltIn::Int, [wrong_type]) => Error::TypeError {
+                message: format!(
+                    "int() argument must be a string or a number, not '{}'",
+                    wrong_type.type_str()
+                ),
+            }
+            .into(),
+            (BuiltIn::Int, wrong_num_args) => Error::TypeError {
+                message: format!(
+                    "int() takes exactly one argument ({} given)",
+                    wrong_num_args.len()
+                ),
+            }
+            .into(),
+            (BuiltIn::Float, [Object::Integer(val)]) => Ok(Object::Float(*val as f64)),
+            (BuiltIn::Float, [Object::BigInt(val)]) => Ok(Object::Float(val.to_f64())),
+            (BuiltIn::Float, [Object::Float(val)]) => Ok(Object::Float(*val)),
+            (BuiltIn::Float, [Object::Boolean(val)]) => {
+                Ok(Object::Float(if *val { 1.0 } else { 0.0 }))
+            }
+            (BuiltIn::Float, [Object::Str(val)]) => {
+                val.trim().parse::<f64>().map(Object::Float).map_err(|_| {
+                    Error::ValueError {
+                        message: format!("could not convert string to float: '{}'", val),
+                    }
+                    .into()
+                })
+            }
+            (BuiltIn::Float, [wrong_type]) => Error::TypeError {
+                message: format!(
+                    "float() argument must be a string or a number, not '{}'",
+                    wrong_type.type_str()
+                ),
+            }
+            .into(),
+            (BuiltIn::Float, wrong_num_args) => Error::TypeError {
+                message: format!(
+                    "float() takes exactly one argument ({} given)",
+                    wrong_num_args.len()
+                ),
+            }
+            .into(),
+            (BuiltIn::Type, [val]) => track(&env, Object::Str(val.type_str())),
+            (BuiltIn::Str, [val @ Object::Str(_)]) => Ok(val.clone()),
+            (BuiltIn::Str, [val]) => track(&env, Object::Str(val.to_string())),
+            (BuiltIn::Bool, [val]) => Ok(Object::from(val.is_truthy())),
+            (BuiltIn::Repr, [val]) => track(&env, Object::Str(val.repr())),
+            (
+                built_in @ (BuiltIn::Type | BuiltIn::Str | BuiltIn::Bool | BuiltIn::Repr),
+                wrong_num_args,
+            ) => num_args_error(&built_in, "exactly one argument", wrong_num_args.len()).into(),
+            (BuiltIn::Error, [message]) => {
+                Ok(Object::from(ErrorObject::new("Error", message.to_string())))
+            }
+            (BuiltIn::Error, wrong_num_args) => Error::TypeError {
+                message: format!(
+                    "Error() takes exactly one argument ({} given)",
+                    wrong_num_args.len()
+                ),
+            }
+            .into(),
+            (BuiltIn::First, [Object::List(vals)]) => Ok(vals.first().cloned().unwrap_or(NULL)),
+            (BuiltIn::Last, [Object::List(vals)]) => Ok(vals.last().cloned().unwrap_or(NULL)),
+            (BuiltIn::Reverse, [Object::List(vals)]) => {
+                track(&env, Object::List(vals.iter().rev().cloned().collect()))
+            }
+            (BuiltIn::Flatten, [Object::List(vals)]) => list::flatten(env, vals),
+            (
+                built_in @ (BuiltIn::First | BuiltIn::Last | BuiltIn::Reverse | BuiltIn::Flatten),
+                [wrong_list_type],
+            ) => list_arg_error(&built_in, wrong_list_type).into(),
+            (
+                built_in @ (BuiltIn::First | BuiltIn::Last | BuiltIn::Reverse | BuiltIn::Flatten),
+                wrong_num_args,
+            ) => num_args_error(&built_in, "exactly one argument", wrong_num_args.len()).into(),
+            (BuiltIn::Map, [Object::List(vals), func]) => list::map(env, vals, func),
+            (BuiltIn::Filter, [Object::List(vals), func]) => list::filter(env, vals, func),
+            (BuiltIn::Contains, [Object::List(vals), val]) => list::contains(vals, val),
+            (BuiltIn::Zip, [Object::List(left), Object::List(right)]) => {
+                list::zip(env, left, right)
+            }
+            (built_in @ BuiltIn::Zip, [Object::List(_), wrong_list_type])
+            | (
+                built_in @ (BuiltIn::Map | BuiltIn::Filter | BuiltIn::Contains | BuiltIn::Zip),
+                [wrong_list_type, _],
+            ) => list_arg_error(&built_in, wrong_list_type).into(),
+            (
+                built_in @ (BuiltIn::Map | BuiltIn::Filter | BuiltIn::Contains | BuiltIn::Zip),
+                wrong_num_args,
+            ) => num_args_error(&built_in, "exactly two arguments", wrong_num_args.len()).into(),
+            (BuiltIn::Reduce, [Object::List(vals), func]) => list::reduce(env, vals, func, None),
+            (BuiltIn::Reduce, [Object::List(vals), func, initial]) => {
+                list::reduce(env, vals, func, Some(initial))
+            }
+            (BuiltIn::Sort, [Object::List(vals)]) => list::sort(env, vals, None),
+            (BuiltIn::Sort, [Object::List(vals), comparator]) => {
+                list::sort(env, vals, Some(comparator))
+            }
+            (BuiltIn::Slice, [Object::List(vals), start]) => {
+                let start = list::integer_arg(&BuiltIn::Slice, start)?;
+                list::slice(env, vals, start, None)
+            }
+            (BuiltIn::Slice, [Object::List(vals), start, stop]) => {
+                let start = list::integer_arg(&BuiltIn::Slice, start)?;
+                let stop = list::integer_arg(&BuiltIn::Slice, stop)?;
+                list::slice(env, vals, start, Some(stop))
+            }
+            (BuiltIn::Slice, [Object::Str(val), start]) => {
+                let start = list::integer_arg(&BuiltIn::Slice, start)?;
+                string::slice(env, val, start, None)
+            }
+            (BuiltIn::Slice, [Object::Str(val), start, stop]) => {
+                let start = list::integer_arg(&BuiltIn::Slice, start)?;
+                let stop = list::integer_arg(&BuiltIn::Slice, stop)?;
+                string::slice(env, val, start, Some(stop))
+            }
+            (BuiltIn::Slice, [wrong_list_type, _] | [wrong_list_type, _, _]) => Error::TypeError {
+                message: format!(
+                    "slice() argument must be a list or a string, not '{}'",
+                    wrong_list_type.type_str()
+                ),
+            }
+            .into(),
+            (built_in @ BuiltIn::Reduce, [wrong_list_type, _] | [wrong_list_type, _, _])
+            | (built_in @ BuiltIn::Sort, [wrong_list_type] | [wrong_list_type, _]) => {
+                list_arg_error(&built_in, wrong_list_type).into()
+            }
+            (built_in @ (BuiltIn::Reduce | BuiltIn::Slice), wrong_num_args) => {
+                num_args_error(&built_in, "from 2 to 3 arguments", wrong_num_args.len()).into()
+            }
+            (BuiltIn::Sort, wrong_num_args) => num_args_error(
+                &BuiltIn::Sort,
+                "from 1 to 2 arguments",
+                wrong_num_args.len(),
+            )
+            .into(),
+            (BuiltIn::Range, args @ ([_] | [_, _] | [_, _, _])) => {
+                let args = args
+                    .iter()
+                    .map(|arg| list::integer_arg(&BuiltIn::Range, arg))
+                    .collect::<Result<Vec<isize>, Error>>()?;
+                match *args.as_slice() {
+                    [stop] => list::range(env, 0, stop, 1),
+                    [start, stop] => list::range(env, start, stop, 1),
+                    [start, stop, step] => list::range(env, start, stop, step),
+                    _ => unreachable!(),
+                }
+            }
+            (BuiltIn::Range, wrong_num_args) => num_args_error(
+                &BuiltIn::Range,
+                "from 1 to 3 arguments",
+                wrong_num_args.len(),
+            )
+            .into(),
+            (
+                built_in @ (BuiltIn::Split
+                | BuiltIn::Join
+                | BuiltIn::Trim
+                | BuiltIn::Upper
+                | BuiltIn::Lower
+                | BuiltIn::Replace
+                | BuiltIn::StartsWith
+                | BuiltIn::EndsWith
+                | BuiltIn::Find
+                | BuiltIn::Chars
+                | BuiltIn::Repeat
+                | BuiltIn::Format),
+                args,
+            ) => string::call(env, built_in, args),
+            (
+                built_in @ (BuiltIn::ReadFile
+                | BuiltIn::WriteFile
+                | BuiltIn::ListDir
+                | BuiltIn::Exists),
+                args,
+            ) => fs::call(env, built_in, args),
+            // Like in Python, the prompt is shown before reading the line.
+            (BuiltIn::Input, args @ ([] | [_])) | (BuiltIn::ReadLine, args @ []) => {
+                let prompt = args.first().map(|prompt| prompt.to_string());
+                match read_line(&env, prompt.as_deref())? {
+                    Some(line) => track(&env, Object::Str(line)),
+                    None => Ok(Object::Null),
+                }
+            }
+            (BuiltIn::Input, wrong_num_args) => num_args_error(
+                &BuiltIn::Input,
+                "from 0 to 1 arguments",
+                wrong_num_args.len(),
+            )
+            .into(),
+            (BuiltIn::ReadLine, wrong_num_args) => {
+                num_args_error(&BuiltIn::ReadLine, "no arguments", wrong_num_args.len()).into()
+            }
+            // All the remaining lines of the input.
+            (BuiltIn::ReadLines, []) => {
+                let mut lines = Vec::new();
+                while let Some(line) = read_line(&env, None)? {
+                    env.allocate_bytes(line.len() + mem::size_of::<Object>())?;
+                    lines.push(Object::Str(line));
+                }
+                Ok(Object::List(lines))
+            }
+            (BuiltIn::ReadLines, wrong_num_args) => {
+                num_args_error(&BuiltIn::ReadLines, "no arguments", wrong_num_args.len()).into()
+            }
             (BuiltIn::Print, args) => {
                 let stdout = args
                     .iter()
@@ -113,39 +337,136 @@ impl Applicable for BuiltIn {
                 Ok(Object::Null)
             }
         }
-        .map_err(|err| err.into())
+    }
+}
+
+impl Applicable for HostFunction {
+    fn call(self, env: Env, arguments: Vec<Object>) -> EvalResult {
+        if arguments.len() != self.arity {
+            return Error::TypeError {
+                message: format!(
+                    "{}() takes exactly {} {} ({} given)",
+                    self.name,
+                    self.arity,
+                    if self.arity == 1 {
+                        "argument"
+                    } else {
+                        "arguments"
+                    },
+                    arguments.len()
+                ),
+            }
+            .into();
+        }
+
+        let result = (self.func)(&arguments)?;
+        track(&env, result)
     }
 }
 
 impl Applicable for Function {
-    fn apply(self, env: Env, arguments: Vec<Expression>) -> EvalResult {
-        let Function {
-            params,
-            body,
-            env: func_env,
-        } = self;
-        // Check params.
-        if params.len() != arguments.len() {
-            Error::CallExpressionWrongNumArgs {
-                params: params.to_vec(), // not really sure what to_vec() does
-                arguments: arguments,
-            }
-            .into()
-        } else {
-            // Evaluate arguments in the current env.
-            let evaluated: Vec<Object> = eval_exprs(env.clone(), arguments)?;
-
-            // bind argument results to a new env which extends the function env.
-            let env_with_objects = params.iter().zip(evaluated).fold(
-                Env::new_extending(func_env),
-                |acc, (name, obj)| {
-                    acc.set(name.to_string(), obj);
-                    acc
-                },
-            );
-            // Alternative to body.clone() here would be to put RC on all AST objects
-            // which is a bit too much/
-            body.as_ref().clone().eval(env_with_objects)
+    fn call(self, env: Env, arguments: Vec<Object>) -> EvalResult {
+        env.enter_call()?;
+        let result = call_function(self, arguments);
+        env.exit_call();
+        result
+    }
+}
+
+fn call_function(func: Function, arguments: Vec<Object>) -> EvalResult {
+    let Function {
+        params,
+        body,
+        env: func_env,
+    } = func;
+    let arguments = check_arity(&params, arguments)?;
+
+    // bind argument results to a new env which extends the function env.
+    let env_with_objects = Env::new_extending(func_env);
+    let mut evaluated = arguments.into_iter();
+    for param in params.iter() {
+        match param {
+            Param::Required(pattern) => {
+                // The arity check makes sure there is an argument.
+                let obj = evaluated.next().unwrap_or(Object::Null);
+                pattern::bind(&env_with_objects, pattern, obj)?;
+            }
+            Param::Optional(pattern, default) => {
+                // Defaults can refer to the parameters before them.
+                let obj = match evaluated.next() {
+                    Some(obj) => obj,
+                    None => default.clone().eval(env_with_objects.clone())?,
+                };
+                pattern::bind(&env_with_objects, pattern, obj)?;
+            }
+            Param::Variadic(name) => {
+                let rest = track(
+                    &env_with_objects,
+                    Object::List(evaluated.by_ref().collect()),
+                )?;
+                env_with_objects.set(name.to_string(), rest);
+            }
         }
     }
+    // Alternative to body.clone() here would be to put RC on all AST objects
+    // which is a bit too much/
+    match body.as_ref().clone().eval(env_with_objects) {
+        // A return ends the function, not whatever called it.
+        Err(ShortCircuit::ReturningObject(object)) => Ok(object),
+        result => result,
+    }
+}
+
+// Calls a function value from outside of `eval`, like from the VM.
+pub fn call(env: Env, func: Object, arguments: Vec<Object>) -> Result<Object, Error> {
+    match func.call(env, arguments) {
+        Ok(object) | Err(ShortCircuit::ReturningObject(object)) => Ok(object),
+        Err(ShortCircuit::RuntimeError(err)) => Err(err),
+    }
+}
+
+// Passes the arguments through if there are as many as the params accept.
+pub fn check_arity(params: &[Param], arguments: Vec<Object>) -> Result<Vec<Object>, Error> {
+    let (min, max) = arity(params);
+    if arguments.len() < min || max.is_some_and(|max| arguments.len() > max) {
+        Err(Error::CallExpressionWrongNumArgs {
+            params: params.to_vec(),
+            arguments,
+        })
+    } else {
+        Ok(arguments)
+    }
+}
+
+fn list_arg_error(built_in: &BuiltIn, wrong_list_type: &Object) -> Error {
+    Error::TypeError {
+        message: format!(
+            "{}() argument must be a list, not '{}'",
+            built_in.name(),
+            wrong_list_type.type_str()
+        ),
+    }
+}
+
+fn read_line(env: &Env, prompt: Option<&str>) -> Result<Option<String>, Error> {
+    env.read_line(prompt).map_err(|err| Error::InputFailed {
+        message: err.to_string(),
+    })
+}
+
+pub fn num_args_error(built_in: &BuiltIn, expected: &str, given: usize) -> Error {
+    Error::TypeError {
+        message: format!("{}() takes {} ({} given)", built_in.name(), expected, given),
+    }
+}
+
+// The least and most number of arguments the params accept, without a most for variadic params.
+pub fn arity(params: &[Param]) -> (usize, Option<usize>) {
+    params
+        .iter()
+        .fold((0, Some(0)), |(min, max), param| match param {
+            Param::Required(_) => (min + 1, max.map(|max| max + 1)),
+            Param::Optional(..) => (min, max.map(|max| max + 1)),
+            Param::Variadic(_) => (min, None),
+        })
 }
@@ -1,7 +1,8 @@
 use self::Error::*;
-use crate::ast::Expression;
+use super::apply::arity;
+use crate::ast::{Param, Pattern};
 use crate::object;
-use crate::object::Object;
+use crate::object::{ErrorObject, Object};
 use std::fmt;
 
 #[derive(Debug, PartialEq)]
@@ -10,6 +11,9 @@ pub enum Error {
     TypeError {
         message: String,
     },
+    ValueError {
+        message: String,
+    },
     IdentifierNotFound {
         name: String,
     },
@@ -17,11 +21,85 @@ pub enum Error {
         received: Object,
     },
     CallExpressionWrongNumArgs {
-        params: Vec<String>,
-        arguments: Vec<Expression>,
+        params: Vec<Param>,
+        arguments: Vec<Object>,
+    },
+    PatternMismatch {
+        pattern: Pattern,
+        value: Object,
+    },
+    // None of the arms of a match expression matched the value.
+    NonExhaustiveMatch {
+        value: Object,
+    },
+    // The module couldn't be found or parsed.
+    ImportFailed {
+        path: String,
+        message: String,
+    },
+    // Reading from the input of the interpreter failed.
+    InputFailed {
+        message: String,
+    },
+    // A filesystem built-in was called while they are disabled, or with a path outside of the
+    // allowed directory.
+    PermissionDenied {
+        message: String,
+    },
+    FileFailed {
+        path: String,
+        message: String,
+    },
+    // The paths of the modules importing each other, where the first and last are the same.
+    ImportCycle {
+        cycle: Vec<String>,
+    },
+    AttributeNotFound {
+        object: Object,
+        name: String,
+    },
+    // An error object thrown with `throw`, which wasn't caught.
+    Thrown {
+        error: ErrorObject,
+    },
+    // The VM failed on bytecode which the compiler doesn't produce, while running a function
+    // called from outside of it.
+    InvalidBytecode {
+        message: String,
     },
 }
 
+impl Error {
+    pub fn kind(&self) -> &str {
+        match self {
+            ObjectError(err) => err.kind(),
+            TypeError { .. }
+            | CallExpressionExpectedFunction { .. }
+            | CallExpressionWrongNumArgs { .. } => "TypeError",
+            ValueError { .. } | PatternMismatch { .. } | NonExhaustiveMatch { .. } => "ValueError",
+            IdentifierNotFound { .. } => "NameError",
+            ImportFailed { .. } | ImportCycle { .. } => "ImportError",
+            InputFailed { .. } | FileFailed { .. } => "IOError",
+            PermissionDenied { .. } => "PermissionError",
+            AttributeNotFound { .. } => "AttributeError",
+            Thrown { error } => &error.kind,
+            InvalidBytecode { .. } => "SystemError",
+        }
+    }
+
+    // The error object `catch` receives, or `None` for errors which can't be caught.
+    pub fn to_catchable(&self) -> Option<ErrorObject> {
+        match self {
+            ObjectError(err) => err.to_catchable(),
+            Thrown { error } => Some(error.clone()),
+            InvalidBytecode { .. } => None,
+            err => Some(ErrorObject::from_error(err.kind(), err)),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -29,23 +107,68 @@ impl fmt::Display for Error {
             "{}",
             match self {
                 ObjectError(err) => format!("{}", err),
-                TypeError { message } => format!("{}", message),
+                TypeError { message } => format!("TypeError: {}", message),
+                ValueError { message } => format!("ValueError: {}", message),
                 IdentifierNotFound { name } => format!("NameError: name '{}' is not defined", name),
                 CallExpressionExpectedFunction { received } => format!(
                     "TypeError: '{}' object is not callable",
                     received.type_str(),
                 ),
-                CallExpressionWrongNumArgs { params, arguments } => format!(
-                    "TypeError: function takes {} positional {} but {} {} given",
-                    params.len(),
-                    if params.len() == 1 {
-                        "argument"
-                    } else {
-                        "arguments"
-                    },
-                    arguments.len(),
-                    if arguments.len() == 1 { "was" } else { "were" },
+                CallExpressionWrongNumArgs { params, arguments } => {
+                    let (min, max) = arity(params);
+                    format!(
+                        "TypeError: function takes {} positional {} but {} {} given",
+                        match max {
+                            Some(max) if min == max => min.to_string(),
+                            Some(max) => format!("from {} to {}", min, max),
+                            None => format!("at least {}", min),
+                        },
+                        if max.unwrap_or(min) == 1 {
+                            "argument"
+                        } else {
+                            "arguments"
+                        },
+                        arguments.len(),
+                        if arguments.len() == 1 { "was" } else { "were" },
+                    )
+                }
+                PatternMismatch { pattern, value } => format!(
+                    "ValueError: cannot match {} '{}' against pattern {}",
+                    value.type_str(),
+                    value,
+                    pattern,
+                ),
+                ImportFailed { path, message } => {
+                    format!("ImportError: cannot import '{}': {}", path, message)
+                }
+                InputFailed { message } => format!("IOError: cannot read input: {}", message),
+                PermissionDenied { message } => format!("PermissionError: {}", message),
+                FileFailed { path, message } => {
+                    format!("IOError: cannot access '{}': {}", path, message)
+                }
+                ImportCycle { cycle } => {
+                    format!("ImportError: circular import {}", cycle.join(" -> "))
+                }
+                AttributeNotFound {
+                    object: Object::Module(module),
+                    name,
+                } => format!(
+                    "AttributeError: module '{}' has no export '{}'",
+                    module.path, name
+                ),
+                AttributeNotFound { object, name } => format!(
+                    "AttributeError: '{}' object has no attribute '{}'",
+                    object.type_str(),
+                    name
+                ),
+                NonExhaustiveMatch { value } => format!(
+                    "ValueError: no match arm for {} '{}'",
+                    value.type_str(),
+                    value,
                 ),
+                Thrown { error } => format!("{}", error),
+                InvalidBytecode { message } =>
+                    format!("SystemError: invalid bytecode: {}", message),
             }
         )
     }
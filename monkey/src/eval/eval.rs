@@ -1,5 +1,6 @@
 use super::error::Error;
 use crate::ast::Expression;
+use crate::object;
 use crate::object::{Env, Object};
 use std::convert::From;
 use std::iter::FromIterator;
@@ -26,6 +27,12 @@ impl From<Error> for ShortCircuit {
     }
 }
 
+impl From<object::Error> for ShortCircuit {
+    fn from(err: object::Error) -> Self {
+        ShortCircuit::RuntimeError(err.into())
+    }
+}
+
 // EvalResult is an alias to manage control flow of evaluated expressions. We use the failure
 // portion of the Result type to handle both errors and return statements.
 pub type EvalResult = Result<Object, ShortCircuit>;
@@ -74,3 +81,9 @@ pub fn eval_exprs(env: Env, exprs: Vec<Expression>) -> Result<Vec<Object>, Short
         .collect::<EvalMultiple>()
         .0
 }
+
+// Counts a newly created object against the memory limit of the env.
+pub fn track(env: &Env, object: Object) -> EvalResult {
+    env.allocate(&object)?;
+    Ok(object)
+}
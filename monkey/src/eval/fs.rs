@@ -0,0 +1,94 @@
+use super::apply::num_args_error;
+use super::error::Error;
+use super::eval::{track, EvalResult};
+use crate::object::{BuiltIn, Env, Object};
+use crate::sandbox;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Paths are relative to the root directory the interpreter was given, and can't leave it.
+pub fn call(env: Env, built_in: BuiltIn, args: &[Object]) -> EvalResult {
+    let (num_args, expected) = match built_in {
+        BuiltIn::WriteFile => (2, "exactly two arguments"),
+        _ => (1, "exactly one argument"),
+    };
+    if args.len() != num_args {
+        return Err(num_args_error(&built_in, expected, args.len()).into());
+    }
+
+    let path = match &args[0] {
+        Object::Str(path) => path,
+        wrong_type => {
+            return Error::TypeError {
+                message: format!(
+                    "{}() argument must be a string, not '{}'",
+                    built_in.name(),
+                    wrong_type.type_str()
+                ),
+            }
+            .into()
+        }
+    };
+    let resolved = resolve(&env, path)?;
+    let failed = |err: io::Error| Error::FileFailed {
+        path: path.to_string(),
+        message: err.to_string(),
+    };
+
+    match (built_in, args) {
+        (BuiltIn::ReadFile, _) => {
+            // Check the size first, so that a huge file fails before it is read.
+            env.allocate_bytes(fs::metadata(&resolved).map_err(failed)?.len() as usize)?;
+            Ok(Object::Str(fs::read_to_string(&resolved).map_err(failed)?))
+        }
+        (BuiltIn::WriteFile, [_, Object::Str(contents)]) => {
+            fs::write(&resolved, contents).map_err(failed)?;
+            Ok(Object::Null)
+        }
+        (BuiltIn::WriteFile, [_, wrong_type]) => Error::TypeError {
+            message: format!(
+                "write_file() contents must be a string, not '{}'",
+                wrong_type.type_str()
+            ),
+        }
+        .into(),
+        // Names of the entries, sorted since the order isn't defined by the filesystem.
+        (BuiltIn::ListDir, _) => {
+            let mut names = fs::read_dir(&resolved)
+                .map_err(failed)?
+                .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+                .collect::<io::Result<Vec<String>>>()
+                .map_err(failed)?;
+            names.sort();
+            track(
+                &env,
+                Object::List(names.into_iter().map(Object::Str).collect()),
+            )
+        }
+        (BuiltIn::Exists, _) => Ok(Object::from(resolved.exists())),
+        (built_in, _) => unreachable!("{} isn't a filesystem built-in", built_in),
+    }
+}
+
+fn resolve(env: &Env, path: &str) -> Result<PathBuf, Error> {
+    let root = match env.filesystem_root() {
+        // There is no filesystem in the browser.
+        Some(root) if !cfg!(target_arch = "wasm32") => root,
+        _ => {
+            return Err(Error::PermissionDenied {
+                message: "filesystem access is disabled".to_string(),
+            })
+        }
+    };
+
+    sandbox::resolve(&root, path).map_err(|err| match err.kind() {
+        io::ErrorKind::PermissionDenied => Error::PermissionDenied {
+            message: err.to_string(),
+        },
+        _ => Error::FileFailed {
+            path: path.to_string(),
+            message: err.to_string(),
+        },
+    })
+}
@@ -0,0 +1,238 @@
+use super::apply::Applicable;
+use super::error::Error;
+use super::eval::{track, EvalResult, ShortCircuit};
+use crate::ast::Operator;
+use crate::object::{BuiltIn, Env, Object};
+use std::cmp::Ordering;
+use std::mem;
+
+// Number of elements `range()` creates between checks of the memory limit.
+const RANGE_CHUNK: usize = 1024;
+
+pub fn map(env: Env, vals: &[Object], func: &Object) -> EvalResult {
+    let mut mapped = Vec::with_capacity(vals.len());
+    for val in vals {
+        mapped.push(func.clone().call(env.clone(), vec![val.clone()])?);
+    }
+
+    track(&env, Object::List(mapped))
+}
+
+pub fn filter(env: Env, vals: &[Object], func: &Object) -> EvalResult {
+    let mut filtered = Vec::new();
+    for val in vals {
+        if func
+            .clone()
+            .call(env.clone(), vec![val.clone()])?
+            .is_truthy()
+        {
+            filtered.push(val.clone());
+        }
+    }
+
+    track(&env, Object::List(filtered))
+}
+
+// Without an initial value, the first element is used instead.
+pub fn reduce(env: Env, vals: &[Object], func: &Object, initial: Option<&Object>) -> EvalResult {
+    let (mut acc, rest) = match (initial, vals) {
+        (Some(initial), vals) => (initial.clone(), vals),
+        (None, [first, rest @ ..]) => (first.clone(), rest),
+        (None, []) => {
+            return Error::TypeError {
+                message: "reduce() of empty list with no initial value".to_string(),
+            }
+            .into()
+        }
+    };
+
+    for val in rest {
+        acc = func.clone().call(env.clone(), vec![acc, val.clone()])?;
+    }
+
+    Ok(acc)
+}
+
+pub fn range(env: Env, start: isize, stop: isize, step: isize) -> EvalResult {
+    if step == 0 {
+        return Error::ValueError {
+            message: "range() arg 3 must not be zero".to_string(),
+        }
+        .into();
+    }
+
+    // Counted in chunks, so that a huge range fails before taking up the memory. Each integer
+    // takes up the size of an object in the list, as `Object::heap_size` counts it.
+    let element_size = mem::size_of::<Object>();
+    let mut vals = Vec::new();
+    let mut current = Some(start);
+    while let Some(val) =
+        current.filter(|&val| (step > 0 && val < stop) || (step < 0 && val > stop))
+    {
+        vals.push(Object::Integer(val));
+        if vals.len() % RANGE_CHUNK == 0 {
+            env.allocate_bytes(RANGE_CHUNK * element_size)?;
+        }
+        current = val.checked_add(step);
+    }
+    env.allocate_bytes(vals.len() % RANGE_CHUNK * element_size)?;
+
+    Ok(Object::List(vals))
+}
+
+// Without a comparator, numbers and strings are sorted in ascending order. The comparator returns
+// a negative number when its first argument goes first, and a positive number when it goes last.
+pub fn sort(env: Env, vals: &[Object], comparator: Option<&Object>) -> EvalResult {
+    let sorted = merge_sort(vals.to_vec(), &mut |left, right| match comparator {
+        Some(comparator) => comparator
+            .clone()
+            .call(env.clone(), vec![left.clone(), right.clone()])
+            .and_then(|result| comparator_ordering(&result)),
+        None => compare(left, right),
+    })?;
+
+    track(&env, Object::List(sorted))
+}
+
+// A stable sort which stops at the first error of the comparison. `slice::sort_by` can't be used,
+// as it may panic when the comparison isn't a total order, which a comparator of the program
+// doesn't have to be.
+fn merge_sort(
+    mut vals: Vec<Object>,
+    compare: &mut impl FnMut(&Object, &Object) -> Result<Ordering, ShortCircuit>,
+) -> Result<Vec<Object>, ShortCircuit> {
+    if vals.len() < 2 {
+        return Ok(vals);
+    }
+
+    let right = vals.split_off(vals.len() / 2);
+    let left = merge_sort(vals, compare)?;
+    let right = merge_sort(right, compare)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(left_val), Some(right_val)) = (left.peek(), right.peek()) {
+        // Equal elements are taken from the left first, which keeps them in order.
+        let next = if compare(right_val, left_val)? == Ordering::Less {
+            right.next()
+        } else {
+            left.next()
+        };
+        merged.extend(next);
+    }
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
+
+pub fn contains(vals: &[Object], val: &Object) -> EvalResult {
+    for element in vals {
+        if element
+            .clone()
+            .apply_operator(Operator::Equal, val.clone())?
+            .is_truthy()
+        {
+            return Ok(Object::from(true));
+        }
+    }
+
+    Ok(Object::from(false))
+}
+
+pub fn zip(env: Env, left: &[Object], right: &[Object]) -> EvalResult {
+    let pairs = left
+        .iter()
+        .zip(right)
+        .map(|(left, right)| Object::List(vec![left.clone(), right.clone()]))
+        .collect();
+
+    track(&env, Object::List(pairs))
+}
+
+// Only flattens one level, and keeps the elements which aren't lists.
+pub fn flatten(env: Env, vals: &[Object]) -> EvalResult {
+    let mut flattened = Vec::new();
+    for val in vals {
+        match val {
+            Object::List(inner) => flattened.extend(inner.iter().cloned()),
+            val => flattened.push(val.clone()),
+        }
+    }
+
+    track(&env, Object::List(flattened))
+}
+
+pub fn slice(env: Env, vals: &[Object], start: isize, stop: Option<isize>) -> EvalResult {
+    let (start, stop) = slice_bounds(vals.len(), start, stop);
+
+    let sliced = vals.get(start..stop).unwrap_or(&[]).to_vec();
+    track(&env, Object::List(sliced))
+}
+
+// Negative indices count from the end, and indices out of range are clamped like in Python.
+pub fn slice_bounds(len: usize, start: isize, stop: Option<isize>) -> (usize, usize) {
+    let clamp = |index: isize| {
+        let index = if index < 0 {
+            index + len as isize
+        } else {
+            index
+        };
+        index.clamp(0, len as isize) as usize
+    };
+
+    (clamp(start), stop.map_or(len, clamp))
+}
+
+// The integer argument of a built-in, like the bounds of `range()`.
+pub fn integer_arg(built_in: &BuiltIn, arg: &Object) -> Result<isize, Error> {
+    match arg {
+        Object::Integer(val) => Ok(*val),
+        Object::BigInt(val) => Err(Error::ValueError {
+            message: format!("{}() argument {} is too large", built_in.name(), val),
+        }),
+        wrong_type => Err(Error::TypeError {
+            message: format!(
+                "{}() argument must be an integer, not '{}'",
+                built_in.name(),
+                wrong_type.type_str()
+            ),
+        }),
+    }
+}
+
+// Strings can't be compared with `<` in Monkey, but can still be sorted.
+fn compare(left: &Object, right: &Object) -> Result<Ordering, ShortCircuit> {
+    if let (Object::Str(left), Object::Str(right)) = (left, right) {
+        return Ok(left.cmp(right));
+    }
+
+    let less = |left: &Object, right: &Object| {
+        left.clone()
+            .apply_operator(Operator::LessThan, right.clone())
+            .map(|result| result.is_truthy())
+    };
+
+    if less(left, right)? {
+        Ok(Ordering::Less)
+    } else if less(right, left)? {
+        Ok(Ordering::Greater)
+    } else {
+        Ok(Ordering::Equal)
+    }
+}
+
+fn comparator_ordering(result: &Object) -> Result<Ordering, ShortCircuit> {
+    match result {
+        Object::Integer(val) => Ok(val.cmp(&0)),
+        Object::Float(val) => Ok(val.partial_cmp(&0.0).unwrap_or(Ordering::Equal)),
+        wrong_type => Err(Error::TypeError {
+            message: format!(
+                "sort() comparator must return a number, not '{}'",
+                wrong_type.type_str()
+            ),
+        }
+        .into()),
+    }
+}
@@ -1,15 +1,29 @@
 mod apply;
 mod error;
 mod eval;
+mod fs;
+mod list;
+mod module;
+mod native;
+mod pattern;
+mod string;
 
 #[cfg(test)]
 mod tests;
 
 use self::apply::Applicable;
+pub use self::apply::{call, check_arity};
 pub use self::error::Error;
-use self::eval::{eval_exprs, Eval, EvalResult, ShortCircuit};
-use crate::ast::{CallFunctionExpression, Expression, Program, Statement, Statements};
-use crate::object::{BuiltIn, Env, Function, Object, NULL};
+use self::eval::{eval_exprs, track, Eval, EvalResult, ShortCircuit};
+pub use self::module::eval_member;
+pub use self::native::call_method;
+pub use self::pattern::matches_list;
+use crate::ast::{
+    CallFunctionExpression, Expression, InterpolationPart, MatchArm, Operator, Pattern, Program,
+    Statement, Statements,
+};
+use crate::object;
+use crate::object::{BuiltIn, Env, Function, Object, FALSE, NULL, TRUE};
 
 impl Program {
     pub fn evaluate(self, env: Env) -> (Env, Result<Object, Error>) {
@@ -46,18 +60,46 @@ impl Eval for Statements {
 impl Eval for Statement {
     fn eval(self, env: Env) -> EvalResult {
         match self {
-            Statement::Let(name, expr) => expr.eval(env.clone()).map(|object| {
-                env.set(name, object);
-                NULL
-            }),
+            Statement::Let(pattern, expr) => {
+                let object = expr.eval(env.clone())?;
+                pattern::bind(&env, &pattern, object)?;
+                Ok(NULL)
+            }
             Statement::Expression(expr) => expr.eval(env),
             Statement::Return(expr) => expr
                 .eval(env)
                 .and_then(|object| Err(ShortCircuit::from(object))),
+            Statement::Throw(expr) => eval_throw_statement(env, expr),
+            Statement::Import { path, name } => {
+                module::eval_import(&env, path, name)?;
+                Ok(NULL)
+            }
+            Statement::Export(pattern, expr) => eval_export_statement(env, pattern, expr),
         }
     }
 }
 
+fn eval_throw_statement(env: Env, expr: Expression) -> EvalResult {
+    match expr.eval(env)? {
+        Object::Error(error) => Error::Thrown {
+            error: error.as_ref().clone(),
+        }
+        .into(),
+        object => Error::from(object::Error::ThrowType { object }).into(),
+    }
+}
+
+fn eval_export_statement(env: Env, pattern: Pattern, expr: Expression) -> EvalResult {
+    let object = expr.eval(env.clone())?;
+    pattern::bind(&env, &pattern, object)?;
+    for name in pattern::names(&pattern) {
+        env.export(name);
+    }
+    Ok(NULL)
+}
+
+// The larger arms are in their own functions, since the frame of this is on the stack once or more
+// for every call in a recursion.
 impl Eval for Expression {
     fn eval(self, env: Env) -> EvalResult {
         // Useful for debugging:
@@ -71,33 +113,28 @@ impl Eval for Expression {
                 }
                 .into(),
             },
-            // TODO: check if this is safe
-            Expression::IntegerLiteral(val) => Object::Integer(val as isize).into(),
-            Expression::StringLiteral(val) => Object::Str(val).into(),
+            Expression::IntegerLiteral(val) => Object::from_unsigned(val).into(),
+            Expression::BigIntegerLiteral(val) => Object::from(val).into(),
+            Expression::FloatLiteral(val) => Object::Float(val).into(),
+            Expression::StringLiteral(val) => track(&env, Object::Str(val)),
+            Expression::Interpolation(parts) => eval_interpolation(env, parts),
             Expression::ListLiteral(vals) => {
-                eval_exprs(env, vals).and_then(|objs| Object::List(objs).into())
+                eval_exprs(env.clone(), vals).and_then(|objs| track(&env, Object::List(objs)))
             }
             Expression::Boolean(val) => Object::from(val).into(),
-            Expression::Prefix { operator, right } => right.eval(env).and_then(|object| {
-                object.apply_prefix_operator(operator).map_err(|apply_err| {
-                    let err: Error = apply_err.into();
-                    err.into()
-                })
-            }),
+            Expression::Prefix { operator, right } => right
+                .eval(env.clone())
+                .and_then(|object| track(&env, object.apply_prefix_operator(operator)?)),
+            Expression::Infix {
+                operator: operator @ (Operator::And | Operator::Or),
+                left,
+                right,
+            } => eval_logical_expr(env, operator, *left, *right),
             Expression::Infix {
                 operator,
                 left,
                 right,
-            } => left.eval(env.clone()).and_then(|left_obj| {
-                right.eval(env).and_then(|right_obj| {
-                    left_obj
-                        .apply_operator(operator, right_obj)
-                        .map_err(|apply_err| {
-                            let err: Error = apply_err.into();
-                            err.into()
-                        })
-                })
-            }),
+            } => eval_infix_expr(env, operator, *left, *right),
             Expression::If {
                 condition,
                 consequence,
@@ -109,27 +146,195 @@ impl Eval for Expression {
             Expression::Call {
                 function,
                 arguments,
-            } => {
-                // Normalize identifier or function literal to common function.
-                let func_result: EvalResult = match function {
-                    CallFunctionExpression::Identifier(name) => env.get(&name).ok_or(
-                        Error::IdentifierNotFound {
-                            name: name.to_string(),
-                        }
-                        .into(),
-                    ),
-                    CallFunctionExpression::Literal(ast_func) => {
-                        Ok(Object::Function(Function::new(env.clone(), ast_func)))
-                    }
-                };
-
-                func_result?.apply(env, arguments)
-            }
+            } => eval_call_expr(env, function, arguments),
             Expression::Index { left, index } => BuiltIn::Index.apply(env, vec![*left, *index]),
+            Expression::Assign {
+                target,
+                operator,
+                value,
+            } => eval_assign_expr(env, *target, operator, *value),
+            Expression::Member { object, name } => {
+                module::eval_member(object.eval(env)?, name).map_err(ShortCircuit::from)
+            }
+            Expression::Match { value, arms } => eval_match_expr(env, *value, arms),
+            Expression::Try {
+                body,
+                name,
+                handler,
+            } => eval_try_expr(env, body, name, handler),
         }
     }
 }
 
+fn eval_infix_expr(
+    env: Env,
+    operator: Operator,
+    left: Expression,
+    right: Expression,
+) -> EvalResult {
+    let left = left.eval(env.clone())?;
+    let right = right.eval(env.clone())?;
+    let object = left.apply_operator(operator, right)?;
+    track(&env, object)
+}
+
+fn eval_call_expr(
+    env: Env,
+    function: CallFunctionExpression,
+    arguments: Vec<Expression>,
+) -> EvalResult {
+    // Normalize identifier or function literal to common function.
+    let func_result: EvalResult = match function {
+        CallFunctionExpression::Identifier(name) => env.get(&name).ok_or(
+            Error::IdentifierNotFound {
+                name: name.to_string(),
+            }
+            .into(),
+        ),
+        CallFunctionExpression::Literal(ast_func) => {
+            Ok(Object::Function(Function::new(env.clone(), ast_func)))
+        }
+        // Methods of native objects aren't values, so they are called directly.
+        CallFunctionExpression::Expression(expr) => match *expr {
+            Expression::Member { object, name } => match object.eval(env.clone())? {
+                Object::Native(native) => {
+                    let arguments = eval_exprs(env.clone(), arguments)?;
+                    return call_method(&env, native, name, &arguments).map_err(ShortCircuit::from);
+                }
+                object => module::eval_member(object, name).map_err(ShortCircuit::from),
+            },
+            expr => expr.eval(env.clone()),
+        },
+    };
+
+    func_result?.apply(env, arguments)
+}
+
+fn eval_interpolation(env: Env, parts: Vec<InterpolationPart>) -> EvalResult {
+    let mut string = String::new();
+    for part in parts {
+        match part {
+            InterpolationPart::Str(part) => string.push_str(&part),
+            InterpolationPart::Expression(expr) => {
+                string.push_str(&expr.eval(env.clone())?.to_string())
+            }
+        }
+    }
+
+    track(&env, Object::Str(string))
+}
+
+// `&&` and `||` always evaluate to a boolean, and only evaluate the right side when the left side
+// doesn't already decide the result.
+fn eval_logical_expr(
+    env: Env,
+    operator: Operator,
+    left: Expression,
+    right: Expression,
+) -> EvalResult {
+    let left = left.eval(env.clone())?.is_truthy();
+
+    match (operator, left) {
+        (Operator::And, false) => Ok(FALSE),
+        (Operator::Or, true) => Ok(TRUE),
+        _ => right.eval(env).map(|right| Object::from(right.is_truthy())),
+    }
+}
+
+// The indices in the target are evaluated before the value. Evaluates to the assigned value.
+fn eval_assign_expr(
+    env: Env,
+    target: Expression,
+    operator: Option<Operator>,
+    value: Expression,
+) -> EvalResult {
+    let (name, indices) = eval_assign_target(env.clone(), target)?;
+    // Built-ins and host functions aren't bindings, so they can't be assigned to, like the compiler
+    // doesn't resolve them either.
+    let root = env
+        .get_binding(&name)
+        .ok_or_else(|| Error::IdentifierNotFound { name: name.clone() })?;
+
+    let value = value.eval(env.clone())?;
+    let value = match operator {
+        Some(operator) => {
+            let current = indices
+                .iter()
+                .try_fold(root.clone(), |object, index| object.get_index(index))?;
+            track(&env, current.apply_operator(operator, value)?)?
+        }
+        None => value,
+    };
+
+    let assigned = if indices.is_empty() {
+        env.assign(&name, value.clone())
+    } else {
+        let updated = track(&env, root.set_index_path(&indices, value.clone())?)?;
+        env.assign(&name, updated)
+    };
+
+    if !assigned {
+        return Error::IdentifierNotFound { name }.into();
+    }
+
+    Ok(value)
+}
+
+// Splits a target like `xs[0][i]` into the name of the binding and the evaluated indices.
+fn eval_assign_target(env: Env, target: Expression) -> Result<(String, Vec<Object>), ShortCircuit> {
+    match target {
+        Expression::Identifier(name) => Ok((name, Vec::new())),
+        Expression::Index { left, index } => {
+            let (name, mut indices) = eval_assign_target(env.clone(), *left)?;
+            indices.push(index.eval(env)?);
+            Ok((name, indices))
+        }
+        target => Err(Error::TypeError {
+            message: format!("cannot assign to {}", target),
+        }
+        .into()),
+    }
+}
+
+// Each arm binds its pattern in its own env, so names from an arm which didn't match don't leak
+// into the next one.
+fn eval_match_expr(env: Env, value: Expression, arms: Vec<MatchArm>) -> EvalResult {
+    let value = value.eval(env.clone())?;
+
+    for arm in arms {
+        let arm_env = Env::new_extending(env.clone());
+        if !pattern::matches(&arm_env, &arm.pattern, value.clone())? {
+            continue;
+        }
+
+        if let Some(guard) = arm.guard {
+            if !guard.eval(arm_env.clone())?.is_truthy() {
+                continue;
+            }
+        }
+
+        return arm.body.eval(arm_env);
+    }
+
+    Error::NonExhaustiveMatch { value }.into()
+}
+
+// Returning from a function isn't an error, so it passes through the handler like errors which
+// can't be caught.
+fn eval_try_expr(env: Env, body: Statements, name: String, handler: Statements) -> EvalResult {
+    match body.eval(Env::new_extending(env.clone())) {
+        Err(ShortCircuit::RuntimeError(err)) => match err.to_catchable() {
+            Some(error) => {
+                let handler_env = Env::new_extending(env);
+                handler_env.set(name, Object::from(error));
+                handler.eval(handler_env)
+            }
+            None => Err(ShortCircuit::RuntimeError(err)),
+        },
+        result => result,
+    }
+}
+
 fn eval_if_expr(
     env: Env,
     condition: Box<Expression>,
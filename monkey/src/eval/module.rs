@@ -0,0 +1,83 @@
+use super::error::Error;
+use super::eval::{Eval, ShortCircuit};
+use crate::lexer::Lexer;
+use crate::module::normalize_path;
+use crate::object::{Env, Module, Object};
+use crate::parser::Parser;
+use std::rc::Rc;
+
+// Modules are evaluated the first time they are imported, and shared by later imports. Paths are
+// relative to the module containing the import.
+pub fn eval_import(env: &Env, path: String, name: String) -> Result<(), ShortCircuit> {
+    let from = env.module_path().unwrap_or_default();
+    let path = normalize_path(&from, &path).map_err(|err| Error::ImportFailed {
+        path: path.clone(),
+        message: err.to_string(),
+    })?;
+
+    let module = match env.cached_module(&path) {
+        Some(module) => module,
+        None => load_module(env, path)?,
+    };
+
+    env.set(name, Object::Module(module));
+    Ok(())
+}
+
+fn load_module(env: &Env, path: String) -> Result<Module, ShortCircuit> {
+    if let Err(cycle) = env.start_loading_module(&path) {
+        return Err(Error::ImportCycle { cycle }.into());
+    }
+
+    let result = eval_module(env, path.clone());
+    env.finish_loading_module(&path, result.as_ref().ok().cloned());
+    result
+}
+
+// Errors while evaluating the module are returned as is, as they already describe the problem.
+fn eval_module(env: &Env, path: String) -> Result<Module, ShortCircuit> {
+    let import_error = |message: String| Error::ImportFailed {
+        path: path.clone(),
+        message,
+    };
+
+    let source = env
+        .resolve_module(&path)
+        .map_err(|err| import_error(err.to_string()))?;
+
+    let program = Parser::new(Lexer::new(&source)).parse().map_err(|errs| {
+        import_error(
+            errs.errors
+                .iter()
+                .map(|err| err.to_string())
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+    })?;
+
+    let module_env = env.new_module(path.clone());
+    match program.statements.eval(module_env.clone()) {
+        // A return at the top level of a module just stops evaluating it.
+        Ok(_) | Err(ShortCircuit::ReturningObject(_)) => (),
+        Err(err) => return Err(err),
+    }
+
+    Ok(Module {
+        exports: Rc::new(module_env.exports()),
+        path,
+    })
+}
+
+// `object.name` works on modules and errors.
+pub fn eval_member(object: Object, name: String) -> Result<Object, Error> {
+    match &object {
+        Object::Module(module) => module.exports.get(&name).cloned(),
+        Object::Error(error) => match name.as_str() {
+            "kind" => Some(Object::Str(error.kind.clone())),
+            "message" => Some(Object::Str(error.message.clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or(Error::AttributeNotFound { object, name })
+}
@@ -0,0 +1,23 @@
+use super::error::Error;
+use crate::object::{Env, NativeObject, Object};
+use std::rc::Rc;
+
+// The result counts against the memory limit like other new objects.
+pub fn call_method(
+    env: &Env,
+    native: Rc<dyn NativeObject>,
+    name: String,
+    arguments: &[Object],
+) -> Result<Object, Error> {
+    match native.call_method(&name, arguments) {
+        Some(result) => {
+            let object = result?;
+            env.allocate(&object)?;
+            Ok(object)
+        }
+        None => Err(Error::AttributeNotFound {
+            object: Object::Native(native),
+            name,
+        }),
+    }
+}
@@ -0,0 +1,84 @@
+use super::error::Error;
+use super::eval::{track, Eval, ShortCircuit};
+use crate::ast::{Operator, Pattern};
+use crate::object::{Env, Object};
+
+// Binds the names in the pattern to the matching parts of the value in `env`.
+pub fn bind(env: &Env, pattern: &Pattern, value: Object) -> Result<(), ShortCircuit> {
+    match (pattern, value) {
+        (Pattern::Identifier(name), value) => {
+            env.set(name.to_string(), value);
+            Ok(())
+        }
+        (Pattern::Wildcard, _) => Ok(()),
+        (Pattern::Literal(literal), value)
+            if literal
+                .clone()
+                .eval(env.clone())?
+                .apply_operator(Operator::Equal, value.clone())?
+                .is_truthy() =>
+        {
+            Ok(())
+        }
+        (Pattern::List { elements, rest }, Object::List(mut values))
+            if matches_len(elements.len(), rest.is_some(), values.len()) =>
+        {
+            let rest_values = values.split_off(elements.len());
+
+            for (element, value) in elements.iter().zip(values) {
+                bind(env, element, value)?;
+            }
+
+            if let Some(rest) = rest {
+                env.set(rest.to_string(), track(env, Object::List(rest_values))?);
+            }
+            Ok(())
+        }
+        (pattern, value) => Err(Error::PatternMismatch {
+            pattern: pattern.clone(),
+            value,
+        }
+        .into()),
+    }
+}
+
+// The names bound by the pattern, in order.
+pub fn names(pattern: &Pattern) -> Vec<String> {
+    match pattern {
+        Pattern::Identifier(name) => vec![name.to_string()],
+        Pattern::List { elements, rest } => elements
+            .iter()
+            .flat_map(names)
+            .chain(rest.iter().cloned())
+            .collect(),
+        Pattern::Wildcard | Pattern::Literal(_) => Vec::new(),
+    }
+}
+
+// Like `bind`, but a value which doesn't match the pattern isn't an error. Some names may
+// already be bound in `env` when the value doesn't match.
+pub fn matches(env: &Env, pattern: &Pattern, value: Object) -> Result<bool, ShortCircuit> {
+    match bind(env, pattern, value) {
+        Ok(()) => Ok(true),
+        Err(ShortCircuit::RuntimeError(Error::PatternMismatch { .. })) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+// Whether the value is a list with as many elements as the list pattern needs.
+pub fn matches_list(pattern: &Pattern, value: &Object) -> bool {
+    match (pattern, value) {
+        (Pattern::List { elements, rest }, Object::List(values)) => {
+            matches_len(elements.len(), rest.is_some(), values.len())
+        }
+        _ => false,
+    }
+}
+
+fn matches_len(elements: usize, has_rest: bool, values: usize) -> bool {
+    if has_rest {
+        values >= elements
+    } else {
+        values == elements
+    }
+}
@@ -0,0 +1,159 @@
+use super::apply::num_args_error;
+use super::error::Error;
+use super::eval::{track, EvalResult};
+use super::list;
+use crate::object::{BuiltIn, Env, Object};
+
+// Positions and lengths of strings are in characters rather than bytes.
+pub fn call(env: Env, built_in: BuiltIn, args: &[Object]) -> EvalResult {
+    let (min, max, expected) = match built_in {
+        BuiltIn::Split => (1, Some(2), "from 1 to 2 arguments"),
+        BuiltIn::Trim | BuiltIn::Upper | BuiltIn::Lower | BuiltIn::Chars => {
+            (1, Some(1), "exactly one argument")
+        }
+        BuiltIn::Replace => (3, Some(3), "exactly three arguments"),
+        BuiltIn::Format => (1, None, "at least one argument"),
+        _ => (2, Some(2), "exactly two arguments"),
+    };
+    if args.len() < min || max.is_some_and(|max| args.len() > max) {
+        return Err(num_args_error(&built_in, expected, args.len()).into());
+    }
+
+    match (&built_in, args) {
+        (BuiltIn::Split, [Object::Str(val)]) => {
+            track(&env, list_of_strings(val.split_whitespace()))
+        }
+        (BuiltIn::Split, [Object::Str(_), Object::Str(separator)]) if separator.is_empty() => {
+            Error::ValueError {
+                message: "empty separator".to_string(),
+            }
+            .into()
+        }
+        (BuiltIn::Split, [Object::Str(val), Object::Str(separator)]) => {
+            track(&env, list_of_strings(val.split(separator.as_str())))
+        }
+        (BuiltIn::Join, [Object::List(vals), Object::Str(separator)]) => {
+            let vals = vals
+                .iter()
+                .map(|val| match val {
+                    Object::Str(val) => Ok(val.as_str()),
+                    wrong_type => Err(Error::TypeError {
+                        message: format!(
+                            "join() list elements must be strings, not '{}'",
+                            wrong_type.type_str()
+                        ),
+                    }),
+                })
+                .collect::<Result<Vec<&str>, Error>>()?;
+            track(&env, Object::Str(vals.join(separator)))
+        }
+        (BuiltIn::Join, [Object::List(_), wrong_type]) => Error::TypeError {
+            message: format!(
+                "join() argument must be a string, not '{}'",
+                wrong_type.type_str()
+            ),
+        }
+        .into(),
+        (BuiltIn::Join, [wrong_list_type, _]) => Error::TypeError {
+            message: format!(
+                "join() argument must be a list, not '{}'",
+                wrong_list_type.type_str()
+            ),
+        }
+        .into(),
+        (BuiltIn::Trim, [Object::Str(val)]) => track(&env, Object::from(val.trim())),
+        (BuiltIn::Upper, [Object::Str(val)]) => track(&env, Object::Str(val.to_uppercase())),
+        (BuiltIn::Lower, [Object::Str(val)]) => track(&env, Object::Str(val.to_lowercase())),
+        (BuiltIn::Replace, [Object::Str(val), Object::Str(from), Object::Str(to)]) => {
+            track(&env, Object::Str(val.replace(from.as_str(), to)))
+        }
+        (BuiltIn::StartsWith, [Object::Str(val), Object::Str(prefix)]) => {
+            Ok(Object::from(val.starts_with(prefix.as_str())))
+        }
+        (BuiltIn::EndsWith, [Object::Str(val), Object::Str(suffix)]) => {
+            Ok(Object::from(val.ends_with(suffix.as_str())))
+        }
+        // The position of the first match, or -1 like in Python.
+        (BuiltIn::Find, [Object::Str(val), Object::Str(sub)]) => Ok(Object::Integer(
+            val.find(sub.as_str())
+                .map(|i| val[..i].chars().count() as isize)
+                .unwrap_or(-1),
+        )),
+        (BuiltIn::Chars, [Object::Str(val)]) => track(
+            &env,
+            Object::List(val.chars().map(|c| Object::Str(c.to_string())).collect()),
+        ),
+        (BuiltIn::Repeat, [Object::Str(val), times]) => {
+            let times = list::integer_arg(&built_in, times)?.max(0) as usize;
+            // Check the size first, since a small string can be repeated into a huge one.
+            env.allocate_bytes(val.len().saturating_mul(times))?;
+            Ok(Object::Str(val.repeat(times)))
+        }
+        (BuiltIn::Format, [Object::Str(template), args @ ..]) => {
+            track(&env, format(template, args)?)
+        }
+        (built_in, args) => {
+            let wrong_type = args
+                .iter()
+                .find(|arg| !matches!(arg, Object::Str(_)))
+                .unwrap_or(&Object::Null);
+            Error::TypeError {
+                message: format!(
+                    "{}() argument must be a string, not '{}'",
+                    built_in.name(),
+                    wrong_type.type_str()
+                ),
+            }
+            .into()
+        }
+    }
+}
+
+// Gets the character at the index, or null when it is out of range.
+pub fn slice(env: Env, val: &str, start: isize, stop: Option<isize>) -> EvalResult {
+    let (start, stop) = list::slice_bounds(val.chars().count(), start, stop);
+
+    track(
+        &env,
+        Object::Str(
+            val.chars()
+                .skip(start)
+                .take(stop.saturating_sub(start))
+                .collect(),
+        ),
+    )
+}
+
+// Replaces each `{}` in the template with the next argument. `{{` and `}}` are literal braces.
+fn format(template: &str, args: &[Object]) -> Result<Object, Error> {
+    let mut formatted = String::new();
+    let mut args = args.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                formatted.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                let arg = args.next().ok_or_else(|| Error::ValueError {
+                    message: "format() got fewer arguments than placeholders".to_string(),
+                })?;
+                formatted.push_str(&arg.to_string());
+            }
+            ('{', _) | ('}', _) => {
+                return Err(Error::ValueError {
+                    message: format!("format() got an unmatched '{}' in the template", c),
+                })
+            }
+            (c, _) => formatted.push(c),
+        }
+    }
+
+    Ok(Object::Str(formatted))
+}
+
+fn list_of_strings<'a>(vals: impl Iterator<Item = &'a str>) -> Object {
+    Object::List(vals.map(Object::from).collect())
+}
@@ -1,9 +1,13 @@
-use crate::ast::{Expression, Operator, Statement};
+use crate::ast::{Expression, Operator, Param, Pattern, Statement};
 use crate::eval::Error;
+use crate::input::Lines;
 use crate::lexer::Lexer;
+use crate::module::MapResolver;
 use crate::object;
-use crate::object::{Env, Function, Object, NULL};
+use crate::object::{Env, Function, Object, FALSE, NULL, TRUE};
 use crate::parser::Parser;
+use std::env;
+use std::fs;
 use std::rc::Rc;
 
 #[test]
@@ -37,6 +41,21 @@ fn test_eval_boolean_expr() {
         ("(1 < 2) == false", false),
         ("(1 > 2) == true", false),
         ("(1 > 2) == false", true),
+        ("1 <= 2", true),
+        ("2 <= 2", true),
+        ("3 <= 2", false),
+        ("1 >= 2", false),
+        ("2 >= 2", true),
+        ("1.5 >= 1", true),
+        ("18446744073709551616 >= 1", true),
+        ("true && true", true),
+        ("true && false", false),
+        ("false || true", true),
+        ("false || false", false),
+        ("1 && 0", true),
+        ("1 < 2 && 2 < 3", true),
+        ("false && true || true", true),
+        ("true || true && false", true),
     ];
 
     for (inp, expected) in cases {
@@ -44,6 +63,27 @@ fn test_eval_boolean_expr() {
     }
 }
 
+#[test]
+fn test_eval_logical_short_circuit() {
+    let cases = vec![
+        ("false && missing", false),
+        ("true || missing", true),
+        ("false && 1 / 0", false),
+        ("let a = [1]; false && push(a, 2); len(a) == 1", true),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected.into(), inp);
+    }
+
+    test_eval_error(
+        Error::IdentifierNotFound {
+            name: "missing".to_string(),
+        },
+        "true && missing",
+    );
+}
+
 #[test]
 fn test_eval_prefix_expr() {
     let cases = vec![
@@ -76,6 +116,9 @@ fn test_eval_integer_expr() {
         ("3 * 3 * 3 + 10", 37),
         ("3 * (3 * 3) + 10", 37),
         ("(5 + 10 * 2 + 15 / 3) * 2 + -10", 50),
+        ("7 % 3", 1),
+        ("-7 % 3", -1),
+        ("1 + 7 % 4 * 2", 7),
     ];
 
     for (inp, expected) in cases {
@@ -87,15 +130,74 @@ fn test_eval_integer_expr() {
 fn test_eval_string_expr() {
     let cases = vec![
         (r#""foo""#, Object::Str("foo".to_string())),
-        (r#""foo" + "bar"#, Object::Str("foobar".to_string())),
-        (r#""foo" == "foo"#, Object::Boolean(true)),
-        (r#""foo" == "bar"#, Object::Boolean(false)),
+        (r#""foo" + "bar""#, Object::Str("foobar".to_string())),
+        (r#""foo" == "foo""#, Object::Boolean(true)),
+        (r#""foo" == "bar""#, Object::Boolean(false)),
+        (r#""a\tb" + r"\t""#, Object::Str("a\tb\\t".to_string())),
+        (
+            r#"let a = 1; let b = 2; "total: ${a + b}""#,
+            Object::from("total: 3"),
+        ),
+        (
+            r#""${"a"} ${[1, "b"]} ${1.0} ${if (false) { 1 }} ${true}""#,
+            Object::from("a [1, b] 1.0 null true"),
+        ),
+        (r#""${"${1}" + "2"}""#, Object::from("12")),
     ];
     for (inp, expected) in cases {
         test_eval(expected, inp);
     }
 }
 
+#[test]
+fn test_eval_float_expr() {
+    let cases = vec![
+        ("1.5", 1.5),
+        ("-1.5", -1.5),
+        ("1.5 + 1.5", 3.0),
+        ("1 + 0.5", 1.5),
+        ("0.5 + 1", 1.5),
+        ("3 - 0.5", 2.5),
+        ("2 * 1.25", 2.5),
+        ("1 / 4.0", 0.25),
+        ("18446744073709551616 * 0.5", 9223372036854775808.0),
+        ("1e3 / 10", 100.0),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(Object::Float(expected), inp);
+    }
+
+    let cases = vec![
+        ("1.5 < 2", true),
+        ("2 > 1.5", true),
+        ("1.5 > 18446744073709551616", false),
+        ("1 == 1.0", true),
+        ("1.0 == 1", true),
+        ("1.5 == 1", false),
+        ("1.5 != 1", true),
+        ("!1.5", false),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected.into(), inp);
+    }
+
+    let cases = vec![
+        ("1.0", "1.0"),
+        ("0.1 + 0.2", "0.30000000000000004"),
+        ("1e20", "1e20"),
+        ("-2.5", "-2.5"),
+    ];
+
+    for (inp, expected) in cases {
+        match eval(inp) {
+            Ok(received) => assert_eq!(expected, format!("{}", received)),
+            Err(received) => panic!("Received {:?} was not expected", received),
+        }
+    }
+}
+
 #[test]
 fn test_eval_if_else_expr() {
     let cases = vec![
@@ -140,6 +242,26 @@ fn test_eval_return_expr() {
     }
 }
 
+#[test]
+fn test_eval_return_from_fn() {
+    let cases = vec![
+        ("let f = fn() { return 1; 2 }; f()", 1),
+        (
+            "let f = fn() { return 1; }; let g = fn() { f(); 2 }; g()",
+            2,
+        ),
+        ("let f = fn() { return 1; }; f() + f()", 2),
+        (
+            "let f = fn(x) { if (x) { return 1; } 2 }; [f(true), f(false)][1]",
+            2,
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected.into(), inp);
+    }
+}
+
 #[test]
 fn test_error_expr() {
     let cases = vec![
@@ -213,6 +335,296 @@ fn test_error_expr() {
     }
 }
 
+#[test]
+fn test_arithmetic_error_expr() {
+    let cases = vec![
+        ("1 / 0", object::Error::ZeroDivision),
+        ("let a = 0; 10 / (a * 2)", object::Error::ZeroDivision),
+        ("18446744073709551616 / 0", object::Error::ZeroDivision),
+        ("1.5 / 0", object::Error::ZeroDivision),
+        ("1 / 0.0", object::Error::ZeroDivision),
+        ("1 % 0", object::Error::ZeroDivision),
+        ("1.5 % 0", object::Error::ZeroDivision),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval_error(expected.into(), inp);
+    }
+}
+
+#[test]
+fn test_eval_big_integer_expr() {
+    let cases = vec![
+        ("9223372036854775807 + 1", "9223372036854775808"),
+        ("-9223372036854775807 - 2", "-9223372036854775809"),
+        ("4611686018427387904 * 2", "9223372036854775808"),
+        ("(-9223372036854775807 - 1) / -1", "9223372036854775808"),
+        ("-(-9223372036854775807 - 1)", "9223372036854775808"),
+        ("18446744073709551616", "18446744073709551616"),
+        ("-18446744073709551616", "-18446744073709551616"),
+        (
+            "18446744073709551616 * 18446744073709551616 / 18446744073709551616",
+            "18446744073709551616",
+        ),
+        (
+            "
+let factorial = fn(n) {
+    if (n == 0) {
+        1
+    } else {
+        n * factorial(n - 1)
+    }
+};
+
+factorial(30)
+            ",
+            "265252859812191058636308480000000",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(Object::BigInt(expected.parse().unwrap()), inp);
+    }
+
+    // Results that fit are demoted back to `Object::Integer`.
+    let cases = vec![
+        ("9223372036854775808 - 1", 9223372036854775807),
+        ("18446744073709551616 / 18446744073709551616", 1),
+        ("-9223372036854775807 - 1", -9223372036854775807 - 1),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(Object::Integer(expected), inp);
+    }
+
+    let cases = vec![
+        ("18446744073709551616 > 1", true),
+        ("18446744073709551616 < 1", false),
+        ("-18446744073709551616 < 1", true),
+        ("18446744073709551616 == 18446744073709551616", true),
+        ("18446744073709551616 == 1", false),
+        ("18446744073709551616 != 1", true),
+        ("!18446744073709551616", false),
+        // Integers too large for a float to hold exactly are still compared exactly.
+        ("9007199254740993 == 9007199254740992", false),
+        ("9007199254740993 > 9007199254740992", true),
+        ("18446744073709551617 == 18446744073709551616", false),
+        ("18446744073709551617 != 18446744073709551616", true),
+        ("18446744073709551616 < 18446744073709551617", true),
+        ("9007199254740993 == 9007199254740993", true),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected.into(), inp);
+    }
+}
+
+#[test]
+fn test_assign_expr() {
+    let cases = vec![
+        ("let a = 1; a = 2; a", Object::from(2)),
+        ("let a = 1; a = a + 1", Object::from(2)),
+        ("let a = 1; let b = 1; a = b = 5; a + b", Object::from(10)),
+        (
+            "let a = 10; a += 5; a -= 1; a *= 2; a /= 4; a %= 4",
+            Object::from(3),
+        ),
+        ("let s = \"a\"; s += \"b\"; s", Object::from("ab")),
+        ("let a = 1; if (true) { a = 2 }; a", Object::from(2)),
+        // A binding which shadows a built-in can be assigned to.
+        ("let len = 1; len = 2; len", Object::from(2)),
+        (
+            "let xs = [1, 2, 3]; xs[0] = 5; xs",
+            Object::List(vec![5.into(), 2.into(), 3.into()]),
+        ),
+        (
+            "let xs = [1, [2, 3]]; xs[1][0] += 10; xs",
+            Object::List(vec![1.into(), Object::List(vec![12.into(), 3.into()])]),
+        ),
+        // Lists are values, so other bindings are not affected.
+        (
+            "let xs = [1]; let ys = xs; xs[0] = 2; ys",
+            Object::List(vec![1.into()]),
+        ),
+        ("let xs = [1]; xs[0] = 2", Object::from(2)),
+        // Functions update the binding in the env they were defined in.
+        (
+            "let count = 0; let inc = fn() { count += 1 }; inc(); inc(); count",
+            Object::from(2),
+        ),
+        // Parameters shadow the outer binding.
+        (
+            "let a = 1; let f = fn(a) { a = 5 }; f(2); a",
+            Object::from(1),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+
+#[test]
+fn test_assign_expr_error() {
+    let cases = vec![
+        (
+            "b = 1",
+            Error::IdentifierNotFound {
+                name: "b".to_string(),
+            },
+        ),
+        (
+            "let f = fn() { b = 1 }; f()",
+            Error::IdentifierNotFound {
+                name: "b".to_string(),
+            },
+        ),
+        (
+            "len = 3",
+            Error::IdentifierNotFound {
+                name: "len".to_string(),
+            },
+        ),
+        (
+            "let f = fn() { len += 1 }; f()",
+            Error::IdentifierNotFound {
+                name: "len".to_string(),
+            },
+        ),
+        (
+            "let xs = [1]; xs[1] = 2",
+            object::Error::IndexOutOfRange.into(),
+        ),
+        (
+            "let xs = [1]; xs[-1] = 2",
+            object::Error::NegativeIndex {
+                object: Object::List(vec![1.into()]),
+            }
+            .into(),
+        ),
+        (
+            "let xs = [1]; xs[true] = 2",
+            object::Error::IndexType {
+                object: Object::List(vec![1.into()]),
+                index: TRUE,
+            }
+            .into(),
+        ),
+        (
+            "let s = \"ab\"; s[0] = \"c\"",
+            object::Error::ItemAssignment {
+                object: Object::from("ab"),
+            }
+            .into(),
+        ),
+        (
+            "let a = 1; a += true",
+            object::Error::TypeMismatch {
+                operator: Operator::Plus,
+                left: 1.into(),
+                right: TRUE,
+            }
+            .into(),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval_error(expected, inp);
+    }
+}
+
+#[test]
+fn test_let_pattern() {
+    let cases = vec![
+        ("let [a, b] = [1, 2]; a - b", Object::from(-1)),
+        ("let [a, [b, c]] = [1, [2, 3]]; a + b + c", Object::from(6)),
+        (
+            "let [a, ...rest] = [1, 2, 3]; rest",
+            Object::List(vec![2.into(), 3.into()]),
+        ),
+        ("let [a, b, ...rest] = [1, 2]; rest", Object::List(vec![])),
+        ("let [] = []; 1", Object::from(1)),
+        (
+            "let f = fn([a, b], c) { a + b + c }; f([1, 2], 3)",
+            Object::from(6),
+        ),
+        (
+            "let tail = fn([_, ...xs]) { xs }; tail([1, 2, 3])",
+            Object::List(vec![2.into(), 3.into()]),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+
+#[test]
+fn test_let_pattern_error() {
+    let identifier = |name: &str| Pattern::Identifier(name.to_string());
+
+    let cases = vec![
+        (
+            "let [a, b] = [1];",
+            Pattern::List {
+                elements: vec![identifier("a"), identifier("b")],
+                rest: None,
+            },
+            Object::List(vec![1.into()]),
+        ),
+        (
+            "let [a] = [1, 2];",
+            Pattern::List {
+                elements: vec![identifier("a")],
+                rest: None,
+            },
+            Object::List(vec![1.into(), 2.into()]),
+        ),
+        (
+            "let [a, ...rest] = [];",
+            Pattern::List {
+                elements: vec![identifier("a")],
+                rest: Some("rest".to_string()),
+            },
+            Object::List(vec![]),
+        ),
+        (
+            "let [a, [b]] = [1, 2];",
+            Pattern::List {
+                elements: vec![identifier("b")],
+                rest: None,
+            },
+            Object::from(2),
+        ),
+        (
+            "let f = fn([a]) { a }; f(1)",
+            Pattern::List {
+                elements: vec![identifier("a")],
+                rest: None,
+            },
+            Object::from(1),
+        ),
+    ];
+
+    for (inp, pattern, value) in cases {
+        test_eval_error(Error::PatternMismatch { pattern, value }, inp);
+    }
+
+    assert_eq!(
+        "ValueError: cannot match list '[1]' against pattern [a, b]",
+        format!(
+            "{}",
+            Error::PatternMismatch {
+                pattern: Pattern::List {
+                    elements: vec![identifier("a"), identifier("b")],
+                    rest: None,
+                },
+                value: Object::List(vec![1.into()]),
+            }
+        )
+    );
+}
+
 #[test]
 fn test_let_expr() {
     let cases = vec![
@@ -232,6 +644,21 @@ fn test_let_expr() {
     }
 }
 
+#[test]
+fn test_comments() {
+    let inp = "
+// Doubles the input.
+let double = fn(x) {
+    /* multiply /* by two */ */
+    x * 2 // result
+};
+
+double(2) / 2 // end
+    ";
+
+    test_eval(2.into(), inp);
+}
+
 #[test]
 fn test_let_expr_error() {
     let cases = vec![(
@@ -293,7 +720,10 @@ fn test_fn_object() {
     let cases = vec![(
         "fn(x, y) { x + y }",
         Object::Function(Function {
-            params: Rc::new(vec!["x".to_string(), "y".to_string()]),
+            params: Rc::new(vec![
+                Param::Required(Pattern::Identifier("x".to_string())),
+                Param::Required(Pattern::Identifier("y".to_string())),
+            ]),
             body: Rc::new(vec![Statement::Expression(Expression::Infix {
                 operator: Operator::Plus,
                 left: Box::new(Expression::Identifier("x".to_string())),
@@ -370,67 +800,952 @@ fn test_enclosing_env() {
 }
 
 #[test]
-fn test_closures() {
-    let inp = "
-        let newAdder = fn(x) {
-          fn(y) { x + y };
-        };
-
-        let addTwo = newAdder(2);
-        addTwo(3);";
-
-    let expected = 5.into();
-    test_eval(expected, inp);
-}
-
-#[test]
-fn test_object_function_display() {
-    let inp = "let a = fn(x, y) {
-        let b = x + y;
-        c
-    };
-
-    a
-    ";
-
-    let expected = "fn(x, y) {
-    let b = (x + y);
-    c;
-}";
+fn test_fn_default_params() {
+    let cases = vec![
+        ("let f = fn(x, y = 10) { x + y }; f(1)", Object::from(11)),
+        ("let f = fn(x, y = 10) { x + y }; f(1, 2)", Object::from(3)),
+        ("let f = fn(x, y = x * 2) { y }; f(4)", Object::from(8)),
+        (
+            "let f = fn(x, ...rest) { rest }; f(1, 2, 3)",
+            Object::List(vec![2.into(), 3.into()]),
+        ),
+        ("let f = fn(...rest) { rest }; f()", Object::List(vec![])),
+        (
+            "let f = fn(x = 1, ...rest) { [x, rest] }; f()",
+            Object::List(vec![1.into(), Object::List(vec![])]),
+        ),
+    ];
 
-    match eval(inp) {
-        Err(received) => panic!("Received {:?} was not expected", received),
-        Ok(received) => assert_eq!(expected, format!("{}", received)),
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
     }
 }
 
 #[test]
-fn test_eval_builtin_expr() {
+fn test_fn_wrong_num_args_error() {
     let cases = vec![
-        (r#"len("")"#, Object::Integer(0)),
-        (r#"len("four")"#, Object::Integer(4)),
-        (r#"len("hello world")"#, Object::Integer(11)),
-        ("len([1,2])", Object::Integer(2)),
         (
-            "push([0], 3)",
-            Object::List(vec![Object::Integer(0), Object::Integer(3)]),
+            "let f = fn(x) { x }; f()",
+            "TypeError: function takes 1 positional argument but 0 were given",
+        ),
+        (
+            "let f = fn(x, y) { x }; f(1)",
+            "TypeError: function takes 2 positional arguments but 1 was given",
+        ),
+        (
+            "let f = fn(x, y = 1) { x }; f(1, 2, 3)",
+            "TypeError: function takes from 1 to 2 positional arguments but 3 were given",
+        ),
+        (
+            "let f = fn(x, y, ...rest) { x }; f(1)",
+            "TypeError: function takes at least 2 positional arguments but 1 was given",
         ),
-        ("rest([1,2])", Object::List(vec![Object::Integer(2)])),
-        ("rest([])", Object::Null),
-        ("rest(rest([1]))", Object::Null),
     ];
 
     for (inp, expected) in cases {
-        test_eval(expected, inp);
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
     }
 }
+
 #[test]
-fn test_eval_builtin_print() {
+fn test_match_expr() {
     let cases = vec![
-        ("print(1)", Ok(Object::Null), vec!["1"]),
-        ("print(1, 2); print(3)", Ok(Object::Null), vec!["1 2", "3"]),
         (
-            "
+            r#"match (2) { 1 => "one", 2 => "two", _ => "many" }"#,
+            Object::from("two"),
+        ),
+        (
+            r#"match (5) { 1 => "one", 2 => "two", _ => "many" }"#,
+            Object::from("many"),
+        ),
+        ("match (-1) { -1 => true, _ => false }", TRUE),
+        (r#"match ("a") { "a" => 1, _ => 2 }"#, Object::from(1)),
+        ("match (1.0) { 1 => true, _ => false }", TRUE),
+        (
+            "match ([1, 2, 3]) { [] => 0, [x, ...rest] => rest }",
+            Object::List(vec![2.into(), 3.into()]),
+        ),
+        (
+            "match ([1, [2]]) { [1, [3]] => 1, [1, [y]] => y }",
+            Object::from(2),
+        ),
+        (
+            "match (5) { n if n > 10 => 1, n if n > 1 => 2, _ => 3 }",
+            Object::from(2),
+        ),
+        ("let n = 1; match (2) { n => n }; n", Object::from(1)),
+        (
+            "let f = fn(xs) { match (xs) { [] => 0, [x, ...rest] => x + f(rest) } }; f([1, 2, 3])",
+            Object::from(6),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+
+#[test]
+fn test_match_expr_error() {
+    test_eval_error(
+        Error::NonExhaustiveMatch {
+            value: Object::List(vec![1.into()]),
+        },
+        "match ([1]) { [] => 0, [x] if x > 1 => x }",
+    );
+
+    assert_eq!(
+        "ValueError: no match arm for int '3'",
+        format!("{}", Error::NonExhaustiveMatch { value: 3.into() })
+    );
+}
+
+#[test]
+fn test_import() {
+    let modules = vec![
+        (
+            "lib/util.mk",
+            r#"
+import "./math.mk" as math;
+import "../config.mk" as config;
+let secret = config.secret;
+export let helper = fn(x) { math.double(x) + secret };
+export let [first, ...others] = [1, 2, 3];
+"#,
+        ),
+        (
+            "lib/math.mk",
+            "export let double = fn(x) { x * 2 }; let hidden = 1;",
+        ),
+        ("lib/print.mk", r#"print("loaded"); export let a = 1;"#),
+        ("config.mk", "export let secret = 10;"),
+    ];
+
+    let cases = vec![
+        (
+            r#"import "lib/util.mk" as util; util.helper(1)"#,
+            Object::from(12),
+        ),
+        (
+            r#"import "lib/util.mk" as util; util.others"#,
+            Object::List(vec![2.into(), 3.into()]),
+        ),
+        (
+            r#"import "lib/math.mk" as m; let f = m.double; f(4)"#,
+            Object::from(8),
+        ),
+        // Modules don't see the bindings of the program importing them.
+        (
+            r#"let secret = 1; import "lib/util.mk" as util; util.helper(0)"#,
+            Object::from(10),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        assert_eq!(Ok(expected), eval_with_modules(&modules, inp).0);
+    }
+
+    // Modules are only evaluated once, however their path is written.
+    let (result, stdout) = eval_with_modules(
+        &modules,
+        r#"import "lib/print.mk" as a; import "./lib/../lib/print.mk" as b; a.a + b.a"#,
+    );
+    assert_eq!(Ok(Object::from(2)), result);
+    assert_eq!(vec!["loaded".to_string()], stdout);
+}
+
+#[test]
+fn test_import_error() {
+    let modules = vec![
+        ("a.mk", r#"import "b.mk" as b; export let a = 1;"#),
+        ("b.mk", r#"import "a.mk" as a; export let b = 1;"#),
+        ("self.mk", r#"import "self.mk" as me;"#),
+        ("broken.mk", "let = 1;"),
+        ("fails.mk", "export let a = 1 / 0;"),
+        ("ok.mk", "let hidden = 1;"),
+    ];
+
+    let cases =
+        vec![
+        (
+            r#"import "a.mk" as a;"#,
+            "ImportError: circular import a.mk -> b.mk -> a.mk",
+        ),
+        (
+            r#"import "self.mk" as me;"#,
+            "ImportError: circular import self.mk -> self.mk",
+        ),
+        (
+            r#"import "missing.mk" as m;"#,
+            "ImportError: cannot import 'missing.mk': no module named 'missing.mk'",
+        ),
+        (
+            r#"import "broken.mk" as m;"#,
+            "ImportError: cannot import 'broken.mk': Error: Expected identifier but received '='",
+        ),
+        (r#"import "fails.mk" as m;"#, "ZeroDivisionError: division by zero"),
+        (
+            r#"import "ok.mk" as m; m.hidden"#,
+            "AttributeError: module 'ok.mk' has no export 'hidden'",
+        ),
+        ("let a = 1; a.b", "AttributeError: 'int' object has no attribute 'b'"),
+        (
+            r#"import "/etc/hostname" as m;"#,
+            "ImportError: cannot import '/etc/hostname': '/etc/hostname' must be a relative path",
+        ),
+        (
+            r#"import "lib/../../a.mk" as m;"#,
+            "ImportError: cannot import 'lib/../../a.mk': 'lib/../../a.mk' is outside of the module root",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        match eval_with_modules(&modules, inp).0 {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_try_catch() {
+    let cases = vec![
+        ("try { 1 } catch (e) { 2 }", Object::from(1)),
+        (
+            r#"try { throw Error("oops"); 1 } catch (e) { e.message }"#,
+            Object::from("oops"),
+        ),
+        (
+            "try { 1 / 0 } catch (e) { e.kind }",
+            Object::from("ZeroDivisionError"),
+        ),
+        (
+            "try { missing } catch (e) { e.message }",
+            Object::from("name 'missing' is not defined"),
+        ),
+        (
+            "try { [1] + 1 } catch (e) { e.kind }",
+            Object::from("TypeError"),
+        ),
+        (
+            "try { match (1) { 2 => 2 } } catch (e) { e.kind }",
+            Object::from("ValueError"),
+        ),
+        (
+            "try { throw 1 } catch (e) { e.message }",
+            Object::from("exceptions must be errors, not int"),
+        ),
+        // Errors from called functions are caught, and returns pass through.
+        (
+            r#"let f = fn() { throw Error("inner") }; try { f() } catch (e) { e.message }"#,
+            Object::from("inner"),
+        ),
+        (
+            "let f = fn() { try { return 1; } catch (e) { 2 }; 3 }; f()",
+            Object::from(1),
+        ),
+        // Rethrowing from the handler reaches the outer handler.
+        (
+            r#"try { try { 1 / 0 } catch (e) { throw Error(e.kind) } } catch (e) { e.message }"#,
+            Object::from("ZeroDivisionError"),
+        ),
+        (
+            "let e = 1; try { 1 / 0 } catch (e) { 2 }; e",
+            Object::from(1),
+        ),
+        (
+            "let x = 1; try { let x = 2; 1 / 0 } catch (e) { x }",
+            Object::from(1),
+        ),
+        (
+            r#"let e = Error("a"); [e.kind, e.message]"#,
+            Object::List(vec!["Error".into(), "a".into()]),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+
+#[test]
+fn test_throw_error() {
+    let cases = vec![
+        (r#"throw Error("oops")"#, "Error: oops"),
+        ("throw 1", "TypeError: exceptions must be errors, not int"),
+        (
+            r#"try { 1 } catch (e) { 2 }; throw Error("after")"#,
+            "Error: after",
+        ),
+        (
+            r#"Error("a").b"#,
+            "AttributeError: 'error' object has no attribute 'b'",
+        ),
+        (
+            "Error()",
+            "TypeError: Error() takes exactly one argument (0 given)",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_closures() {
+    let inp = "
+        let newAdder = fn(x) {
+          fn(y) { x + y };
+        };
+
+        let addTwo = newAdder(2);
+        addTwo(3);";
+
+    let expected = 5.into();
+    test_eval(expected, inp);
+}
+
+#[test]
+fn test_object_function_display() {
+    let inp = "let a = fn(x, y) {
+        let b = x + y;
+        c
+    };
+
+    a
+    ";
+
+    let expected = "fn(x, y) {
+    let b = (x + y);
+    c;
+}";
+
+    match eval(inp) {
+        Err(received) => panic!("Received {:?} was not expected", received),
+        Ok(received) => assert_eq!(expected, format!("{}", received)),
+    }
+}
+
+#[test]
+fn test_eval_builtin_expr() {
+    let cases = vec![
+        (r#"len("")"#, Object::Integer(0)),
+        (r#"len("four")"#, Object::Integer(4)),
+        (r#"len("hello world")"#, Object::Integer(11)),
+        ("len([1,2])", Object::Integer(2)),
+        (
+            "push([0], 3)",
+            Object::List(vec![Object::Integer(0), Object::Integer(3)]),
+        ),
+        ("rest([1,2])", Object::List(vec![Object::Integer(2)])),
+        ("rest([])", Object::Null),
+        ("rest(rest([1]))", Object::Null),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+#[test]
+fn test_eval_builtin_list() {
+    let list = |vals: Vec<isize>| Object::List(vals.into_iter().map(Object::from).collect());
+
+    let cases = vec![
+        ("first([1, 2])", Object::from(1)),
+        ("last([1, 2])", Object::from(2)),
+        ("first([])", Object::Null),
+        ("map([1, 2, 3], fn(x) { x * 2 })", list(vec![2, 4, 6])),
+        ("map([1, 2], fn(x) { return x + 1; })", list(vec![2, 3])),
+        ("map([-1, 2], int)", list(vec![-1, 2])),
+        (
+            "filter([1, 2, 3, 4], fn(x) { x % 2 == 0 })",
+            list(vec![2, 4]),
+        ),
+        ("reduce([1, 2, 3], fn(acc, x) { acc + x })", Object::from(6)),
+        ("reduce([], fn(acc, x) { acc + x }, 10)", Object::from(10)),
+        ("range(3)", list(vec![0, 1, 2])),
+        ("range(1, 4)", list(vec![1, 2, 3])),
+        ("range(5, 0, -2)", list(vec![5, 3, 1])),
+        ("range(3, 1)", list(vec![])),
+        ("len(range(2500))", Object::from(2500)),
+        ("reverse([1, 2, 3])", list(vec![3, 2, 1])),
+        ("sort([3, 1, 2])", list(vec![1, 2, 3])),
+        (
+            r#"sort(["b", "c", "a"])"#,
+            Object::List(vec!["a".into(), "b".into(), "c".into()]),
+        ),
+        ("sort([3, 1, 2], fn(a, b) { b - a })", list(vec![3, 2, 1])),
+        // The sort is stable.
+        (
+            "map(sort([[1, 0], [0, 1], [1, 2], [0, 3]], fn(a, b) { a[0] - b[0] }), fn(p) { p[1] })",
+            list(vec![1, 3, 0, 2]),
+        ),
+        // A comparator which isn't consistent gives some order rather than failing.
+        (
+            "let n = 0; len(sort(range(50), fn(a, b) { n += 1; (n * 7919) % 3 - 1 }))",
+            Object::from(50),
+        ),
+        ("contains([1, 2], 2)", TRUE),
+        ("contains([1, 2], 2.0)", TRUE),
+        ("contains([[1]], [1])", TRUE),
+        ("contains([1, 2], 3)", FALSE),
+        (
+            "zip([1, 2, 3], [4, 5])",
+            Object::List(vec![list(vec![1, 4]), list(vec![2, 5])]),
+        ),
+        (
+            "flatten([[1, 2], 3, [[4]]])",
+            Object::List(vec![1.into(), 2.into(), 3.into(), list(vec![4])]),
+        ),
+        ("slice([1, 2, 3, 4], 1)", list(vec![2, 3, 4])),
+        ("slice([1, 2, 3, 4], 1, 3)", list(vec![2, 3])),
+        ("slice([1, 2, 3, 4], -2)", list(vec![3, 4])),
+        ("slice([1, 2, 3, 4], 3, 1)", list(vec![])),
+        ("slice([1, 2], 0, 10)", list(vec![1, 2])),
+        // Higher-order built-ins can be passed around like other functions.
+        (
+            "let apply = fn(f, x) { f(x, fn(x) { -x }) }; apply(map, [1])",
+            list(vec![-1]),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+
+#[test]
+fn test_eval_builtin_list_error() {
+    let cases = vec![
+        (
+            "first(1)",
+            "TypeError: first() argument must be a list, not 'int'",
+        ),
+        (
+            "first([], [])",
+            "TypeError: first() takes exactly one argument (2 given)",
+        ),
+        (
+            "map(1, fn(x) { x })",
+            "TypeError: map() argument must be a list, not 'int'",
+        ),
+        (
+            "map([1])",
+            "TypeError: map() takes exactly two arguments (1 given)",
+        ),
+        ("map([1], 1)", "TypeError: 'int' object is not callable"),
+        (
+            "map([1], fn() { 1 })",
+            "TypeError: function takes 0 positional arguments but 1 was given",
+        ),
+        (
+            "filter([1], fn(x) { x / 0 })",
+            "ZeroDivisionError: division by zero",
+        ),
+        (
+            "zip([1], 2)",
+            "TypeError: zip() argument must be a list, not 'int'",
+        ),
+        (
+            "reduce([], fn(acc, x) { acc })",
+            "TypeError: reduce() of empty list with no initial value",
+        ),
+        (
+            "reduce([1])",
+            "TypeError: reduce() takes from 2 to 3 arguments (1 given)",
+        ),
+        (
+            "range()",
+            "TypeError: range() takes from 1 to 3 arguments (0 given)",
+        ),
+        (
+            "range(1, 2, 0)",
+            "ValueError: range() arg 3 must not be zero",
+        ),
+        (
+            "range(1.5)",
+            "TypeError: range() argument must be an integer, not 'float'",
+        ),
+        (
+            "range(18446744073709551616)",
+            "ValueError: range() argument 18446744073709551616 is too large",
+        ),
+        (
+            "slice([1], \"a\")",
+            "TypeError: slice() argument must be an integer, not 'string'",
+        ),
+        (
+            "sort([1, \"a\"])",
+            "TypeError: unsupported operand type(s) for <: 'string' and 'int'",
+        ),
+        (
+            "sort([1, 2], fn(a, b) { true })",
+            "TypeError: sort() comparator must return a number, not 'bool'",
+        ),
+        // The first error of the comparator ends the sort.
+        (
+            "let n = 0; sort(range(50), fn(a, b) { n += 1; if (n == 20) { throw Error(\"bad\") }; a - b })",
+            "Error: bad",
+        ),
+        (
+            "sort(1, 2, 3)",
+            "TypeError: sort() takes from 1 to 2 arguments (3 given)",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_eval_builtin_string() {
+    let strings = |vals: Vec<&str>| Object::List(vals.into_iter().map(Object::from).collect());
+
+    let cases = vec![
+        (r#"len("héllo")"#, Object::from(5)),
+        (r#"len("日本")"#, Object::from(2)),
+        (r#""héllo"[1]"#, Object::from("é")),
+        (r#""héllo"[5]"#, Object::Null),
+        (r#"slice("héllo", 1, 3)"#, Object::from("él")),
+        (r#"slice("héllo", -2)"#, Object::from("lo")),
+        (r#"split(" a  b ")"#, strings(vec!["a", "b"])),
+        (r#"split("a,b,,c", ",")"#, strings(vec!["a", "b", "", "c"])),
+        (r#"join(["a", "b", "c"], ", ")"#, Object::from("a, b, c")),
+        (r#"join([], "-")"#, Object::from("")),
+        (r#"trim("  a b  ")"#, Object::from("a b")),
+        (r#"upper("straße")"#, Object::from("STRASSE")),
+        (r#"lower("ÀB")"#, Object::from("àb")),
+        (r#"replace("a-b-c", "-", "+")"#, Object::from("a+b+c")),
+        (r#"starts_with("monkey", "mon")"#, TRUE),
+        (r#"ends_with("monkey", "mon")"#, FALSE),
+        (r#"find("héllo", "l")"#, Object::from(2)),
+        (r#"find("hello", "z")"#, Object::from(-1)),
+        (r#"chars("añb")"#, strings(vec!["a", "ñ", "b"])),
+        (r#"repeat("ab", 3)"#, Object::from("ababab")),
+        (r#"repeat("ab", -1)"#, Object::from("")),
+        (
+            r#"format("{} + {} = {}", 1, 2.5, [3])"#,
+            Object::from("1 + 2.5 = [3]"),
+        ),
+        (r#"format("{{}} {}", "a")"#, Object::from("{} a")),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+}
+
+#[test]
+fn test_eval_builtin_string_error() {
+    let cases = vec![
+        (
+            r#""abc"[-1]"#,
+            "TypeError: string indices must not be negative",
+        ),
+        (
+            r#""abc"["a"]"#,
+            "TypeError: string indices must be integers, not string",
+        ),
+        (
+            "upper(1)",
+            "TypeError: upper() argument must be a string, not 'int'",
+        ),
+        (
+            r#"replace("a", "b", 1)"#,
+            "TypeError: replace() argument must be a string, not 'int'",
+        ),
+        (r#"split("a", "")"#, "ValueError: empty separator"),
+        (
+            r#"split("a", ",", 1)"#,
+            "TypeError: split() takes from 1 to 2 arguments (3 given)",
+        ),
+        (
+            r#"join("ab", "")"#,
+            "TypeError: join() argument must be a list, not 'string'",
+        ),
+        (
+            "join([1], \"\")",
+            "TypeError: join() list elements must be strings, not 'int'",
+        ),
+        (
+            "join([], 1)",
+            "TypeError: join() argument must be a string, not 'int'",
+        ),
+        (
+            r#"repeat("a", "b")"#,
+            "TypeError: repeat() argument must be an integer, not 'string'",
+        ),
+        (
+            "format()",
+            "TypeError: format() takes at least one argument (0 given)",
+        ),
+        (
+            r#"format("{} {}", 1)"#,
+            "ValueError: format() got fewer arguments than placeholders",
+        ),
+        (
+            r#"format("{", 1)"#,
+            "ValueError: format() got an unmatched '{' in the template",
+        ),
+        (
+            "slice(1, 2)",
+            "TypeError: slice() argument must be a list or a string, not 'int'",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_eval_builtin_number_conversion() {
+    let cases = vec![
+        ("int(1)", Object::Integer(1)),
+        ("int(1.9)", Object::Integer(1)),
+        ("int(-1.9)", Object::Integer(-1)),
+        ("int(true)", Object::Integer(1)),
+        (r#"int(" 42 ")"#, Object::Integer(42)),
+        (r#"int("-42")"#, Object::Integer(-42)),
+        (
+            "int(1e20)",
+            Object::BigInt("100000000000000000000".parse().unwrap()),
+        ),
+        (
+            r#"int("18446744073709551616")"#,
+            Object::BigInt("18446744073709551616".parse().unwrap()),
+        ),
+        ("float(1)", Object::Float(1.0)),
+        ("float(1.5)", Object::Float(1.5)),
+        ("float(false)", Object::Float(0.0)),
+        (r#"float("2.5")"#, Object::Float(2.5)),
+        (r#"float("1e3")"#, Object::Float(1000.0)),
+        (
+            "float(18446744073709551616)",
+            Object::Float(18446744073709551616.0),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+
+    let cases = vec![
+        (
+            r#"int("1.5")"#,
+            Error::ValueError {
+                message: "invalid literal for int(): '1.5'".to_string(),
+            },
+        ),
+        (
+            r#"float("abc")"#,
+            Error::ValueError {
+                message: "could not convert string to float: 'abc'".to_string(),
+            },
+        ),
+        (
+            "int(float(\"inf\"))",
+            Error::ValueError {
+                message: "cannot convert float inf to integer".to_string(),
+            },
+        ),
+        (
+            "int([])",
+            Error::TypeError {
+                message: "int() argument must be a string or a number, not 'list'".to_string(),
+            },
+        ),
+        (
+            "float(1, 2)",
+            Error::TypeError {
+                message: "float() takes exactly one argument (2 given)".to_string(),
+            },
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval_error(expected, inp);
+    }
+}
+
+#[test]
+fn test_eval_builtin_input() {
+    let cases = vec![
+        ("input()", Object::from("first")),
+        (
+            r#"let name = input("name?"); "hello " + name"#,
+            Object::from("hello first"),
+        ),
+        ("read_line() + read_line()", Object::from("firstsecond")),
+        ("read_lines(); read_line()", NULL),
+        (
+            "input(); read_lines()",
+            Object::List(vec![Object::from("second"), Object::from("")]),
+        ),
+        ("read_lines(); input()", NULL),
+        ("read_lines(); read_lines()", Object::List(vec![])),
+    ];
+
+    for (inp, expected) in cases {
+        let env = Env::new();
+        env.set_input(Box::new(Lines::new(vec!["first", "second", ""])));
+
+        let program = Parser::new(Lexer::new(inp))
+            .parse()
+            .expect("No parse errors");
+        let (env, result) = program.evaluate(env);
+        assert_eq!(Ok(expected), result);
+        // The prompt is given to the input, not printed.
+        assert!(env.pop_stdout().is_empty());
+    }
+
+    let cases = vec![
+        (
+            "input(1, 2)",
+            "TypeError: input() takes from 0 to 1 arguments (2 given)",
+        ),
+        (
+            "read_lines(1)",
+            "TypeError: read_lines() takes no arguments (1 given)",
+        ),
+        (
+            r#"read_line("a")"#,
+            "TypeError: read_line() takes no arguments (1 given)",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_eval_builtin_fs() {
+    let root = env::temp_dir().join(format!("monkey-fs-test-{}", std::process::id()));
+    fs::create_dir_all(root.join("dir")).unwrap();
+    fs::write(root.join("dir/a.txt"), "a").unwrap();
+    fs::write(root.join("m.mk"), "export let a = 1;").unwrap();
+
+    let eval_in_root = |inp: &str| {
+        let env = Env::new();
+        env.set_filesystem_root(Some(root.clone()));
+        let program = Parser::new(Lexer::new(inp))
+            .parse()
+            .expect("No parse errors");
+        program.evaluate(env).1.map_err(|err| err.to_string())
+    };
+
+    let cases = vec![
+        (r#"read_file("dir/a.txt")"#, Object::from("a")),
+        (
+            r#"write_file("dir/b.txt", "b"); read_file("./dir/b.txt")"#,
+            Object::from("b"),
+        ),
+        (
+            r#"list_dir("dir")"#,
+            Object::List(vec![Object::from("a.txt"), Object::from("b.txt")]),
+        ),
+        (r#"exists("dir/a.txt")"#, TRUE),
+        (r#"exists("missing.txt")"#, FALSE),
+        // Without a module resolver, modules are read from the root too.
+        (r#"import "./m.mk" as m; m.a"#, Object::from(1)),
+    ];
+    let results: Vec<_> = cases.iter().map(|(inp, _)| eval_in_root(inp)).collect();
+
+    let mut errors = vec![
+        (
+            r#"read_file("../a.txt")"#,
+            "PermissionError: '../a.txt' is outside of the allowed directory",
+        ),
+        (
+            r#"exists("/etc")"#,
+            "PermissionError: '/etc' is outside of the allowed directory",
+        ),
+        (
+            r#"read_file("missing.txt")"#,
+            "IOError: cannot access 'missing.txt': No such file or directory (os error 2)",
+        ),
+        (
+            r#"write_file("dir/c.txt", 1)"#,
+            "TypeError: write_file() contents must be a string, not 'int'",
+        ),
+        (
+            "list_dir(1)",
+            "TypeError: list_dir() argument must be a string, not 'int'",
+        ),
+        (
+            "exists()",
+            "TypeError: exists() takes exactly one argument (0 given)",
+        ),
+    ];
+    // A symlink can't be used to leave the root either, even one to a file which doesn't exist yet.
+    let target = env::temp_dir().join(format!("monkey-fs-test-{}.txt", std::process::id()));
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(env::temp_dir(), root.join("link")).unwrap();
+        std::os::unix::fs::symlink(&target, root.join("dangling")).unwrap();
+        errors.push((
+            r#"read_file("link/a.txt")"#,
+            "PermissionError: 'link/a.txt' is outside of the allowed directory",
+        ));
+        errors.push((
+            r#"write_file("dangling", "a")"#,
+            "PermissionError: 'dangling' is outside of the allowed directory",
+        ));
+        errors.push((
+            r#"import "link/m.mk" as m;"#,
+            "ImportError: cannot import 'link/m.mk': 'link/m.mk' is outside of the allowed directory",
+        ));
+    }
+    let error_results: Vec<_> = errors.iter().map(|(inp, _)| eval_in_root(inp)).collect();
+    fs::remove_dir_all(&root).unwrap();
+    assert!(!target.exists());
+
+    for ((_, expected), result) in cases.into_iter().zip(results) {
+        assert_eq!(Ok(expected), result);
+    }
+    for ((_, expected), result) in errors.into_iter().zip(error_results) {
+        assert_eq!(Err(expected.to_string()), result);
+    }
+
+    // Disabled by default, including imports.
+    let disabled = vec![
+        (
+            r#"exists("a.txt")"#,
+            "PermissionError: filesystem access is disabled",
+        ),
+        (
+            r#"import "m.mk" as m;"#,
+            "ImportError: cannot import 'm.mk': filesystem access is disabled",
+        ),
+    ];
+    for (inp, expected) in disabled {
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, received.to_string()),
+            Ok(received) => panic!("Expected an error, received result {:?}", received),
+        }
+    }
+}
+
+#[test]
+fn test_eval_builtin_type_conversion() {
+    let cases = vec![
+        ("type(1)", "int"),
+        ("type(18446744073709551616)", "int"),
+        ("type(1.5)", "float"),
+        (r#"type("a")"#, "string"),
+        ("type(true)", "bool"),
+        ("type(if (false) { 1 })", "null"),
+        ("type([])", "list"),
+        ("type(fn() {})", "function"),
+        ("type(len)", "builtin"),
+        (r#"type(Error("a"))"#, "error"),
+        ("type(type)", "builtin"),
+        ("str(1)", "1"),
+        ("str(1.5)", "1.5"),
+        (r#"str("a")"#, "a"),
+        (r#"str([1, "a"])"#, "[1, a]"),
+        (r#"repr("a")"#, r#""a""#),
+        (r#"repr([1, "a", ["b"]])"#, r#"[1, "a", ["b"]]"#),
+        (r#"repr("say \"hi\"\n")"#, r#""say \"hi\"\n""#),
+        (r#"repr("\${a} $a")"#, r#""\${a} $a""#),
+        ("repr(true)", "true"),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(Object::from(expected), inp);
+    }
+
+    let cases = vec![
+        ("bool(0)", TRUE),
+        (r#"bool("")"#, TRUE),
+        ("bool(false)", FALSE),
+        ("bool(if (false) { 1 })", FALSE),
+        (r#"int(str(42))"#, Object::from(42)),
+    ];
+
+    for (inp, expected) in cases {
+        test_eval(expected, inp);
+    }
+
+    let cases = vec![
+        (
+            "type()",
+            "TypeError: type() takes exactly one argument (0 given)",
+        ),
+        (
+            "str(1, 2)",
+            "TypeError: str() takes exactly one argument (2 given)",
+        ),
+        (
+            "repr()",
+            "TypeError: repr() takes exactly one argument (0 given)",
+        ),
+        (r#"int("a")"#, "ValueError: invalid literal for int(): 'a'"),
+        ("[1]()", "TypeError: 'list' object is not callable"),
+        (
+            "len()()",
+            "TypeError: len() takes exactly one argument (0 given)",
+        ),
+        (
+            "len(len)",
+            "TypeError: object of type 'builtin' has no len()",
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        match eval(inp) {
+            Err(received) => assert_eq!(expected, format!("{}", received)),
+            Ok(received) => panic!(
+                "Expected error {}, received result {:?}",
+                expected, received
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_eval_builtin_print() {
+    let cases = vec![
+        ("print(1)", Ok(Object::Null), vec!["1"]),
+        ("print(1, 2); print(3)", Ok(Object::Null), vec!["1 2", "3"]),
+        (
+            "
 let test = fn(x) {
     print(1, 2);
     print(3);
@@ -554,21 +1869,22 @@ fn test_eval_builtin_expr_error() {
         ),
         (
             r#"[]["string index"]"#,
-            Error::TypeError {
-                message: "list indices must be integers, not string".to_string(),
-            },
+            object::Error::IndexType {
+                object: Object::List(vec![]),
+                index: Object::from("string index"),
+            }
+            .into(),
         ),
         (
             r#"[][-1]"#,
-            Error::TypeError {
-                message: "list indices must be positive".to_string(),
-            },
+            object::Error::NegativeIndex {
+                object: Object::List(vec![]),
+            }
+            .into(),
         ),
         (
-            r#""some string"[1]"#,
-            Error::TypeError {
-                message: "object of type 'string' has no index".to_string(),
-            },
+            "1[0]",
+            object::Error::NotIndexable { object: 1.into() }.into(),
         ),
     ];
 
@@ -577,6 +1893,124 @@ fn test_eval_builtin_expr_error() {
     }
 }
 
+#[test]
+fn test_memory_limit() {
+    let cases = vec![
+        (
+            r#"let a = "aaaa"; a + a"#,
+            Some(16),
+            Ok(Object::Str("aaaaaaaa".to_string())),
+        ),
+        (
+            r#"let a = "aaaa"; a + a + a"#,
+            Some(16),
+            Err(object::Error::MemoryLimitExceeded { limit: 16 }.into()),
+        ),
+        (
+            "
+let grow = fn(x, n) {
+    if (n == 0) {
+        x
+    } else {
+        grow(push(x, x), n - 1)
+    }
+};
+
+len(grow([], 20))
+            ",
+            Some(1024),
+            Err(object::Error::MemoryLimitExceeded { limit: 1024 }.into()),
+        ),
+        // Running out of memory can't be caught.
+        (
+            r#"let a = "aaaa"; try { a + a + a } catch (e) { 1 }"#,
+            Some(16),
+            Err(object::Error::MemoryLimitExceeded { limit: 16 }.into()),
+        ),
+        (
+            "len(range(100))",
+            Some(1024),
+            Err(object::Error::MemoryLimitExceeded { limit: 1024 }.into()),
+        ),
+        (
+            "-340282366920938463463374607431768211456",
+            Some(16),
+            Err(object::Error::MemoryLimitExceeded { limit: 16 }.into()),
+        ),
+        (
+            r#"repeat("a", 1000000000000)"#,
+            Some(1024),
+            Err(object::Error::MemoryLimitExceeded { limit: 1024 }.into()),
+        ),
+        (
+            r#"let a = "aaaa"; a + a + a"#,
+            None,
+            Ok(Object::Str("aaaaaaaaaaaa".to_string())),
+        ),
+    ];
+
+    for (inp, limit, expected) in cases {
+        let lexer = Lexer::new(inp);
+        let parser = Parser::new(lexer);
+
+        let program = parser.parse().expect("No parse errors");
+
+        let env = Env::new();
+        env.set_memory_limit(limit);
+
+        let (_, result) = program.evaluate(env);
+        assert_eq!(expected, result);
+    }
+
+    // There is no limit by default, even when a lot is allocated over time.
+    test_eval(
+        Object::from(1000000),
+        r#"reduce(range(100), fn(acc, i) { len(repeat("x", 1000000)) }, 0)"#,
+    );
+}
+
+#[test]
+fn test_recursion_limit() {
+    let cases = vec![
+        (
+            "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(10)",
+            Ok(Object::from(10)),
+        ),
+        (
+            "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(30)",
+            Err(object::Error::RecursionLimitExceeded { limit: 20 }.into()),
+        ),
+        (
+            "let f = fn(n) { f(n + 1) }; try { f(0) } catch (e) { e.kind }",
+            Ok(Object::from("RecursionError")),
+        ),
+        // Calls through built-ins count too.
+        (
+            "let f = fn(n) { map([n], fn(x) { f(x + 1) }) }; try { f(0) } catch (e) { e.kind }",
+            Ok(Object::from("RecursionError")),
+        ),
+        // The calls which failed don't count anymore after the error is caught.
+        (
+            "let f = fn(n) { f(n + 1) }; try { f(0) } catch (e) { 0 }; \
+             let g = fn(n) { if (n == 0) { 0 } else { g(n - 1) } }; g(15)",
+            Ok(Object::from(0)),
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        let lexer = Lexer::new(inp);
+        let parser = Parser::new(lexer);
+
+        let program = parser.parse().expect("No parse errors");
+
+        let env = Env::new();
+        env.set_recursion_limit(20);
+
+        let (_, result) = program.evaluate(env);
+        assert_eq!(expected, result, "{}", inp);
+    }
+}
+
 fn test_eval(expected: Object, inp: &str) {
     match eval(inp) {
         Ok(received) => assert_eq!(expected, received),
@@ -594,6 +2028,23 @@ fn test_eval_error(expected: Error, inp: &str) {
     }
 }
 
+// Evaluates with the given modules available to import, returning the result and the stdout.
+fn eval_with_modules(modules: &[(&str, &str)], inp: &str) -> (Result<Object, Error>, Vec<String>) {
+    let mut resolver = MapResolver::new();
+    for (path, source) in modules {
+        resolver.insert(*path, *source);
+    }
+
+    let env = Env::new();
+    env.set_module_resolver(Rc::new(resolver));
+
+    let program = Parser::new(Lexer::new(inp))
+        .parse()
+        .expect("No parse errors");
+    let (env, result) = program.evaluate(env);
+    (result, env.pop_stdout())
+}
+
 fn eval(inp: &str) -> Result<Object, Error> {
     let lexer = Lexer::new(inp);
     let parser = Parser::new(lexer);
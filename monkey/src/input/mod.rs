@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::io::{BufRead, Write};
+
+#[cfg(test)]
+mod tests;
+
+// Where `input`, `read_line` and `read_lines` read from.
+pub trait Input {
+    // The next line without its line ending, or `None` at the end of the input. The prompt given to
+    // `input` should be shown to the user before waiting for the line.
+    fn read_line(&mut self, prompt: Option<&str>) -> io::Result<Option<String>>;
+}
+
+// Reads from the stdin of the process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stdin;
+
+impl Input for Stdin {
+    fn read_line(&mut self, prompt: Option<&str>) -> io::Result<Option<String>> {
+        if let Some(prompt) = prompt {
+            let mut stdout = io::stdout();
+            write!(stdout, "{}", prompt)?;
+            stdout.flush()?;
+        }
+
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let len = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(len);
+        Ok(Some(line))
+    }
+}
+
+// A fixed queue of lines, for tests and for programs which are given their input up front. This is
+// the default, with no lines. Prompts are ignored since nobody is waiting to answer them.
+#[derive(Debug, Clone, Default)]
+pub struct Lines {
+    lines: VecDeque<String>,
+}
+
+impl Lines {
+    pub fn new<S: Into<String>>(lines: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            lines: lines.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl Input for Lines {
+    fn read_line(&mut self, _prompt: Option<&str>) -> io::Result<Option<String>> {
+        Ok(self.lines.pop_front())
+    }
+}
+
+// Calls a function with the prompt for each line, e.g. to ask for it in a UI.
+pub struct Callback<F: FnMut(Option<&str>) -> Option<String>>(pub F);
+
+impl<F: FnMut(Option<&str>) -> Option<String>> Input for Callback<F> {
+    fn read_line(&mut self, prompt: Option<&str>) -> io::Result<Option<String>> {
+        Ok((self.0)(prompt))
+    }
+}
+
+impl<F: FnMut(Option<&str>) -> Option<String>> fmt::Debug for Callback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Callback")
+    }
+}
@@ -0,0 +1,26 @@
+use super::{Callback, Input, Lines};
+
+#[test]
+fn test_lines() {
+    let mut input = Lines::new(vec!["a", "b"]);
+
+    assert_eq!(Some("a".to_string()), input.read_line(None).unwrap());
+    assert_eq!(Some("b".to_string()), input.read_line(Some("?")).unwrap());
+    assert_eq!(None, input.read_line(None).unwrap());
+}
+
+#[test]
+fn test_callback() {
+    let mut count = 0;
+    let mut input = Callback(|prompt: Option<&str>| {
+        count += 1;
+        Some(format!("{}{}", prompt.unwrap_or(""), count)).filter(|_| count < 3)
+    });
+
+    assert_eq!(Some("1".to_string()), input.read_line(None).unwrap());
+    assert_eq!(
+        Some("n=2".to_string()),
+        input.read_line(Some("n=")).unwrap()
+    );
+    assert_eq!(None, input.read_line(None).unwrap());
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    // Location of the opening quote.
+    UnterminatedString { location: Location },
+    InvalidEscape { escape: String, location: Location },
+    // Location of the opening `/*`.
+    UnterminatedComment { location: Location },
+    // A character which can't start any token, e.g. `@` or `$`.
+    UnexpectedCharacter { character: char, location: Location },
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnterminatedString { location } => {
+                write!(f, "unterminated string starting at {}", location)
+            }
+            Error::InvalidEscape { escape, location } => {
+                write!(f, "invalid escape sequence {} at {}", escape, location)
+            }
+            Error::UnterminatedComment { location } => {
+                write!(f, "unterminated block comment starting at {}", location)
+            }
+            Error::UnexpectedCharacter {
+                character,
+                location,
+            } => write!(f, "unexpected character {:?} at {}", character, location),
+        }
+    }
+}
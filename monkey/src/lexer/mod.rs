@@ -1,38 +1,153 @@
-use crate::token::{Token, Token::*};
-use std::iter::Peekable;
-use std::str::Chars;
-
+mod error;
 #[cfg(test)]
 mod tests;
+mod unicode;
+
+pub use self::error::{Error, Location};
+use self::unicode::{is_xid_continue, is_xid_start};
+use crate::token::{Token, Token::*, TokenWithTrivia, Trivia};
+use std::mem;
+use std::str::Chars;
 
 pub struct Lexer<'a> {
-    iter: Peekable<Chars<'a>>,
+    iter: Source<'a>,
+    // Trivia seen since the last token.
+    trivia: Vec<Trivia>,
+    // The `${` interpolations in strings which haven't been closed yet, innermost last.
+    interpolations: Vec<Interpolation>,
+}
+
+struct Interpolation {
+    // Location of the opening quote of the string.
+    location: Location,
+    // Number of unclosed `{` in the interpolation, so that the `}` closing it can be found.
+    depth: usize,
 }
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str) -> Self {
         Lexer {
-            iter: input.chars().peekable(),
+            iter: Source::new(input),
+            trivia: Vec::new(),
+            interpolations: Vec::new(),
         }
     }
 
     pub fn next_token(&mut self) -> Option<Token> {
+        self.next_token_with_trivia()
+            .map(|TokenWithTrivia { token, .. }| token)
+    }
+
+    // Like `next_token`, but also returns the comments before the token. Comments at the end of
+    // the input are not followed by a token, so use `take_trivia()` to get them after this returns
+    // `None`.
+    pub fn next_token_with_trivia(&mut self) -> Option<TokenWithTrivia> {
+        if let Err(err) = self.skip_trivia() {
+            return Some(self.with_trivia(Token::Error(err)));
+        }
+
+        self.next_significant_token()
+            .map(|token| self.with_trivia(token))
+    }
+
+    pub fn take_trivia(&mut self) -> Vec<Trivia> {
+        mem::take(&mut self.trivia)
+    }
+
+    fn with_trivia(&mut self, token: Token) -> TokenWithTrivia {
+        TokenWithTrivia {
+            token,
+            leading_trivia: self.take_trivia(),
+        }
+    }
+
+    // Skips whitespace and comments, keeping the comments as trivia.
+    fn skip_trivia(&mut self) -> Result<(), Error> {
+        loop {
+            match (self.iter.peek(), self.iter.peek_second()) {
+                (Some(ch), _) if is_whitespace(ch) => {
+                    self.iter.next();
+                }
+                (Some('/'), Some('/')) => {
+                    let comment = self.next_line_comment();
+                    self.trivia.push(comment);
+                }
+                (Some('/'), Some('*')) => {
+                    let comment = self.next_block_comment()?;
+                    self.trivia.push(comment);
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn next_line_comment(&mut self) -> Trivia {
+        // Consume the `//`.
+        self.iter.next();
+        self.iter.next();
+
+        Trivia::LineComment(consume_while(|ch| ch != '\n', &mut self.iter))
+    }
+
+    // Block comments can be nested, e.g. `/* outer /* inner */ still outer */`.
+    fn next_block_comment(&mut self) -> Result<Trivia, Error> {
+        let location = self.iter.location;
+
+        // Consume the `/*`.
+        self.iter.next();
+        self.iter.next();
+
+        let mut depth = 1;
+        let mut comment = String::new();
+        loop {
+            match (self.iter.next(), self.iter.peek()) {
+                (Some('*'), Some('/')) => {
+                    self.iter.next();
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(Trivia::BlockComment(comment));
+                    }
+                    comment.push_str("*/");
+                }
+                (Some('/'), Some('*')) => {
+                    self.iter.next();
+                    depth += 1;
+                    comment.push_str("/*");
+                }
+                (Some(ch), _) => comment.push(ch),
+                (None, _) => return Err(Error::UnterminatedComment { location }),
+            }
+        }
+    }
+
+    fn next_significant_token(&mut self) -> Option<Token> {
+        let location = self.iter.location;
+
         match self.iter.peek() {
-            Some(&ch) => match ch {
-                ch if is_whitespace(ch) => {
+            Some(ch) => match ch {
+                '}' if self.interpolations.last().map(|i| i.depth) == Some(0) => {
                     self.iter.next();
-                    return self.next_token();
+                    let Interpolation { location, .. } = self.interpolations.pop().unwrap();
+                    Some(self.next_str_part(location, false))
                 }
                 ch if is_symbol(ch) => Some(self.next_symbol()),
-                ch if ch.is_alphabetic() => Some(self.next_identifier()),
-                ch if ch.is_digit(10) => Some(self.next_int()),
-                '"' => {
+                ch if is_identifier_start(ch) => Some(self.next_identifier()),
+                ch if ch.is_ascii_digit() => Some(self.next_number()),
+                '"' => Some(self.next_str()),
+                '.' if self.iter.starts_with("...") => {
+                    self.iter.nth(2);
+                    Some(Ellipsis)
+                }
+                '.' => {
                     self.iter.next();
-                    Some(self.next_str())
+                    Some(Dot)
                 }
                 _ => {
                     self.iter.next();
-                    Some(Illegal(ch.to_string()))
+                    Some(Token::Error(Error::UnexpectedCharacter {
+                        character: ch,
+                        location,
+                    }))
                 }
             },
             None => None,
@@ -40,15 +155,33 @@ impl<'a> Lexer<'a> {
     }
 
     fn next_symbol(&mut self) -> Token {
+        if let Some(interpolation) = self.interpolations.last_mut() {
+            match self.iter.peek() {
+                Some('{') => interpolation.depth += 1,
+                Some('}') => interpolation.depth = interpolation.depth.saturating_sub(1),
+                _ => (),
+            }
+        }
+
         match self.iter.next() {
             Some('=') => match self.iter.peek() {
                 Some('=') => {
                     self.iter.next();
                     Equal
                 }
+                Some('>') => {
+                    self.iter.next();
+                    FatArrow
+                }
                 _ => Assign,
             },
-            Some('+') => Plus,
+            Some('+') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    PlusAssign
+                }
+                _ => Plus,
+            },
             Some('(') => LParen,
             Some(')') => RParen,
             Some('{') => LBrace,
@@ -57,7 +190,13 @@ impl<'a> Lexer<'a> {
             Some(']') => RBracket,
             Some(',') => Comma,
             Some(';') => Semicolon,
-            Some('-') => Minus,
+            Some('-') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    MinusAssign
+                }
+                _ => Minus,
+            },
             Some('!') => match self.iter.peek() {
                 Some('=') => {
                     self.iter.next();
@@ -65,31 +204,215 @@ impl<'a> Lexer<'a> {
                 }
                 _ => Bang,
             },
-            Some('*') => Asterisk,
-            Some('/') => Slash,
-            Some('<') => LessThan,
-            Some('>') => GreaterThan,
+            Some('*') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    AsteriskAssign
+                }
+                _ => Asterisk,
+            },
+            Some('/') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    SlashAssign
+                }
+                _ => Slash,
+            },
+            Some('%') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    PercentAssign
+                }
+                _ => Percent,
+            },
+            Some('<') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    LessThanEqual
+                }
+                _ => LessThan,
+            },
+            Some('>') => match self.iter.peek() {
+                Some('=') => {
+                    self.iter.next();
+                    GreaterThanEqual
+                }
+                _ => GreaterThan,
+            },
+            Some('&') => match self.iter.peek() {
+                Some('&') => {
+                    self.iter.next();
+                    And
+                }
+                _ => Illegal("&".to_string()),
+            },
+            Some('|') => match self.iter.peek() {
+                Some('|') => {
+                    self.iter.next();
+                    Or
+                }
+                _ => Illegal("|".to_string()),
+            },
             Some(ch) => Illegal(ch.to_string()),
             None => panic!("None matched for next_symbol"),
         }
     }
 
     fn next_identifier(&mut self) -> Token {
-        consume_while(|ch| ch.is_alphabetic(), &mut self.iter).into()
+        let literal = consume_while(is_xid_continue, &mut self.iter);
+
+        match (literal.as_ref(), self.iter.peek()) {
+            ("r", Some('"')) | ("r", Some('#')) => self.next_raw_str(),
+            _ => literal.into(),
+        }
     }
 
-    fn next_int(&mut self) -> Token {
-        let literal = consume_while(|ch| ch.is_alphanumeric(), &mut self.iter);
-        match literal.parse() {
-            Ok(val) => Int(val),
-            Err(_) => Illegal(literal),
+    fn next_number(&mut self) -> Token {
+        let mut literal = consume_while(is_xid_continue, &mut self.iter);
+
+        // Fractional part.
+        if literal.chars().all(|ch| ch.is_ascii_digit()) && self.iter.peek() == Some('.') {
+            self.iter.next();
+            literal.push('.');
+            literal.push_str(&consume_while(is_xid_continue, &mut self.iter));
+        }
+
+        // Sign of the exponent, e.g. `1e-5`.
+        if literal.ends_with(['e', 'E']) {
+            if let Some(sign) = self.iter.peek().filter(|&ch| ch == '+' || ch == '-') {
+                self.iter.next();
+                literal.push(sign);
+                literal.push_str(&consume_while(is_xid_continue, &mut self.iter));
+            }
+        }
+
+        if literal.chars().all(|ch| ch.is_ascii_digit()) {
+            match (literal.parse(), literal.parse()) {
+                (Ok(val), _) => Int(val),
+                (_, Ok(val)) => BigInt(val),
+                _ => Illegal(literal),
+            }
+        } else if is_float(&literal) {
+            // Validated above, as `parse()` also accepts strings like `inf`.
+            Float(literal.parse().unwrap())
+        } else {
+            Illegal(literal)
         }
     }
 
     fn next_str(&mut self) -> Token {
-        let literal = consume_while(|ch| ch != '"', &mut self.iter);
-        self.iter.next();
-        Str(literal)
+        let location = self.iter.location;
+        self.iter.next(); // consume the opening quote
+
+        self.next_str_part(location, true)
+    }
+
+    // Reads a string until the closing quote, or until a `${` interpolation. Strings with
+    // interpolations are split into a `TemplateStart`, any number of `TemplateMiddle` and a
+    // `TemplateEnd`, with the tokens of the interpolated expressions in between.
+    fn next_str_part(&mut self, location: Location, start: bool) -> Token {
+        let mut literal = String::new();
+        loop {
+            match self.iter.next() {
+                Some('"') if start => return Str(literal),
+                Some('"') => return TemplateEnd(literal),
+                Some('$') if self.iter.peek() == Some('{') => {
+                    self.iter.next();
+                    self.interpolations
+                        .push(Interpolation { location, depth: 0 });
+
+                    return if start {
+                        TemplateStart(literal)
+                    } else {
+                        TemplateMiddle(literal)
+                    };
+                }
+                Some('\\') => match self.next_escape() {
+                    Ok(ch) => literal.push(ch),
+                    Err(Error::UnterminatedString { .. }) => {
+                        return Token::Error(Error::UnterminatedString { location })
+                    }
+                    Err(err) => {
+                        // Skip the rest of the string so that lexing can continue after it.
+                        consume_while(|ch| ch != '"', &mut self.iter);
+                        self.iter.next();
+                        return Token::Error(err);
+                    }
+                },
+                Some(ch) => literal.push(ch),
+                None => return Token::Error(Error::UnterminatedString { location }),
+            }
+        }
+    }
+
+    // Expects the backslash to have been consumed.
+    fn next_escape(&mut self) -> Result<char, Error> {
+        // Location of the backslash.
+        let location = Location {
+            column: self.iter.location.column - 1,
+            ..self.iter.location
+        };
+
+        match self.iter.next() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('\\') => Ok('\\'),
+            Some('"') => Ok('"'),
+            Some('$') => Ok('$'),
+            Some('u') => {
+                let mut escape = String::from("\\u");
+                if self.iter.peek() == Some('{') {
+                    escape.push_str(&consume_while(|ch| ch != '}' && ch != '"', &mut self.iter));
+                    if self.iter.peek() == Some('}') {
+                        escape.push('}');
+                        self.iter.next();
+                    }
+                }
+
+                escape
+                    .strip_prefix("\\u{")
+                    .and_then(|hex| hex.strip_suffix('}'))
+                    .filter(|hex| (1..=6).contains(&hex.len()))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .and_then(std::char::from_u32)
+                    .ok_or(Error::InvalidEscape { escape, location })
+            }
+            Some(ch) => Err(Error::InvalidEscape {
+                escape: format!("\\{}", ch),
+                location,
+            }),
+            None => Err(Error::UnterminatedString { location }),
+        }
+    }
+
+    // Raw strings don't process escapes, and can be delimited with any number of `#` so that they
+    // can contain quotes, e.g. `r#"say "hi""#`. Expects the `r` to have been consumed.
+    fn next_raw_str(&mut self) -> Token {
+        let location = Location {
+            column: self.iter.location.column - 1,
+            ..self.iter.location
+        };
+
+        let hashes = consume_while(|ch| ch == '#', &mut self.iter);
+        if self.iter.next() != Some('"') {
+            return Illegal(format!("r{}", hashes));
+        }
+
+        let terminator = format!("\"{}", hashes);
+        let mut literal = String::new();
+        loop {
+            match self.iter.next() {
+                Some(ch) => {
+                    literal.push(ch);
+                    if literal.ends_with(&terminator) {
+                        literal.truncate(literal.len() - terminator.len());
+                        return Str(literal);
+                    }
+                }
+                None => return Token::Error(Error::UnterminatedString { location }),
+            }
+        }
     }
 }
 
@@ -102,24 +425,106 @@ impl<'a> Iterator for Lexer<'a> {
 }
 
 fn is_whitespace(ch: char) -> bool {
-    ch == ' ' || ch == '\t' || ch == '\n'
+    ch == ' ' || ch == '\t' || ch == '\n' || ch == '\r'
+}
+
+// Like in Rust and Python, identifiers can also start with an underscore, e.g. `_unused`.
+fn is_identifier_start(ch: char) -> bool {
+    ch == '_' || is_xid_start(ch)
+}
+
+// Matches `digits[.digits][(e|E)[+|-]digits]`.
+fn is_float(literal: &str) -> bool {
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|ch| ch.is_ascii_digit());
+
+    let (mantissa, exponent) = match literal.find(['e', 'E']) {
+        Some(i) => (&literal[..i], Some(&literal[i + 1..])),
+        None => (literal, None),
+    };
+
+    let mantissa_valid = match mantissa.find('.') {
+        Some(i) => is_digits(&mantissa[..i]) && is_digits(&mantissa[i + 1..]),
+        None => is_digits(mantissa),
+    };
+
+    let exponent_valid = match exponent {
+        Some(exponent) => is_digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent)),
+        None => true,
+    };
+
+    mantissa_valid && exponent_valid
 }
 
 fn is_symbol(ch: char) -> bool {
     match ch {
-        '=' | '+' | '(' | ')' | '{' | '}' | '[' | ']' | ',' | ';' | '-' | '!' | '*' | '/' | '<'
-        | '>' => true,
+        '=' | '+' | '(' | ')' | '{' | '}' | '[' | ']' | ',' | ';' | '-' | '!' | '*' | '/' | '%'
+        | '<' | '>' | '&' | '|' => true,
         _ => false,
     }
 }
 
-fn consume_while<F>(condition: F, iter: &mut Peekable<Chars>) -> String
+// Iterator over the characters of the input, which keeps track of the location of the next
+// character. `\r\n` is read as a single `\n`, so that files with Windows line endings lex the same
+// way, including inside strings and comments.
+struct Source<'a> {
+    chars: Chars<'a>,
+    location: Location,
+}
+
+impl<'a> Source<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            chars: input.chars(),
+            location: Location { line: 1, column: 1 },
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        read_char(&mut self.chars.clone())
+    }
+
+    fn starts_with(&self, prefix: &str) -> bool {
+        self.chars.as_str().starts_with(prefix)
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        let mut chars = self.chars.clone();
+        read_char(&mut chars);
+        read_char(&mut chars)
+    }
+}
+
+fn read_char(chars: &mut Chars) -> Option<char> {
+    match chars.next()? {
+        '\r' if chars.as_str().starts_with('\n') => chars.next(),
+        ch => Some(ch),
+    }
+}
+
+impl<'a> Iterator for Source<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ch = read_char(&mut self.chars)?;
+
+        if ch == '\n' {
+            self.location.line += 1;
+            self.location.column = 1;
+        } else {
+            self.location.column += 1;
+        }
+
+        Some(ch)
+    }
+}
+
+fn consume_while<F>(condition: F, iter: &mut Source) -> String
 where
     F: Fn(char) -> bool,
 {
     let mut literal = String::new();
 
-    while let Some(&ch) = iter.peek() {
+    while let Some(ch) = iter.peek() {
         if !condition(ch) {
             break;
         }
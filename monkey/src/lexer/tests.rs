@@ -1,6 +1,6 @@
-use crate::lexer::Lexer;
-use crate::token::Token;
+use crate::lexer::{Error, Lexer, Location};
 use crate::token::Token::*;
+use crate::token::{Token, TokenWithTrivia, Trivia};
 
 #[test]
 fn test_next_token_symbols() {
@@ -26,10 +26,63 @@ fn test_next_token_symbols() {
     test_lexer(expected, input);
 }
 
+#[test]
+fn test_next_token_multi_char_symbols() {
+    let input = "<= >= == != && || % => < >";
+
+    let expected = vec![
+        LessThanEqual,
+        GreaterThanEqual,
+        Equal,
+        NotEqual,
+        And,
+        Or,
+        Percent,
+        FatArrow,
+        LessThan,
+        GreaterThan,
+    ];
+
+    test_lexer(expected, input);
+    test_lexer(vec![Illegal("&".to_string()), Int(1)], "& 1");
+}
+
+#[test]
+fn test_next_token_ellipsis() {
+    let input = "[...rest] ..";
+    let expected = vec![
+        LBracket,
+        Ellipsis,
+        Identifier("rest".to_string()),
+        RBracket,
+        Dot,
+        Dot,
+    ];
+
+    test_lexer(expected, input);
+}
+
+#[test]
+fn test_next_token_compound_assign() {
+    let input = "+= -= *= /= %= = ==";
+
+    let expected = vec![
+        PlusAssign,
+        MinusAssign,
+        AsteriskAssign,
+        SlashAssign,
+        PercentAssign,
+        Assign,
+        Equal,
+    ];
+
+    test_lexer(expected, input);
+}
+
 #[test]
 fn test_next_token_keyword() {
-    let input = "let";
-    let expected = vec![Let];
+    let input = "let match import export as throw try catch";
+    let expected = vec![Let, Match, Import, Export, As, Throw, Try, Catch];
 
     test_lexer(expected, input);
 }
@@ -42,6 +95,65 @@ fn test_next_token_identifier() {
     test_lexer(expected, input);
 }
 
+#[test]
+fn test_next_token_identifier_xid() {
+    let input = "foo_bar x1 _unused __init__ café 変数 αβγ x\u{301}";
+    let expected = vec![
+        Identifier("foo_bar".to_string()),
+        Identifier("x1".to_string()),
+        Identifier("_unused".to_string()),
+        Identifier("__init__".to_string()),
+        Identifier("café".to_string()),
+        Identifier("変数".to_string()),
+        Identifier("αβγ".to_string()),
+        Identifier("x\u{301}".to_string()),
+    ];
+
+    test_lexer(expected, input);
+
+    // Combining marks and digits can continue but not start an identifier.
+    test_lexer(
+        vec![
+            Token::Error(Error::UnexpectedCharacter {
+                character: '\u{301}',
+                location: Location { line: 1, column: 1 },
+            }),
+            Identifier("x".to_string()),
+        ],
+        "\u{301}x",
+    );
+    test_lexer(vec![Illegal("1x".to_string())], "1x");
+}
+
+#[test]
+fn test_next_token_crlf() {
+    let input = "let a = 1;\r\n// comment\r\n\"multi\r\nline\";\r\n@";
+    let expected = vec![
+        Let,
+        Identifier("a".to_string()),
+        Assign,
+        Int(1),
+        Semicolon,
+        Str("multi\nline".to_string()),
+        Semicolon,
+        Token::Error(Error::UnexpectedCharacter {
+            character: '@',
+            location: Location { line: 5, column: 1 },
+        }),
+    ];
+
+    test_lexer(expected, input);
+
+    let mut lexer = Lexer::new("// comment\r\n1");
+    assert_eq!(
+        Some(TokenWithTrivia {
+            token: Int(1),
+            leading_trivia: vec![Trivia::LineComment(" comment".to_string())],
+        }),
+        lexer.next_token_with_trivia()
+    );
+}
+
 #[test]
 fn test_next_token_int() {
     let input = "123";
@@ -58,10 +170,53 @@ fn test_next_token_int_invalid() {
     test_lexer(expected, input);
 }
 
+#[test]
+fn test_next_token_float() {
+    let input = "1.5 0.25 1e3 1E3 2.5e-3 2.5e+3 10.0;";
+    let expected = vec![
+        Float(1.5),
+        Float(0.25),
+        Float(1000.0),
+        Float(1000.0),
+        Float(0.0025),
+        Float(2500.0),
+        Float(10.0),
+        Semicolon,
+    ];
+
+    test_lexer(expected, input);
+}
+
+#[test]
+fn test_next_token_float_invalid() {
+    let cases = vec![
+        ("1.5a", vec![Illegal("1.5a".to_string())]),
+        ("1e", vec![Illegal("1e".to_string())]),
+        ("1e+", vec![Illegal("1e+".to_string())]),
+        ("1.e5", vec![Illegal("1.e5".to_string())]),
+        ("1.", vec![Illegal("1.".to_string())]),
+        ("1.2.3", vec![Float(1.2), Dot, Int(3)]),
+    ];
+
+    for (input, expected) in cases {
+        test_lexer(expected, input);
+    }
+}
+
 #[test]
 fn test_unknown_symbol() {
-    let input = ".";
-    let expected = vec![Illegal(".".to_string())];
+    let input = "a @\n  $";
+    let expected = vec![
+        Identifier("a".to_string()),
+        Token::Error(Error::UnexpectedCharacter {
+            character: '@',
+            location: Location { line: 1, column: 3 },
+        }),
+        Token::Error(Error::UnexpectedCharacter {
+            character: '$',
+            location: Location { line: 2, column: 3 },
+        }),
+    ];
 
     test_lexer(expected, input);
 }
@@ -122,7 +277,7 @@ let result = add(five, ten);
 
 #[test]
 fn test_next_token_extended() {
-    let input = r#"!-/*5;
+    let input = r#"!-/ *5;
 5 < 10 > 5;
 
 if (5 < 10) {
@@ -184,6 +339,176 @@ if (5 < 10) {
     test_lexer(expected, input);
 }
 
+#[test]
+fn test_next_token_str_escapes() {
+    let cases = vec![
+        (r#""a\nb""#, "a\nb"),
+        (r#""a\tb""#, "a\tb"),
+        (r#""a\r\nb""#, "a\r\nb"),
+        (r#""a\\b""#, "a\\b"),
+        (r#""say \"hi\"""#, "say \"hi\""),
+        (r#""\u{41}\u{1F600}""#, "A\u{1F600}"),
+        ("\"multi\nline\"", "multi\nline"),
+        (r#""\${a} $a""#, "${a} $a"),
+    ];
+
+    for (input, expected) in cases {
+        test_lexer(vec![Str(expected.to_string())], input);
+    }
+}
+
+#[test]
+fn test_next_token_interpolation() {
+    test_lexer(
+        vec![
+            TemplateStart("a ".to_string()),
+            Identifier("b".to_string()),
+            TemplateMiddle(" c ".to_string()),
+            Int(1),
+            Plus,
+            Int(2),
+            TemplateEnd("".to_string()),
+            Semicolon,
+        ],
+        r#""a ${b} c ${1 + 2}";"#,
+    );
+
+    // Braces and strings inside an interpolation don't end it.
+    test_lexer(
+        vec![
+            TemplateStart("".to_string()),
+            Function,
+            LParen,
+            RParen,
+            LBrace,
+            Str("}".to_string()),
+            RBrace,
+            TemplateEnd("!".to_string()),
+        ],
+        r#""${fn() { "}" }}!""#,
+    );
+
+    test_lexer(
+        vec![
+            TemplateStart("a".to_string()),
+            TemplateStart("b".to_string()),
+            Identifier("c".to_string()),
+            TemplateEnd("d".to_string()),
+            TemplateEnd("e".to_string()),
+        ],
+        r#""a${"b${c}d"}e""#,
+    );
+
+    test_lexer(
+        vec![
+            TemplateStart("a".to_string()),
+            Identifier("b".to_string()),
+            Token::Error(Error::UnterminatedString {
+                location: Location { line: 1, column: 1 },
+            }),
+        ],
+        r#""a${b}c"#,
+    );
+}
+
+#[test]
+fn test_next_token_raw_str() {
+    let cases = vec![
+        (r#"r"C:\path\n""#, r"C:\path\n"),
+        (r##"r#"say "hi""#"##, r#"say "hi""#),
+        (r###"r##"a "# b"##"###, r##"a "# b"##),
+        ("r\"multi\nline\"", "multi\nline"),
+    ];
+
+    for (input, expected) in cases {
+        test_lexer(vec![Str(expected.to_string())], input);
+    }
+
+    // `r` on its own is still an identifier.
+    test_lexer(
+        vec![
+            Identifier("r".to_string()),
+            Plus,
+            Identifier("rs".to_string()),
+        ],
+        "r + rs",
+    );
+}
+
+#[test]
+fn test_next_token_str_error() {
+    let cases = vec![
+        (
+            r#"let a = "abc;"#,
+            vec![
+                Let,
+                Identifier("a".to_string()),
+                Assign,
+                Token::Error(Error::UnterminatedString {
+                    location: Location { line: 1, column: 9 },
+                }),
+            ],
+        ),
+        (
+            "let a = 1;\n  \"abc\\",
+            vec![
+                Let,
+                Identifier("a".to_string()),
+                Assign,
+                Int(1),
+                Semicolon,
+                Token::Error(Error::UnterminatedString {
+                    location: Location { line: 2, column: 3 },
+                }),
+            ],
+        ),
+        (
+            "r#\"abc\"",
+            vec![Token::Error(Error::UnterminatedString {
+                location: Location { line: 1, column: 1 },
+            })],
+        ),
+        (
+            r#""a\qb"; 1"#,
+            vec![
+                Token::Error(Error::InvalidEscape {
+                    escape: r"\q".to_string(),
+                    location: Location { line: 1, column: 3 },
+                }),
+                Semicolon,
+                Int(1),
+            ],
+        ),
+        (
+            r#""\u{110000}" "\u{}" "\u{41""#,
+            vec![
+                Token::Error(Error::InvalidEscape {
+                    escape: r"\u{110000}".to_string(),
+                    location: Location { line: 1, column: 2 },
+                }),
+                Token::Error(Error::InvalidEscape {
+                    escape: r"\u{}".to_string(),
+                    location: Location {
+                        line: 1,
+                        column: 15,
+                    },
+                }),
+                Token::Error(Error::InvalidEscape {
+                    escape: r"\u{41".to_string(),
+                    location: Location {
+                        line: 1,
+                        column: 22,
+                    },
+                }),
+            ],
+        ),
+    ];
+
+    for (input, expected) in cases {
+        test_lexer(expected, input);
+    }
+}
+
 #[test]
 fn test_next_token_list() {
     let input = r"[1, 2];";
@@ -192,6 +517,95 @@ fn test_next_token_list() {
     test_lexer(expected, input);
 }
 
+#[test]
+fn test_next_token_comments() {
+    let input = "// leading
+let a = 1; // trailing
+/* block */ let b /* nested /* comment */ here */ = a / 2;
+// end";
+
+    let expected = vec![
+        Let,
+        Identifier("a".to_string()),
+        Assign,
+        Int(1),
+        Semicolon,
+        Let,
+        Identifier("b".to_string()),
+        Assign,
+        Identifier("a".to_string()),
+        Slash,
+        Int(2),
+        Semicolon,
+    ];
+
+    test_lexer(expected, input);
+}
+
+#[test]
+fn test_next_token_with_trivia() {
+    let input = "// leading
+let a = 1; // trailing
+/* block */ b /* nested /* comment */ here */;
+// end";
+
+    let mut lexer = Lexer::new(input);
+
+    let mut received = Vec::new();
+    while let Some(token) = lexer.next_token_with_trivia() {
+        received.push(token);
+    }
+
+    let token = |token| TokenWithTrivia {
+        token,
+        leading_trivia: vec![],
+    };
+
+    let expected = vec![
+        TokenWithTrivia {
+            token: Let,
+            leading_trivia: vec![Trivia::LineComment(" leading".to_string())],
+        },
+        token(Identifier("a".to_string())),
+        token(Assign),
+        token(Int(1)),
+        token(Semicolon),
+        TokenWithTrivia {
+            token: Identifier("b".to_string()),
+            leading_trivia: vec![
+                Trivia::LineComment(" trailing".to_string()),
+                Trivia::BlockComment(" block ".to_string()),
+            ],
+        },
+        TokenWithTrivia {
+            token: Semicolon,
+            leading_trivia: vec![Trivia::BlockComment(
+                " nested /* comment */ here ".to_string(),
+            )],
+        },
+    ];
+
+    assert_eq!(expected, received);
+    assert_eq!(
+        vec![Trivia::LineComment(" end".to_string())],
+        lexer.take_trivia()
+    );
+}
+
+#[test]
+fn test_next_token_comment_error() {
+    let input = "1; /* outer /* inner */";
+    let expected = vec![
+        Int(1),
+        Semicolon,
+        Token::Error(Error::UnterminatedComment {
+            location: Location { line: 1, column: 4 },
+        }),
+    ];
+
+    test_lexer(expected, input);
+}
+
 fn test_lexer(expected: Vec<Token>, input: &str) {
     let lexer = Lexer::new(input);
 
@@ -1,18 +1,78 @@
+// An interpreter for the Monkey programming language.
+//
+// Programs go through `tokenize`, `parse`, and then either `Interpreter::evaluate`, which walks the
+// syntax tree, or `compile` and `run`, which use the bytecode VM. Tools like formatters and linters
+// can stop after any step.
 mod ast;
+mod bigint;
 mod bytecode;
 mod compiler;
 mod eval;
+pub mod input;
 mod lexer;
+pub mod module;
 pub mod object;
+pub mod output;
 mod parser;
+mod sandbox;
 mod token;
 pub mod vm;
 
-use crate::eval::Error as EvalError;
+// The types used by the entry points, and the types of their fields, so that tools can inspect
+// tokens and syntax trees. The modules defining them are internal.
+pub use crate::ast::{
+    CallFunctionExpression, Expression, Function, InterpolationPart, MatchArm, Operator, Param,
+    Pattern, Program, Statement, Statements,
+};
+pub use crate::compiler::{Error as CompileError, Output as Bytecode};
+pub use crate::eval::Error as EvalError;
+pub use crate::lexer::{Error as LexError, Location};
+pub use crate::parser::{Error as ParseError, ErrorExpected, Errors as ParseErrors};
+pub use crate::token::{Token, TokenWithTrivia, Trivia};
+
+use crate::input::Input;
 use crate::lexer::Lexer;
-use crate::object::{Env, Object};
-use crate::parser::{Errors, Parser};
+use crate::module::ModuleResolver;
+use crate::object::{Env, FromArgs, HostFunction, IntoObject, Object};
+use crate::output::Output;
+use crate::parser::Parser;
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+// The tokens of the source. Invalid input becomes `Token::Error` tokens rather than failing, so
+// that the rest of the source can still be inspected.
+pub fn tokenize(source: &str) -> Vec<Token> {
+    Lexer::new(source).collect()
+}
+
+// Like `tokenize`, but keeps the comments before each token, for tools like formatters. The
+// comments after the last token are returned separately.
+pub fn tokenize_with_trivia(source: &str) -> (Vec<TokenWithTrivia>, Vec<Trivia>) {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next_token_with_trivia() {
+        tokens.push(token);
+    }
+    (tokens, lexer.take_trivia())
+}
+
+pub fn parse(source: &str) -> Result<Program, Error> {
+    Parser::new(Lexer::new(source))
+        .parse()
+        .map_err(Error::Parse)
+}
+
+pub fn compile(program: Program) -> Result<Bytecode, Error> {
+    compiler::compile(program).map_err(Error::Compile)
+}
+
+// Runs compiled bytecode on a new VM, returning the value of the last expression statement.
+pub fn run(bytecode: Bytecode) -> Result<Object, Error> {
+    let mut vm = vm::Vm::new();
+    vm.run_compiled(bytecode).map_err(Error::Run)?;
+    Ok(vm.last_popped().cloned().unwrap_or(Object::Null))
+}
 
 pub struct Interpreter {
     env: Env,
@@ -28,20 +88,103 @@ impl Interpreter {
         Self { env: Env::new() }
     }
 
-    pub fn evaluate(&mut self, s: String) -> InterpreterResult {
-        // TODO cleanup output
-        let result = match Parser::new(Lexer::new(&s)).parse() {
-            Ok(program) => {
-                let (env, eval_result) = program.evaluate(self.env.clone());
+    // Sets a budget for the approximate number of bytes allocated for lists and strings in a single
+    // call to `evaluate`, or `None` to remove it. Memory isn't given back when values are dropped, so
+    // this bounds the total allocated rather than the live heap. There is no limit by default.
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.env.set_memory_limit(limit);
+    }
+
+    // Sets how many function calls can be nested before a `RecursionError`, which can be caught.
+    // The default is low enough for the main thread, so raise it only when running on a thread
+    // with a larger stack.
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.env.set_recursion_limit(limit);
+    }
+
+    // Allows `read_file`, `write_file`, `list_dir` and `exists`, with paths relative to `root`
+    // which can't leave it. They are disabled by default, with `None`, and always in wasm. Without
+    // a module resolver, `import` reads modules from the same root.
+    pub fn set_filesystem_root(&mut self, root: Option<PathBuf>) {
+        self.env.set_filesystem_root(root);
+    }
+
+    // Sets where `import` statements find modules, e.g. `FileResolver` to read them from a
+    // directory. By default they are read from the filesystem root, so imports are disabled until
+    // one is set. Modules which were already imported stay cached.
+    pub fn set_module_resolver(&mut self, resolver: impl ModuleResolver + 'static) {
+        self.env.set_module_resolver(Rc::new(resolver));
+    }
+
+    // Sets where `input`, `read_line` and `read_lines` read from. By default there is no input, as
+    // if stdin was empty.
+    pub fn set_input(&mut self, input: impl Input + 'static) {
+        self.env.set_input(Box::new(input));
+    }
+
+    // Sets where `print` writes to as the program runs. By default the output is kept in memory
+    // and returned in `InterpreterResult::stdout`, which is empty with any other output.
+    pub fn set_output(&mut self, output: impl Output + 'static) {
+        self.env.set_output(Box::new(output));
+    }
+
+    // Exposes a Rust function to programs under the given name. It is called with the evaluated
+    // arguments, after checking that there are `arity` of them, and its errors can be caught
+    // like other runtime errors.
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        arity: usize,
+        func: impl Fn(&[Object]) -> std::result::Result<Object, object::Error> + 'static,
+    ) {
+        self.env
+            .register_function(HostFunction::new(name, arity, func));
+    }
+
+    // Like `register_function`, but the arguments are converted to a tuple of Rust values and the
+    // result is converted back, e.g. `|(a, b): (isize, isize)| Ok(a + b)`. An argument of the
+    // wrong type is a `TypeError`.
+    pub fn register_typed_function<A: FromArgs, R: IntoObject>(
+        &mut self,
+        name: &str,
+        func: impl Fn(A) -> std::result::Result<R, object::Error> + 'static,
+    ) {
+        let function = name.to_string();
+        self.register_function(name, A::ARITY, move |args| {
+            Ok(func(A::from_args(&function, args)?)?.into_object())
+        });
+    }
+
+    // Removes the built-in functions like `print` and `len`, for sandboxing. Registered functions
+    // are kept.
+    pub fn remove_built_ins(&mut self) {
+        self.env.remove_built_ins();
+    }
+
+    // Like `evaluate`, but runs bytecode from `compile` on the VM.
+    pub fn run(&mut self, bytecode: Bytecode) -> InterpreterResult {
+        let mut vm = vm::Vm::with_env(self.env.clone());
+        let result = vm
+            .run_compiled(bytecode)
+            .map(|_| vm.last_popped().cloned().unwrap_or(Object::Null))
+            .map_err(Error::Run);
+        InterpreterResult {
+            result,
+            stdout: self.env.pop_stdout().join("\n"),
+        }
+    }
+
+    pub fn evaluate(&mut self, source: &str) -> InterpreterResult {
+        self.env.reset_memory();
 
-                // Update the existing env to preserve state.
-                self.env = env;
+        let result = parse(source).and_then(|program| {
+            let (env, eval_result) = program.evaluate(self.env.clone());
 
-                eval_result.map_err(|err| Error::Eval(err))
-            }
+            // Update the existing env to preserve state.
+            self.env = env;
 
-            Err(e) => Err(Error::Parse(e)),
-        };
+            eval_result.map_err(Error::Eval)
+        });
         InterpreterResult {
             result,
             stdout: self.env.pop_stdout().join("\n"),
@@ -49,24 +192,38 @@ impl Interpreter {
     }
 }
 
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
 pub enum Error {
-    Parse(Errors),
+    Parse(ParseErrors),
     Eval(EvalError),
+    Compile(CompileError),
+    Run(vm::Error),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
         match self {
-            Error::Parse(err) => write!(
-                f,
-                "{}",
-                err.errors
-                    .iter()
-                    .map(|x| format!("{}", x))
-                    .collect::<Vec<String>>()
-                    .join("\n")
-            ),
-            Error::Eval(error) => write!(f, "{}", error),
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::Eval(err) => write!(f, "{}", err),
+            Error::Compile(err) => write!(f, "{}", err),
+            Error::Run(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            Error::Eval(err) => Some(err),
+            Error::Compile(err) => Some(err),
+            Error::Run(err) => Some(err),
         }
     }
 }
@@ -1,60 +1,79 @@
 extern crate monkey;
 
+use monkey::input::Stdin;
+use monkey::module::FileResolver;
+use monkey::output::Output;
 use monkey::{Interpreter, InterpreterResult};
+use std::cell::RefCell;
 use std::io;
-use std::io::{BufRead, Write};
+use std::io::Write;
+use std::rc::Rc;
 
 const PROMPT: &str = ">>> ";
 
 fn main() {
-    let stdin = io::stdin();
     let stdout = io::stdout();
 
-    Repl::new(stdin.lock(), stdout.lock()).start();
+    Repl::new(stdout.lock()).start();
 }
 
-struct Repl<R: BufRead, W: Write> {
+// Stdin isn't locked for the whole session like stdout, since `input()` also reads from it.
+struct Repl<W: Write> {
     interpreter: Interpreter,
-    reader: R,
-    writer: W,
+    // Shared with the interpreter, so that `print` writes to the same place as the results.
+    writer: Rc<RefCell<W>>,
 }
 
-impl<R: BufRead, W: Write> Repl<R, W> {
-    pub fn new(reader: R, writer: W) -> Self {
+impl<W: Write + 'static> Repl<W> {
+    pub fn new(writer: W) -> Self {
+        let writer = Rc::new(RefCell::new(writer));
+        // Print as the program runs rather than after each input.
+        let mut interpreter = monkey::Interpreter::new();
+        interpreter.set_output(Writer(Rc::clone(&writer)));
+        interpreter.set_input(Stdin);
+        interpreter.set_module_resolver(FileResolver::new("."));
+
         Self {
-            interpreter: monkey::Interpreter::new(),
-            reader: reader,
+            interpreter,
             writer: writer,
         }
     }
 
     pub fn start(&mut self) {
         loop {
-            self.writer.write(PROMPT.as_bytes()).unwrap();
-            self.writer.flush().unwrap();
+            let mut writer = self.writer.borrow_mut();
+            writer.write(PROMPT.as_bytes()).unwrap();
+            writer.flush().unwrap();
+            drop(writer);
 
             let mut buf = String::new();
-            self.reader.read_line(&mut buf).unwrap();
+            io::stdin().read_line(&mut buf).unwrap();
 
-            let output = handle_input(&mut self.interpreter, &mut self.writer, buf);
+            let output = handle_input(&mut self.interpreter, &self.writer, buf);
             output.expect("no errors for as_bytes()");
         }
     }
 }
 
+struct Writer<W: Write>(Rc<RefCell<W>>);
+
+impl<W: Write> Output for Writer<W> {
+    fn write_line(&mut self, line: &str) {
+        writeln!(self.0.borrow_mut(), "{}", line).unwrap();
+    }
+}
+
+// The writer isn't borrowed while evaluating, since `print` writes to it.
 fn handle_input<W>(
     interpreter: &mut Interpreter,
-    output: &mut W,
+    output: &RefCell<W>,
     s: String,
 ) -> std::result::Result<usize, std::io::Error>
 where
     W: Write,
 {
-    let InterpreterResult { stdout, result } = interpreter.evaluate(s);
-    if stdout.len() > 0 {
-        output.write(format!("{}\n", stdout).as_bytes())?;
-    };
-
+    let InterpreterResult { result, .. } = interpreter.evaluate(&s);
+    let mut output = output.borrow_mut();
     match result {
         Ok(object) => output.write(format!("{}\n", object).as_bytes()),
         Err(e) => output.write(format!("{}\n", e).as_bytes()),
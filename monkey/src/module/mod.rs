@@ -0,0 +1,94 @@
+use crate::sandbox;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+// Finds the source of the modules in `import` statements.
+pub trait ModuleResolver {
+    // `path` is normalized by `normalize_path`, so it is relative to the root of the modules and
+    // never leaves it. It is also the key modules are cached by.
+    fn resolve(&self, path: &str) -> io::Result<String>;
+}
+
+// Joins the path in an import statement to the directory of the module containing it, which is
+// `from`, or `""` for the program itself. `.` and `..` are removed, so that different spellings of
+// the same module share the same path. Absolute paths and paths leaving the root are rejected.
+pub fn normalize_path(from: &str, path: &str) -> io::Result<String> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::PermissionDenied, message);
+    if path.starts_with('/') {
+        return Err(invalid(format!("'{}' must be a relative path", path)));
+    }
+
+    let mut components: Vec<&str> = from.split('/').collect();
+    // The last component is the file name of the importing module.
+    components.pop();
+    for component in path.split('/') {
+        match component {
+            "" | "." => (),
+            ".." => {
+                if components.pop().is_none() {
+                    return Err(invalid(format!("'{}' is outside of the module root", path)));
+                }
+            }
+            component => components.push(component),
+        }
+    }
+
+    Ok(components.join("/"))
+}
+
+// Reads modules from files, with paths relative to `root` which can't leave it.
+#[derive(Debug, Clone)]
+pub struct FileResolver {
+    root: PathBuf,
+}
+
+impl FileResolver {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Default for FileResolver {
+    // Resolves relative to the current directory.
+    fn default() -> Self {
+        Self::new(".")
+    }
+}
+
+impl ModuleResolver for FileResolver {
+    fn resolve(&self, path: &str) -> io::Result<String> {
+        fs::read_to_string(sandbox::resolve(&self.root, &normalize_path("", path)?)?)
+    }
+}
+
+// Modules kept in memory, for tests and for environments without a filesystem like wasm.
+#[derive(Debug, Clone, Default)]
+pub struct MapResolver {
+    modules: HashMap<String, String>,
+}
+
+impl MapResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, path: impl Into<String>, source: impl Into<String>) {
+        self.modules.insert(path.into(), source.into());
+    }
+}
+
+impl ModuleResolver for MapResolver {
+    fn resolve(&self, path: &str) -> io::Result<String> {
+        self.modules.get(path).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no module named '{}'", path),
+            )
+        })
+    }
+}
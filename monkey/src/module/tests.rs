@@ -0,0 +1,53 @@
+use super::{normalize_path, FileResolver, MapResolver, ModuleResolver};
+use std::env;
+use std::fs;
+use std::io;
+
+#[test]
+fn test_map_resolver() {
+    let mut resolver = MapResolver::new();
+    resolver.insert("lib/util.mk", "export let a = 1;");
+
+    assert_eq!(
+        "export let a = 1;",
+        resolver.resolve("lib/util.mk").unwrap()
+    );
+
+    let err = resolver.resolve("missing.mk").unwrap_err();
+    assert_eq!(io::ErrorKind::NotFound, err.kind());
+    assert_eq!("no module named 'missing.mk'", err.to_string());
+}
+
+#[test]
+fn test_file_resolver() {
+    let root = env::temp_dir().join(format!("monkey-module-test-{}", std::process::id()));
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::write(root.join("lib/util.mk"), "export let a = 1;").unwrap();
+
+    let resolver = FileResolver::new(&root);
+    let result = resolver.resolve("lib/util.mk");
+    let missing = resolver.resolve("missing.mk");
+    let outside = resolver.resolve("../lib/util.mk");
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!("export let a = 1;", result.unwrap());
+    assert_eq!(io::ErrorKind::NotFound, missing.unwrap_err().kind());
+    assert_eq!(io::ErrorKind::PermissionDenied, outside.unwrap_err().kind());
+}
+
+#[test]
+fn test_normalize_path() {
+    let tests = vec![
+        ("", "a.mk", Some("a.mk")),
+        ("", "./lib//a.mk", Some("lib/a.mk")),
+        ("lib/main.mk", "a.mk", Some("lib/a.mk")),
+        ("lib/main.mk", "../a.mk", Some("a.mk")),
+        ("lib/main.mk", "./sub/../a.mk", Some("lib/a.mk")),
+        ("lib/main.mk", "../../a.mk", None),
+        ("", "/etc/hostname", None),
+    ];
+
+    for (from, path, expected) in tests {
+        assert_eq!(expected, normalize_path(from, path).ok().as_deref());
+    }
+}
@@ -0,0 +1,78 @@
+use super::{fmt_function, Object};
+use crate::compiler::Function;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+// A function compiled for the VM, with the variables of the functions around it. Like `Function`,
+// it sees later changes to those variables.
+pub struct Closure {
+    pub(crate) func: Rc<Function>,
+    // The locals of the call which created the closure, or `None` at the top level.
+    pub(crate) parent: Option<Rc<Locals>>,
+    pub(crate) globals: Rc<Globals>,
+}
+
+// A closure is only equal to itself.
+impl PartialEq for Closure {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for Closure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Exclude the captured variables from output to avoid stack overflow
+        write!(
+            f,
+            "Closure {{ params: {:#?}, body: {:#?}, locals: <omitted> }}",
+            self.func.params, self.func.body
+        )
+    }
+}
+
+impl fmt::Display for Closure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_function(f, &self.func.params, &self.func.body)
+    }
+}
+
+// The locals of a call of a compiled function. Slots are `None` until they are assigned.
+pub(crate) struct Locals {
+    pub func: Rc<Function>,
+    pub slots: RefCell<Vec<Option<Object>>>,
+    pub parent: Option<Rc<Locals>>,
+}
+
+impl Locals {
+    pub fn new(func: Rc<Function>, parent: Option<Rc<Locals>>) -> Self {
+        let slots = RefCell::new(vec![None; func.output.names.len()]);
+        Self {
+            func,
+            slots,
+            parent,
+        }
+    }
+}
+
+// The top level bindings of a compiled program, shared with the closures it creates.
+pub(crate) struct Globals {
+    // The name of each slot, for errors.
+    pub names: Vec<String>,
+    // The slots of the bindings which are still in scope at the end of the program, for names
+    // which weren't defined yet when a function using them was compiled.
+    pub scope: HashMap<String, u16>,
+    pub slots: RefCell<Vec<Option<Object>>>,
+}
+
+impl Globals {
+    pub fn new(names: Vec<String>, scope: HashMap<String, u16>) -> Self {
+        let slots = RefCell::new(vec![None; names.len()]);
+        Self {
+            names,
+            scope,
+            slots,
+        }
+    }
+}
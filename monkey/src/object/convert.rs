@@ -0,0 +1,249 @@
+use super::{BigInt, Error, Object};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+// Conversions between Rust values and objects, for passing values between a program and the
+// application embedding the interpreter.
+pub trait IntoObject {
+    fn into_object(self) -> Object;
+}
+
+pub trait FromObject: Sized {
+    fn from_object(object: &Object) -> Result<Self, Error>;
+}
+
+// The arguments of a host function, converted to a tuple of Rust values.
+pub trait FromArgs: Sized {
+    const ARITY: usize;
+
+    // The arguments must already have been checked to be `ARITY` long.
+    fn from_args(function: &str, args: &[Object]) -> Result<Self, Error>;
+}
+
+fn conversion_error(expected: &str, object: &Object) -> Error {
+    Error::Conversion {
+        expected: expected.to_string(),
+        object: object.clone(),
+    }
+}
+
+impl IntoObject for Object {
+    fn into_object(self) -> Object {
+        self
+    }
+}
+
+impl FromObject for Object {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        Ok(object.clone())
+    }
+}
+
+impl IntoObject for () {
+    fn into_object(self) -> Object {
+        Object::Null
+    }
+}
+
+impl IntoObject for bool {
+    fn into_object(self) -> Object {
+        Object::from(self)
+    }
+}
+
+impl FromObject for bool {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        match object {
+            Object::Boolean(val) => Ok(*val),
+            object => Err(conversion_error("bool", object)),
+        }
+    }
+}
+
+// Integers which don't fit in an `isize` become a `BigInt`.
+macro_rules! impl_signed {
+    ($($int:ident),*) => {$(
+        impl IntoObject for $int {
+            fn into_object(self) -> Object {
+                match isize::try_from(self) {
+                    Ok(val) => Object::Integer(val),
+                    Err(_) if self < 0 => Object::from(-BigInt::from(self.unsigned_abs() as u64)),
+                    Err(_) => Object::from(BigInt::from(self as u64)),
+                }
+            }
+        }
+
+        impl_from_object!($int);
+    )*};
+}
+
+macro_rules! impl_unsigned {
+    ($($int:ident),*) => {$(
+        impl IntoObject for $int {
+            fn into_object(self) -> Object {
+                match isize::try_from(self) {
+                    Ok(val) => Object::Integer(val),
+                    Err(_) => Object::from(BigInt::from(self as u64)),
+                }
+            }
+        }
+
+        impl_from_object!($int);
+    )*};
+}
+
+macro_rules! impl_from_object {
+    ($int:ident) => {
+        impl FromObject for $int {
+            fn from_object(object: &Object) -> Result<Self, Error> {
+                let out_of_range = || Error::IntegerRange {
+                    expected: stringify!($int).to_string(),
+                    object: object.clone(),
+                };
+
+                match object {
+                    Object::Integer(val) => $int::try_from(*val).map_err(|_| out_of_range()),
+                    Object::BigInt(val) => val
+                        .to_u64()
+                        .and_then(|val| $int::try_from(val).ok())
+                        .ok_or_else(out_of_range),
+                    object => Err(conversion_error("int", object)),
+                }
+            }
+        }
+    };
+}
+
+impl_signed!(i8, i16, i32, i64, isize);
+impl_unsigned!(u8, u16, u32, u64, usize);
+
+impl IntoObject for f64 {
+    fn into_object(self) -> Object {
+        Object::Float(self)
+    }
+}
+
+// Integers are converted too, like in arithmetic with floats.
+impl FromObject for f64 {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        match object {
+            Object::Float(val) => Ok(*val),
+            Object::Integer(val) => Ok(*val as f64),
+            Object::BigInt(val) => Ok(val.to_f64()),
+            object => Err(conversion_error("float", object)),
+        }
+    }
+}
+
+impl IntoObject for String {
+    fn into_object(self) -> Object {
+        Object::Str(self)
+    }
+}
+
+impl IntoObject for &str {
+    fn into_object(self) -> Object {
+        Object::from(self)
+    }
+}
+
+impl FromObject for String {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        match object {
+            Object::Str(val) => Ok(val.clone()),
+            object => Err(conversion_error("string", object)),
+        }
+    }
+}
+
+// `None` is `null`.
+impl<T: IntoObject> IntoObject for Option<T> {
+    fn into_object(self) -> Object {
+        self.map_or(Object::Null, IntoObject::into_object)
+    }
+}
+
+impl<T: FromObject> FromObject for Option<T> {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        match object {
+            Object::Null => Ok(None),
+            object => T::from_object(object).map(Some),
+        }
+    }
+}
+
+impl<T: IntoObject> IntoObject for Vec<T> {
+    fn into_object(self) -> Object {
+        Object::List(self.into_iter().map(IntoObject::into_object).collect())
+    }
+}
+
+impl<T: FromObject> FromObject for Vec<T> {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        match object {
+            Object::List(vals) => vals.iter().map(T::from_object).collect(),
+            object => Err(conversion_error("list", object)),
+        }
+    }
+}
+
+// Monkey has no maps, so a map is a list of `[key, value]` pairs, sorted by key.
+impl<T: IntoObject> IntoObject for HashMap<String, T> {
+    fn into_object(self) -> Object {
+        let mut pairs: Vec<(String, T)> = self.into_iter().collect();
+        pairs.sort_by(|(left, _), (right, _)| left.cmp(right));
+        pairs.into_object()
+    }
+}
+
+impl<T: FromObject> FromObject for HashMap<String, T> {
+    fn from_object(object: &Object) -> Result<Self, Error> {
+        Vec::<(String, T)>::from_object(object).map(|pairs| pairs.into_iter().collect())
+    }
+}
+
+// Tuples are lists of a fixed length. As arguments, each element is a separate argument.
+macro_rules! impl_tuple {
+    ($len:expr, $($name:ident: $index:tt),*) => {
+        impl<$($name: IntoObject),*> IntoObject for ($($name,)*) {
+            fn into_object(self) -> Object {
+                Object::List(vec![$(self.$index.into_object()),*])
+            }
+        }
+
+        impl<$($name: FromObject),*> FromObject for ($($name,)*) {
+            fn from_object(object: &Object) -> Result<Self, Error> {
+                match object {
+                    Object::List(vals) if vals.len() == $len => {
+                        Ok(($($name::from_object(&vals[$index])?,)*))
+                    }
+                    object => Err(conversion_error(concat!("list of length ", $len), object)),
+                }
+            }
+        }
+
+        impl<$($name: FromObject),*> FromArgs for ($($name,)*) {
+            const ARITY: usize = $len;
+
+            fn from_args(function: &str, args: &[Object]) -> Result<Self, Error> {
+                Ok(($(
+                    $name::from_object(&args[$index])
+                        .map_err(|err| err.for_argument(function, $index + 1))?,
+                )*))
+            }
+        }
+    };
+}
+
+impl_tuple!(1, A: 0);
+impl_tuple!(2, A: 0, B: 1);
+impl_tuple!(3, A: 0, B: 1, C: 2);
+impl_tuple!(4, A: 0, B: 1, C: 2, D: 3);
+
+impl FromArgs for () {
+    const ARITY: usize = 0;
+
+    fn from_args(_function: &str, _args: &[Object]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
@@ -1,29 +1,87 @@
-use super::{BuiltIn, Object};
+use super::{BuiltIn, Error, HostFunction, Memory, Module, Object};
+use crate::input::{Input, Lines};
+use crate::module::{FileResolver, ModuleResolver};
+use crate::output::{Buffer, Output};
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::mem;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
 use std::rc::Rc;
 
 type EnvRef = Rc<RefCell<_Env>>;
 
+// How many function calls can be nested by default, which is low enough for recursion to fail with
+// an error rather than overflow the stack of the main thread. Frames are much larger without
+// optimizations, so debug builds allow fewer.
+const DEFAULT_RECURSION_LIMIT: usize = if cfg!(debug_assertions) { 100 } else { 400 };
+
 // Wrapper type to deal with RCs and RefCells, so that Env is cheap to clone
 #[derive(Debug)]
 pub struct Env(EnvRef);
 
-fn register_built_ins(env: Env) -> Env {
-    env.set("len".to_string(), Object::BuiltIn(BuiltIn::Len));
-    env.set("push".to_string(), Object::BuiltIn(BuiltIn::Push));
-    env.set("rest".to_string(), Object::BuiltIn(BuiltIn::Rest));
-    env.set("print".to_string(), Object::BuiltIn(BuiltIn::Print));
+fn built_ins() -> HashMap<String, Object> {
+    let mut built_ins = HashMap::new();
+    built_ins.insert("len".to_string(), Object::BuiltIn(BuiltIn::Len));
+    built_ins.insert("push".to_string(), Object::BuiltIn(BuiltIn::Push));
+    built_ins.insert("rest".to_string(), Object::BuiltIn(BuiltIn::Rest));
+    built_ins.insert("print".to_string(), Object::BuiltIn(BuiltIn::Print));
+    built_ins.insert("int".to_string(), Object::BuiltIn(BuiltIn::Int));
+    built_ins.insert("float".to_string(), Object::BuiltIn(BuiltIn::Float));
+    built_ins.insert("Error".to_string(), Object::BuiltIn(BuiltIn::Error));
+    built_ins.insert("first".to_string(), Object::BuiltIn(BuiltIn::First));
+    built_ins.insert("last".to_string(), Object::BuiltIn(BuiltIn::Last));
+    built_ins.insert("map".to_string(), Object::BuiltIn(BuiltIn::Map));
+    built_ins.insert("filter".to_string(), Object::BuiltIn(BuiltIn::Filter));
+    built_ins.insert("reduce".to_string(), Object::BuiltIn(BuiltIn::Reduce));
+    built_ins.insert("range".to_string(), Object::BuiltIn(BuiltIn::Range));
+    built_ins.insert("reverse".to_string(), Object::BuiltIn(BuiltIn::Reverse));
+    built_ins.insert("sort".to_string(), Object::BuiltIn(BuiltIn::Sort));
+    built_ins.insert("contains".to_string(), Object::BuiltIn(BuiltIn::Contains));
+    built_ins.insert("zip".to_string(), Object::BuiltIn(BuiltIn::Zip));
+    built_ins.insert("flatten".to_string(), Object::BuiltIn(BuiltIn::Flatten));
+    built_ins.insert("slice".to_string(), Object::BuiltIn(BuiltIn::Slice));
+    built_ins.insert("split".to_string(), Object::BuiltIn(BuiltIn::Split));
+    built_ins.insert("join".to_string(), Object::BuiltIn(BuiltIn::Join));
+    built_ins.insert("trim".to_string(), Object::BuiltIn(BuiltIn::Trim));
+    built_ins.insert("upper".to_string(), Object::BuiltIn(BuiltIn::Upper));
+    built_ins.insert("lower".to_string(), Object::BuiltIn(BuiltIn::Lower));
+    built_ins.insert("replace".to_string(), Object::BuiltIn(BuiltIn::Replace));
+    built_ins.insert(
+        "starts_with".to_string(),
+        Object::BuiltIn(BuiltIn::StartsWith),
+    );
+    built_ins.insert("ends_with".to_string(), Object::BuiltIn(BuiltIn::EndsWith));
+    built_ins.insert("find".to_string(), Object::BuiltIn(BuiltIn::Find));
+    built_ins.insert("chars".to_string(), Object::BuiltIn(BuiltIn::Chars));
+    built_ins.insert("repeat".to_string(), Object::BuiltIn(BuiltIn::Repeat));
+    built_ins.insert("format".to_string(), Object::BuiltIn(BuiltIn::Format));
+    built_ins.insert("type".to_string(), Object::BuiltIn(BuiltIn::Type));
+    built_ins.insert("str".to_string(), Object::BuiltIn(BuiltIn::Str));
+    built_ins.insert("bool".to_string(), Object::BuiltIn(BuiltIn::Bool));
+    built_ins.insert("repr".to_string(), Object::BuiltIn(BuiltIn::Repr));
+    built_ins.insert("input".to_string(), Object::BuiltIn(BuiltIn::Input));
+    built_ins.insert("read_line".to_string(), Object::BuiltIn(BuiltIn::ReadLine));
+    built_ins.insert(
+        "read_lines".to_string(),
+        Object::BuiltIn(BuiltIn::ReadLines),
+    );
+    built_ins.insert("read_file".to_string(), Object::BuiltIn(BuiltIn::ReadFile));
+    built_ins.insert(
+        "write_file".to_string(),
+        Object::BuiltIn(BuiltIn::WriteFile),
+    );
+    built_ins.insert("list_dir".to_string(), Object::BuiltIn(BuiltIn::ListDir));
+    built_ins.insert("exists".to_string(), Object::BuiltIn(BuiltIn::Exists));
     // Not all built-ins are here such as `Index` because it can be called using `[$index]`.
     // This allows us to reuse the apply logic of the built-ins for operators.
-    env
+    built_ins
 }
 
 impl Env {
     pub fn new() -> Self {
-        let env = Env(Rc::new(RefCell::new(_Env::new())));
-        register_built_ins(env)
+        let runtime = Rc::new(RefCell::new(Runtime::new()));
+        Env(Rc::new(RefCell::new(_Env::new(runtime))))
     }
 
     pub fn new_extending(parent: Self) -> Self {
@@ -31,20 +89,212 @@ impl Env {
         Env(Rc::new(RefCell::new(new_env)))
     }
 
+    // A new top level env for evaluating the module at `path`, which doesn't see the bindings of
+    // this env but shares its stdout, memory and modules.
+    pub fn new_module(&self, path: String) -> Self {
+        let runtime = Rc::clone(&self.0.borrow().runtime);
+        let mut env = _Env::new(runtime);
+        env.module_path = Some(path);
+        Env(Rc::new(RefCell::new(env)))
+    }
+
+    // The path of the module this env is in, or `None` in the program itself.
+    pub fn module_path(&self) -> Option<String> {
+        let env = self.0.borrow();
+        match &env.parent {
+            Some(parent) => parent.module_path(),
+            None => env.module_path.clone(),
+        }
+    }
+
     pub fn get(&self, key: &String) -> Option<Object> {
         self.0.borrow().get(key)
     }
 
+    // Like `get`, but only finds bindings and not the built-ins or host functions, which can be
+    // shadowed but not assigned to.
+    pub fn get_binding(&self, key: &str) -> Option<Object> {
+        self.0.borrow().get_binding(key)
+    }
+
     pub fn set(&self, key: String, val: Object) {
         self.0.borrow_mut().set(key, val);
     }
 
+    // Updates the nearest existing binding, unlike `set` which always binds in this env. Returns
+    // false if there is no such binding.
+    pub fn assign(&self, key: &str, val: Object) -> bool {
+        self.0.borrow_mut().assign(key, val)
+    }
+
+    // Marks a binding in this env as exported when it is evaluated as a module.
+    pub fn export(&self, key: String) {
+        self.0.borrow_mut().exports.push(key);
+    }
+
+    // The current values of the exported bindings.
+    pub fn exports(&self) -> HashMap<String, Object> {
+        let env = self.0.borrow();
+        env.exports
+            .iter()
+            .filter_map(|key| env.get(key).map(|val| (key.clone(), val)))
+            .collect()
+    }
+
     pub fn write_stdout(&self, msg: String) {
-        self.0.borrow_mut().write_stdout(msg);
+        self.with_runtime(|runtime| runtime.output.write_line(&msg));
     }
 
+    // The lines written since the last call, while the output is the default buffer.
     pub fn pop_stdout(&self) -> Vec<String> {
-        self.0.borrow_mut().pop_stdout()
+        self.with_runtime(|runtime| runtime.buffer.take())
+    }
+
+    pub fn set_output(&self, output: Box<dyn Output>) {
+        self.with_runtime(|runtime| runtime.output = output);
+    }
+
+    pub fn read_line(&self, prompt: Option<&str>) -> io::Result<Option<String>> {
+        self.with_runtime(|runtime| runtime.input.read_line(prompt))
+    }
+
+    pub fn set_input(&self, input: Box<dyn Input>) {
+        self.with_runtime(|runtime| runtime.input = input);
+    }
+
+    // Accounts for the heap used by a newly created object, failing if the memory limit is reached.
+    pub fn allocate(&self, object: &Object) -> Result<(), Error> {
+        self.with_runtime(|runtime| runtime.memory.allocate(object))
+    }
+
+    pub fn allocate_bytes(&self, bytes: usize) -> Result<(), Error> {
+        self.with_runtime(|runtime| runtime.memory.allocate_bytes(bytes))
+    }
+
+    pub fn set_memory_limit(&self, limit: Option<usize>) {
+        self.with_runtime(|runtime| runtime.memory.set_limit(limit));
+    }
+
+    // Counts a call which is starting, failing if as many calls as the recursion limit are already
+    // running. Every successful call to this must be followed by `exit_call` when the call ends.
+    pub fn enter_call(&self) -> Result<(), Error> {
+        self.with_runtime(|runtime| {
+            if runtime.calls >= runtime.recursion_limit {
+                return Err(Error::RecursionLimitExceeded {
+                    limit: runtime.recursion_limit,
+                });
+            }
+            runtime.calls += 1;
+            Ok(())
+        })
+    }
+
+    pub fn exit_call(&self) {
+        self.with_runtime(|runtime| runtime.calls -= 1);
+    }
+
+    pub fn set_recursion_limit(&self, limit: usize) {
+        self.with_runtime(|runtime| runtime.recursion_limit = limit);
+    }
+
+    pub fn set_filesystem_root(&self, root: Option<PathBuf>) {
+        self.with_runtime(|runtime| runtime.filesystem_root = root);
+    }
+
+    pub fn filesystem_root(&self) -> Option<PathBuf> {
+        self.with_runtime(|runtime| runtime.filesystem_root.clone())
+    }
+
+    pub fn reset_memory(&self) {
+        self.with_runtime(|runtime| runtime.memory.reset());
+    }
+
+    // Makes a host function callable from the program and from every module.
+    pub fn register_function(&self, func: HostFunction) {
+        self.with_runtime(|runtime| {
+            runtime
+                .globals
+                .insert(func.name.clone(), Object::HostFunction(Rc::new(func)))
+        });
+    }
+
+    // Removes the built-in functions, leaving only the registered host functions.
+    pub fn remove_built_ins(&self) {
+        self.with_runtime(|runtime| {
+            runtime
+                .globals
+                .retain(|_, global| !matches!(global, Object::BuiltIn(_)))
+        });
+    }
+
+    pub fn set_module_resolver(&self, resolver: Rc<dyn ModuleResolver>) {
+        self.with_runtime(|runtime| runtime.modules.resolver = Some(resolver));
+    }
+
+    // Without a resolver, modules are files in the filesystem root, like for the filesystem
+    // built-ins.
+    pub fn resolve_module(&self, path: &str) -> io::Result<String> {
+        // Don't hold the borrow of the runtime while the resolver runs.
+        let resolver = self.with_runtime(|runtime| match &runtime.modules.resolver {
+            Some(resolver) => Some(Rc::clone(resolver)),
+            None => runtime
+                .filesystem_root
+                .clone()
+                .filter(|_| !cfg!(target_arch = "wasm32"))
+                .map(|root| Rc::new(FileResolver::new(root)) as Rc<dyn ModuleResolver>),
+        });
+
+        match resolver {
+            Some(resolver) => resolver.resolve(path),
+            None => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "filesystem access is disabled",
+            )),
+        }
+    }
+
+    pub fn cached_module(&self, path: &str) -> Option<Module> {
+        self.with_runtime(|runtime| runtime.modules.cache.get(path).cloned())
+    }
+
+    // Marks the module as being evaluated until `finish_loading_module`. Fails with the chain of
+    // imports if the module is already being evaluated, i.e. the imports are circular.
+    pub fn start_loading_module(&self, path: &str) -> Result<(), Vec<String>> {
+        self.with_runtime(|runtime| {
+            let loading = &mut runtime.modules.loading;
+
+            if let Some(start) = loading.iter().position(|loading| loading == path) {
+                let mut cycle = loading[start..].to_vec();
+                cycle.push(path.to_string());
+                return Err(cycle);
+            }
+
+            loading.push(path.to_string());
+            Ok(())
+        })
+    }
+
+    // Caches the module if it was evaluated successfully.
+    pub fn finish_loading_module(&self, path: &str, module: Option<Module>) {
+        self.with_runtime(|runtime| {
+            runtime.modules.loading.retain(|loading| loading != path);
+
+            if let Some(module) = module {
+                runtime.modules.cache.insert(path.to_string(), module);
+            }
+        });
+    }
+
+    fn with_runtime<T>(&self, f: impl FnOnce(&mut Runtime) -> T) -> T {
+        let runtime = Rc::clone(&self.0.borrow().runtime);
+        let result = f(&mut runtime.borrow_mut());
+        result
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -66,20 +316,27 @@ impl PartialEq for Env {
 struct _Env {
     store: HashMap<String, Object>,
     parent: Option<Env>,
-    stdout: Vec<String>,
+    // Names of the bindings exported from a module.
+    exports: Vec<String>,
+    // Only set in the top level env of a module, which imports are relative to.
+    module_path: Option<String>,
+    runtime: Rc<RefCell<Runtime>>,
 }
 
 impl _Env {
-    fn new() -> Self {
+    fn new(runtime: Rc<RefCell<Runtime>>) -> Self {
         Self {
             store: HashMap::new(),
             parent: None,
-            stdout: Vec::new(),
+            exports: Vec::new(),
+            module_path: None,
+            runtime,
         }
     }
 
     fn new_extending(parent: Env) -> Self {
-        let mut result = Self::new();
+        let runtime = Rc::clone(&parent.0.borrow().runtime);
+        let mut result = Self::new(runtime);
         result.parent = Some(parent);
         result
     }
@@ -88,6 +345,14 @@ impl _Env {
         match (self.store.get(key).cloned(), &self.parent) {
             (Some(x), _) => Some(x),
             (None, Some(parent)) => parent.get(key),
+            (None, None) => self.runtime.borrow().globals.get(key).cloned(),
+        }
+    }
+
+    fn get_binding(&self, key: &str) -> Option<Object> {
+        match (self.store.get(key).cloned(), &self.parent) {
+            (Some(x), _) => Some(x),
+            (None, Some(parent)) => parent.get_binding(key),
             (None, None) => None,
         }
     }
@@ -96,19 +361,86 @@ impl _Env {
         self.store.insert(key, val);
     }
 
-    // TODO: More performant way to always write and read the stdout instead of going up the tree.
+    fn assign(&mut self, key: &str, val: Object) -> bool {
+        match (self.store.get_mut(key), &self.parent) {
+            (Some(binding), _) => {
+                *binding = val;
+                true
+            }
+            (None, Some(parent)) => parent.assign(key, val),
+            (None, None) => false,
+        }
+    }
+}
 
-    fn write_stdout(&mut self, msg: String) {
-        match &self.parent {
-            None => self.stdout.push(msg),
-            Some(parent) => parent.write_stdout(msg),
+// State shared by all the envs of an interpreter, including the envs of imported modules.
+struct Runtime {
+    // The built-ins and host functions, which can be shadowed by bindings.
+    globals: HashMap<String, Object>,
+    input: Box<dyn Input>,
+    output: Box<dyn Output>,
+    // The default output, kept to read what was printed to it.
+    buffer: Buffer,
+    memory: Memory,
+    // The number of function calls running, which deep recursion would overflow the stack with.
+    calls: usize,
+    recursion_limit: usize,
+    modules: Modules,
+    // The directory the filesystem built-ins are restricted to, or `None` to disable them.
+    filesystem_root: Option<PathBuf>,
+}
+
+impl Runtime {
+    fn new() -> Self {
+        let buffer = Buffer::new();
+        Self {
+            globals: built_ins(),
+            input: Box::new(Lines::default()),
+            output: Box::new(buffer.clone()),
+            buffer,
+            memory: Memory::default(),
+            calls: 0,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            modules: Modules::new(),
+            filesystem_root: None,
         }
     }
+}
 
-    fn pop_stdout(&mut self) -> Vec<String> {
-        match &self.parent {
-            None => mem::replace(&mut self.stdout, Vec::new()),
-            Some(parent) => parent.pop_stdout(),
+impl fmt::Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Runtime")
+            .field("globals", &self.globals)
+            .field("memory", &self.memory)
+            .field("modules", &self.modules)
+            .field("filesystem_root", &self.filesystem_root)
+            .finish()
+    }
+}
+
+struct Modules {
+    resolver: Option<Rc<dyn ModuleResolver>>,
+    // Modules are only evaluated once, keyed by their normalized path.
+    cache: HashMap<String, Module>,
+    // Paths of the modules being evaluated, in the order they were imported.
+    loading: Vec<String>,
+}
+
+impl Modules {
+    fn new() -> Self {
+        Self {
+            resolver: None,
+            cache: HashMap::new(),
+            loading: Vec::new(),
         }
     }
 }
+
+impl fmt::Debug for Modules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Modules")
+            .field("cache", &self.cache)
+            .field("loading", &self.loading)
+            .finish()
+    }
+}
@@ -0,0 +1,70 @@
+use super::{Error, Object};
+use std::convert::TryFrom;
+
+impl Object {
+    // Indices past the end of the list evaluate to `null`, like the `index` built-in.
+    pub fn get_index(&self, index: &Object) -> Result<Object, Error> {
+        match (self, index) {
+            (Object::List(_) | Object::Str(_), Object::Integer(i)) if *i < 0 => {
+                Err(Error::NegativeIndex {
+                    object: self.clone(),
+                })
+            }
+            (Object::List(vals), Object::Integer(i)) => {
+                Ok(vals.get(*i as usize).cloned().unwrap_or(Object::Null))
+            }
+            (Object::Str(val), Object::Integer(i)) => Ok(val
+                .chars()
+                .nth(*i as usize)
+                .map(|c| Object::Str(c.to_string()))
+                .unwrap_or(Object::Null)),
+            (Object::List(_) | Object::Str(_), index) => Err(Error::IndexType {
+                object: self.clone(),
+                index: index.clone(),
+            }),
+            (Object::Native(native), index) => native.index(index).unwrap_or_else(|| {
+                Err(Error::NotIndexable {
+                    object: self.clone(),
+                })
+            }),
+            (object, _) => Err(Error::NotIndexable {
+                object: object.clone(),
+            }),
+        }
+    }
+
+    // Lists are values, so this returns an updated copy instead of changing the list in place.
+    pub fn set_index(self, index: &Object, value: Object) -> Result<Object, Error> {
+        match (self, index) {
+            (object @ Object::List(_), Object::Integer(i)) if *i < 0 => {
+                Err(Error::NegativeIndex { object })
+            }
+            (Object::List(mut vals), Object::Integer(i)) => {
+                match usize::try_from(*i).ok().and_then(|i| vals.get_mut(i)) {
+                    Some(element) => {
+                        *element = value;
+                        Ok(Object::List(vals))
+                    }
+                    None => Err(Error::IndexOutOfRange),
+                }
+            }
+            (object @ Object::List(_), index) => Err(Error::IndexType {
+                object,
+                index: index.clone(),
+            }),
+            (object, _) => Err(Error::ItemAssignment { object }),
+        }
+    }
+
+    // Sets a nested element, e.g. `xs[0][1] = value` is `xs.set_index_path(&[0, 1], value)`.
+    pub fn set_index_path(self, indices: &[Object], value: Object) -> Result<Object, Error> {
+        match indices.split_first() {
+            None => Ok(value),
+            Some((index, [])) => self.set_index(index, value),
+            Some((index, rest)) => {
+                let element = self.get_index(index)?.set_index_path(rest, value)?;
+                self.set_index(index, element)
+            }
+        }
+    }
+}
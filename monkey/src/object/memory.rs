@@ -0,0 +1,66 @@
+use super::{Error, Object};
+use std::mem;
+
+// Approximate accounting of the heap allocated by objects created by the interpreter.
+//
+// We don't hook into the allocator, so this only counts the values that the interpreter knows it
+// has created, i.e. `Object::List`, `Object::Str` and `Object::BigInt`. Memory is never given back
+// as we don't know when objects are dropped, so the limit is a budget for the total allocated since
+// the last `reset()`, not a cap on the live heap. There is no limit by default.
+#[derive(Debug, Clone, Default)]
+pub struct Memory {
+    limit: Option<usize>,
+    used: usize,
+}
+
+impl Memory {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self { limit, used: 0 }
+    }
+
+    pub fn allocate(&mut self, object: &Object) -> Result<(), Error> {
+        self.allocate_bytes(object.heap_size())
+    }
+
+    // For checking the size of an object before creating it.
+    pub fn allocate_bytes(&mut self, bytes: usize) -> Result<(), Error> {
+        let used = self.used.saturating_add(bytes);
+
+        match self.limit {
+            Some(limit) if used > limit => Err(Error::MemoryLimitExceeded { limit }),
+            _ => {
+                self.used = used;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    pub fn used(&self) -> usize {
+        self.used
+    }
+
+    pub fn reset(&mut self) {
+        self.used = 0;
+    }
+}
+
+impl Object {
+    // Approximate number of bytes on the heap owned by this object. Cloning a list clones all of
+    // its elements, so nested values are counted as well.
+    pub fn heap_size(&self) -> usize {
+        match self {
+            Object::Str(val) => val.len(),
+            Object::Error(error) => error.kind.len() + error.message.len(),
+            Object::BigInt(val) => val.size(),
+            Object::List(vals) => vals
+                .iter()
+                .map(|val| mem::size_of::<Object>() + val.heap_size())
+                .sum(),
+            _ => 0,
+        }
+    }
+}
@@ -1,9 +1,25 @@
+mod closure;
+mod convert;
 mod env;
+mod index;
+mod memory;
+mod native;
+#[cfg(feature = "serde")]
+mod serialize;
 
 use crate::ast;
 use crate::ast::{format_vec, Operator, Statements};
+pub use crate::bigint::BigInt;
+pub use closure::Closure;
+pub(crate) use closure::{Globals, Locals};
+pub use convert::{FromArgs, FromObject, IntoObject};
 pub use env::Env;
-use std::convert::From;
+pub use memory::Memory;
+pub use native::NativeObject;
+#[cfg(feature = "serde")]
+pub use serialize::{from_object, to_object};
+use std::collections::HashMap;
+use std::convert::{From, TryFrom};
 use std::fmt;
 use std::rc::Rc;
 
@@ -12,12 +28,23 @@ pub enum Object {
     Null,
     Boolean(bool),
     Integer(isize),
+    // Integers are promoted to a `BigInt` when they don't fit in an `isize`. A `BigInt` always holds
+    // a value outside of the range of `isize`, so create it with `Object::from` to normalize it.
+    BigInt(BigInt),
+    Float(f64),
     Str(String),
     // Separate this out because it simplifies passing the specific enum variant around with helper
     // functions for function call evaluations
     Function(Function),
+    // A function compiled for the VM.
+    Closure(Rc<Closure>),
     BuiltIn(BuiltIn),
+    HostFunction(Rc<HostFunction>),
     List(Vec<Object>),
+    Module(Module),
+    // Using Rc here keeps Object small.
+    Error(Rc<ErrorObject>),
+    Native(Rc<dyn NativeObject>),
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -27,21 +54,99 @@ pub enum BuiltIn {
     Push,
     Rest,
     Print,
+    Int,
+    Float,
+    Error,
+    First,
+    Last,
+    Map,
+    Filter,
+    Reduce,
+    Range,
+    Reverse,
+    Sort,
+    Contains,
+    Zip,
+    Flatten,
+    Slice,
+    Split,
+    Join,
+    Trim,
+    Upper,
+    Lower,
+    Replace,
+    StartsWith,
+    EndsWith,
+    Find,
+    Chars,
+    Repeat,
+    Format,
+    Type,
+    Str,
+    Bool,
+    Repr,
+    Input,
+    ReadLine,
+    ReadLines,
+    ReadFile,
+    WriteFile,
+    ListDir,
+    Exists,
+}
+
+impl BuiltIn {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltIn::Len => "len",
+            BuiltIn::Index => "index",
+            BuiltIn::Push => "push",
+            BuiltIn::Rest => "rest",
+            BuiltIn::Print => "print",
+            BuiltIn::Int => "int",
+            BuiltIn::Float => "float",
+            BuiltIn::Error => "Error",
+            BuiltIn::First => "first",
+            BuiltIn::Last => "last",
+            BuiltIn::Map => "map",
+            BuiltIn::Filter => "filter",
+            BuiltIn::Reduce => "reduce",
+            BuiltIn::Range => "range",
+            BuiltIn::Reverse => "reverse",
+            BuiltIn::Sort => "sort",
+            BuiltIn::Contains => "contains",
+            BuiltIn::Zip => "zip",
+            BuiltIn::Flatten => "flatten",
+            BuiltIn::Slice => "slice",
+            BuiltIn::Split => "split",
+            BuiltIn::Join => "join",
+            BuiltIn::Trim => "trim",
+            BuiltIn::Upper => "upper",
+            BuiltIn::Lower => "lower",
+            BuiltIn::Replace => "replace",
+            BuiltIn::StartsWith => "starts_with",
+            BuiltIn::EndsWith => "ends_with",
+            BuiltIn::Find => "find",
+            BuiltIn::Chars => "chars",
+            BuiltIn::Repeat => "repeat",
+            BuiltIn::Format => "format",
+            BuiltIn::Type => "type",
+            BuiltIn::Str => "str",
+            BuiltIn::Bool => "bool",
+            BuiltIn::Repr => "repr",
+            BuiltIn::Input => "input",
+            BuiltIn::ReadLine => "read_line",
+            BuiltIn::ReadLines => "read_lines",
+            BuiltIn::ReadFile => "read_file",
+            BuiltIn::WriteFile => "write_file",
+            BuiltIn::ListDir => "list_dir",
+            BuiltIn::Exists => "exists",
+        }
+    }
 }
 
 impl fmt::Display for BuiltIn {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "<built-in function {}>",
-            match self {
-                BuiltIn::Len => "len",
-                BuiltIn::Index => "index",
-                BuiltIn::Push => "push",
-                BuiltIn::Rest => "rest",
-                BuiltIn::Print => "print",
-            }
-        )
+        write!(f, "<built-in function {}>", self.name())
     }
 }
 
@@ -66,13 +171,43 @@ impl From<isize> for Object {
     }
 }
 
+impl From<f64> for Object {
+    fn from(val: f64) -> Self {
+        Object::Float(val)
+    }
+}
+
+impl From<BigInt> for Object {
+    fn from(val: BigInt) -> Self {
+        match val.to_isize() {
+            Some(val) => Object::Integer(val),
+            None => Object::BigInt(val),
+        }
+    }
+}
+
 impl From<&str> for Object {
     fn from(val: &str) -> Self {
         Object::Str(val.to_string())
     }
 }
 
+impl From<ErrorObject> for Object {
+    fn from(val: ErrorObject) -> Self {
+        Object::Error(Rc::new(val))
+    }
+}
+
 impl Object {
+    // Not implemented as `From<usize>` so that integer literals can still be inferred as `isize`
+    // when calling `into()`.
+    pub fn from_unsigned(val: usize) -> Self {
+        match isize::try_from(val) {
+            Ok(val) => Object::Integer(val),
+            Err(_) => Object::BigInt(val.into()),
+        }
+    }
+
     pub fn is_truthy(&self) -> bool {
         match self {
             Object::Boolean(false) | Object::Null => false,
@@ -80,33 +215,107 @@ impl Object {
         }
     }
 
+    // The name of the type, as returned by `type()` and used in error messages.
     pub fn type_str(&self) -> String {
         match self {
             Object::Null => "null",
             Object::Boolean(_) => "bool",
-            Object::Integer(_) => "int",
-            Object::Function(_) => "function",
+            Object::Integer(_) | Object::BigInt(_) => "int",
+            Object::Float(_) => "float",
+            Object::Function(_) | Object::Closure(_) => "function",
             Object::Str(_) => "string",
-            Object::BuiltIn(_) => "BuiltIn",
-            Object::List(_) => "List",
+            Object::BuiltIn(_) | Object::HostFunction(_) => "builtin",
+            Object::List(_) => "list",
+            Object::Module(_) => "module",
+            Object::Error(_) => "error",
+            Object::Native(native) => native.type_name(),
         }
         .to_string()
     }
+
+    // Like `Display`, but strings are quoted and escaped like in the source, also inside lists.
+    pub fn repr(&self) -> String {
+        match self {
+            Object::Str(val) => {
+                let mut quoted = String::from('"');
+                let mut chars = val.chars().peekable();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => quoted.push_str("\\\""),
+                        '\\' => quoted.push_str("\\\\"),
+                        '\n' => quoted.push_str("\\n"),
+                        '\t' => quoted.push_str("\\t"),
+                        '\r' => quoted.push_str("\\r"),
+                        // Only `${` would start an interpolation.
+                        '$' if chars.peek() == Some(&'{') => quoted.push_str("\\$"),
+                        c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+                        c => quoted.push(c),
+                    }
+                }
+                quoted.push('"');
+                quoted
+            }
+            Object::List(vals) => format!(
+                "[{}]",
+                vals.iter()
+                    .map(|val| val.repr())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            object => object.to_string(),
+        }
+    }
+
     pub fn apply_operator(self, operator: Operator, other: Object) -> Result<Object, Error> {
         use Object::*;
         use Operator::*;
 
         match (operator, self, other) {
-            (Plus, Integer(left), Integer(right)) => Ok(Integer(left + right)),
-            (Minus, Integer(left), Integer(right)) => Ok(Integer(left - right)),
-            (Multiply, Integer(left), Integer(right)) => Ok(Integer(left * right)),
-            (Divide, Integer(left), Integer(right)) => Ok(Integer(left / right)),
+            (Divide | Modulo, Integer(_), Integer(0)) => Err(self::Error::ZeroDivision),
+            (Plus, Integer(left), Integer(right)) => {
+                checked(left.checked_add(right), Plus, left, right)
+            }
+            (Minus, Integer(left), Integer(right)) => {
+                checked(left.checked_sub(right), Minus, left, right)
+            }
+            (Multiply, Integer(left), Integer(right)) => {
+                checked(left.checked_mul(right), Multiply, left, right)
+            }
+            (Divide, Integer(left), Integer(right)) => {
+                checked(left.checked_div(right), Divide, left, right)
+            }
+            (Modulo, Integer(left), Integer(right)) => {
+                checked(left.checked_rem(right), Modulo, left, right)
+            }
             (LessThan, Integer(left), Integer(right)) => Ok(Boolean(left < right)),
             (GreaterThan, Integer(left), Integer(right)) => Ok(Boolean(left > right)),
+            (LessThanEqual, Integer(left), Integer(right)) => Ok(Boolean(left <= right)),
+            (GreaterThanEqual, Integer(left), Integer(right)) => Ok(Boolean(left >= right)),
+            (Equal, Integer(left), Integer(right)) => Ok(Boolean(left == right)),
+            (NotEqual, Integer(left), Integer(right)) => Ok(Boolean(left != right)),
+            // Integers are compared exactly, as floats can't hold every large integer.
+            (
+                operator @ (Plus | Minus | Multiply | Divide | Modulo | LessThan | GreaterThan
+                | LessThanEqual | GreaterThanEqual | Equal | NotEqual),
+                left @ (Integer(_) | BigInt(_)),
+                right @ (Integer(_) | BigInt(_)),
+            ) => apply_big_operator(operator, left.into_big(), right.into_big()),
+            (
+                operator @ (Plus | Minus | Multiply | Divide | Modulo | LessThan | GreaterThan
+                | LessThanEqual | GreaterThanEqual | Equal | NotEqual),
+                left @ Float(_),
+                right @ (Integer(_) | BigInt(_) | Float(_)),
+            )
+            | (
+                operator @ (Plus | Minus | Multiply | Divide | Modulo | LessThan | GreaterThan
+                | LessThanEqual | GreaterThanEqual | Equal | NotEqual),
+                left @ (Integer(_) | BigInt(_)),
+                right @ Float(_),
+            ) => apply_float_operator(operator, left.to_f64(), right.to_f64()),
             (Plus, Str(left), Str(right)) => Ok(Str(left + &right)),
             (Equal, left, right) => Ok(Boolean(left == right)),
             (NotEqual, left, right) => Ok(Boolean(left != right)),
-            (operator, left, right) => Err(Error::TypeMismatch {
+            (operator, left, right) => Err(self::Error::TypeMismatch {
                 operator: operator,
                 left: left,
                 right: right,
@@ -120,14 +329,106 @@ impl Object {
         match (operator, self) {
             (Not, Boolean(true)) => Ok(Boolean(false)),
             (Not, Boolean(false)) => Ok(Boolean(true)),
-            (Not, Integer(_)) => Ok(Boolean(false)),
-            (Minus, Integer(val)) => Ok(Integer(-val)),
-            (operator, right) => Err(Error::UnknownOperation {
+            (Not, Integer(_)) | (Not, BigInt(_)) | (Not, Float(_)) => Ok(Boolean(false)),
+            (Minus, Integer(val)) => Ok(match val.checked_neg() {
+                Some(val) => Integer(val),
+                None => (-crate::bigint::BigInt::from(val)).into(),
+            }),
+            (Minus, BigInt(val)) => Ok((-val).into()),
+            (Minus, Float(val)) => Ok(Float(-val)),
+            (operator, right) => Err(self::Error::UnknownOperation {
                 operator: operator,
                 right: right,
             }),
         }
     }
+
+    // Only valid for integers.
+    fn into_big(self) -> BigInt {
+        match self {
+            Object::Integer(val) => val.into(),
+            Object::BigInt(val) => val,
+            object => panic!("Expected an integer but received {}", object.type_str()),
+        }
+    }
+
+    // Only valid for numbers.
+    fn to_f64(&self) -> f64 {
+        match self {
+            Object::Integer(val) => *val as f64,
+            Object::BigInt(val) => val.to_f64(),
+            Object::Float(val) => *val,
+            object => panic!("Expected a number but received {}", object.type_str()),
+        }
+    }
+}
+
+// Integer arithmetic is done on `isize` where possible, and falls back to `BigInt` on overflow.
+fn checked(
+    result: Option<isize>,
+    operator: Operator,
+    left: isize,
+    right: isize,
+) -> Result<Object, Error> {
+    match result {
+        Some(val) => Ok(Object::Integer(val)),
+        None => apply_big_operator(operator, left.into(), right.into()),
+    }
+}
+
+// Used when at least one of the operands is a float.
+fn apply_float_operator(operator: Operator, left: f64, right: f64) -> Result<Object, Error> {
+    use Operator::*;
+
+    match operator {
+        Plus => Ok(Object::Float(left + right)),
+        Minus => Ok(Object::Float(left - right)),
+        Multiply => Ok(Object::Float(left * right)),
+        Divide if right == 0.0 => Err(Error::ZeroDivision),
+        Divide => Ok(Object::Float(left / right)),
+        Modulo if right == 0.0 => Err(Error::ZeroDivision),
+        Modulo => Ok(Object::Float(left % right)),
+        LessThan => Ok((left < right).into()),
+        GreaterThan => Ok((left > right).into()),
+        LessThanEqual => Ok((left <= right).into()),
+        GreaterThanEqual => Ok((left >= right).into()),
+        Equal => Ok((left == right).into()),
+        NotEqual => Ok((left != right).into()),
+        operator => Err(Error::TypeMismatch {
+            operator,
+            left: left.into(),
+            right: right.into(),
+        }),
+    }
+}
+
+fn apply_big_operator(operator: Operator, left: BigInt, right: BigInt) -> Result<Object, Error> {
+    use Operator::*;
+
+    match operator {
+        Plus => Ok((left + right).into()),
+        Minus => Ok((left - right).into()),
+        Multiply => Ok((left * right).into()),
+        Divide => left
+            .checked_div(&right)
+            .map(Object::from)
+            .ok_or(Error::ZeroDivision),
+        Modulo => left
+            .checked_rem(&right)
+            .map(Object::from)
+            .ok_or(Error::ZeroDivision),
+        LessThan => Ok((left < right).into()),
+        GreaterThan => Ok((left > right).into()),
+        LessThanEqual => Ok((left <= right).into()),
+        GreaterThanEqual => Ok((left >= right).into()),
+        Equal => Ok((left == right).into()),
+        NotEqual => Ok((left != right).into()),
+        operator => Err(Error::TypeMismatch {
+            operator,
+            left: left.into(),
+            right: right.into(),
+        }),
+    }
 }
 
 impl fmt::Display for Object {
@@ -136,18 +437,74 @@ impl fmt::Display for Object {
             Object::Null => write!(f, "null"),
             Object::Boolean(val) => write!(f, "{}", val),
             Object::Integer(val) => write!(f, "{}", val),
+            Object::BigInt(val) => write!(f, "{}", val),
+            // Use the debug representation so that there is always a decimal point, e.g. `1.0`.
+            Object::Float(val) => write!(f, "{:?}", val),
             Object::Str(val) => write!(f, "{}", val),
             Object::Function(func) => write!(f, "{}", func),
+            Object::Closure(closure) => write!(f, "{}", closure),
             Object::BuiltIn(built_in) => write!(f, "{}", built_in),
+            Object::HostFunction(func) => write!(f, "{}", func),
             Object::List(values) => write!(f, "[{}]", format_vec(values)),
+            Object::Module(module) => write!(f, "{}", module),
+            Object::Error(error) => write!(f, "{}", error),
+            Object::Native(native) => write!(f, "{}", native),
         }
     }
 }
 
+// The exports of an imported module, accessed with `module.name`.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Module {
+    pub path: String,
+    pub exports: Rc<HashMap<String, Object>>,
+}
+
+impl fmt::Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<module '{}'>", self.path)
+    }
+}
+
+// Errors are values which can be thrown and caught. Errors from the interpreter, like a `TypeError`,
+// become error objects with the same kind and message when they are caught.
+#[derive(PartialEq, Debug, Clone)]
+pub struct ErrorObject {
+    pub kind: String,
+    pub message: String,
+}
+
+impl ErrorObject {
+    pub fn new(kind: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            message: message.into(),
+        }
+    }
+
+    // Uses the message of an interpreter error, without the kind it starts with.
+    pub fn from_error(kind: &str, err: &impl fmt::Display) -> Self {
+        let message = err.to_string();
+        let message = message
+            .strip_prefix(kind)
+            .and_then(|message| message.strip_prefix(": "))
+            .map(|message| message.to_string())
+            .unwrap_or(message);
+
+        Self::new(kind, message)
+    }
+}
+
+impl fmt::Display for ErrorObject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub struct Function {
     // Using Rc here makes Function cheap to clone
-    pub params: Rc<Vec<String>>,
+    pub params: Rc<Vec<ast::Param>>,
     pub body: Rc<Statements>,
     pub env: Env,
 }
@@ -174,18 +531,70 @@ impl fmt::Debug for Function {
 }
 
 impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_function(f, &self.params, &self.body)
+    }
+}
+
+// Shared with `Closure`, so that functions look the same with both backends.
+fn fmt_function(f: &mut fmt::Formatter, params: &[ast::Param], body: &Statements) -> fmt::Result {
+    write!(
+        f,
+        "fn({}) {{{}}}",
+        format_vec(params),
+        body.iter().fold(String::from("\n"), |acc, line| acc
+          // 4 spaces for indentation
+            + &format!("    {};\n", line))
+    )
+}
+
+type HostFn = dyn Fn(&[Object]) -> Result<Object, Error>;
+
+// A function registered by the application embedding the interpreter, which is called with the
+// evaluated arguments.
+#[derive(Clone)]
+pub struct HostFunction {
+    pub name: String,
+    pub arity: usize,
+    pub func: Rc<HostFn>,
+}
+
+impl HostFunction {
+    pub fn new(
+        name: impl Into<String>,
+        arity: usize,
+        func: impl Fn(&[Object]) -> Result<Object, Error> + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            arity,
+            func: Rc::new(func),
+        }
+    }
+}
+
+impl PartialEq for HostFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && Rc::ptr_eq(&self.func, &other.func)
+    }
+}
+
+impl fmt::Debug for HostFunction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "fn({}) {{{}}}",
-            self.params.join(", "),
-            self.body.iter().fold(String::from("\n"), |acc, line| acc
-              // 4 spaces for indentation
-                + &format!("    {};\n", line))
+            "HostFunction {{ name: {:?}, arity: {} }}",
+            self.name, self.arity
         )
     }
 }
 
+impl fmt::Display for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<built-in function {}>", self.name)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     TypeMismatch {
@@ -197,8 +606,107 @@ pub enum Error {
         operator: Operator,
         right: Object,
     },
+    MemoryLimitExceeded {
+        limit: usize,
+    },
+    // More calls are nested than the recursion limit allows.
+    RecursionLimitExceeded {
+        limit: usize,
+    },
+    ZeroDivision,
+    NegativeIndex {
+        object: Object,
+    },
+    IndexType {
+        object: Object,
+        index: Object,
+    },
+    IndexOutOfRange,
+    NotIndexable {
+        object: Object,
+    },
+    ItemAssignment {
+        object: Object,
+    },
+    // Only error objects can be thrown.
+    ThrowType {
+        object: Object,
+    },
+    // A value passed to the host doesn't have the type it expects.
+    Conversion {
+        expected: String,
+        object: Object,
+    },
+    // Like `Conversion`, for an argument of a host function.
+    ArgumentType {
+        function: String,
+        index: usize,
+        expected: String,
+        object: Object,
+    },
+    // An integer which doesn't fit in the integer type of the host.
+    IntegerRange {
+        expected: String,
+        object: Object,
+    },
+    // An error from a host function, with the kind and message it is caught with.
+    Host(Box<ErrorObject>),
+}
+
+impl Error {
+    pub fn kind(&self) -> &str {
+        use Error::*;
+        match self {
+            Host(error) => &error.kind,
+            MemoryLimitExceeded { .. } => "MemoryError",
+            RecursionLimitExceeded { .. } => "RecursionError",
+            ZeroDivision => "ZeroDivisionError",
+            IndexOutOfRange => "IndexError",
+            IntegerRange { .. } => "ValueError",
+            TypeMismatch { .. }
+            | UnknownOperation { .. }
+            | NegativeIndex { .. }
+            | IndexType { .. }
+            | NotIndexable { .. }
+            | ItemAssignment { .. }
+            | ThrowType { .. }
+            | Conversion { .. }
+            | ArgumentType { .. } => "TypeError",
+        }
+    }
+
+    // Says which argument of a host function couldn't be converted.
+    pub fn for_argument(self, function: &str, index: usize) -> Self {
+        match self {
+            Error::Conversion { expected, object } => Error::ArgumentType {
+                function: function.to_string(),
+                index,
+                expected,
+                object,
+            },
+            err => err,
+        }
+    }
+
+    // The error object `catch` receives, or `None` for errors which can't be caught. Running out
+    // of memory can't be caught so that the limit can't be worked around.
+    pub fn to_catchable(&self) -> Option<ErrorObject> {
+        match self {
+            Error::MemoryLimitExceeded { .. } => None,
+            Error::Host(error) => Some(ErrorObject::clone(error)),
+            err => Some(ErrorObject::from_error(err.kind(), err)),
+        }
+    }
 }
 
+impl From<ErrorObject> for Error {
+    fn from(error: ErrorObject) -> Self {
+        Error::Host(Box::new(error))
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use Error::*;
@@ -220,6 +728,65 @@ impl fmt::Display for Error {
                 operator,
                 right.type_str(),
             ),
+            MemoryLimitExceeded { limit } => {
+                write!(f, "MemoryError: memory limit of {} bytes exceeded", limit)
+            }
+            RecursionLimitExceeded { limit } => write!(
+                f,
+                "RecursionError: maximum recursion depth of {} exceeded",
+                limit
+            ),
+            ZeroDivision => write!(f, "ZeroDivisionError: division by zero"),
+            NegativeIndex { object } => write!(
+                f,
+                "TypeError: {} indices must not be negative",
+                object.type_str()
+            ),
+            IndexType { object, index } => write!(
+                f,
+                "TypeError: {} indices must be integers, not {}",
+                object.type_str(),
+                index.type_str()
+            ),
+            IndexOutOfRange => write!(f, "IndexError: list assignment index out of range"),
+            NotIndexable { object } => write!(
+                f,
+                "TypeError: object of type '{}' has no index",
+                object.type_str()
+            ),
+            ItemAssignment { object } => write!(
+                f,
+                "TypeError: '{}' object does not support item assignment",
+                object.type_str()
+            ),
+            Conversion { expected, object } => write!(
+                f,
+                "TypeError: expected {}, not '{}'",
+                expected,
+                object.type_str()
+            ),
+            ArgumentType {
+                function,
+                index,
+                expected,
+                object,
+            } => write!(
+                f,
+                "TypeError: {}() argument {} must be {}, not '{}'",
+                function,
+                index,
+                expected,
+                object.type_str()
+            ),
+            IntegerRange { expected, object } => {
+                write!(f, "ValueError: {} is out of range for {}", object, expected)
+            }
+            Host(error) => write!(f, "{}", error),
+            ThrowType { object } => write!(
+                f,
+                "TypeError: exceptions must be errors, not {}",
+                object.type_str()
+            ),
         }
     }
 }
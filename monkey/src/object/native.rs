@@ -0,0 +1,40 @@
+use super::{Error, Object};
+use std::any::Any;
+use std::fmt;
+
+// A value owned by the application embedding the interpreter, like a handle or a config struct.
+// Scripts can pass it around, index it and call its methods, but can't look inside it.
+pub trait NativeObject: Any + fmt::Debug + fmt::Display {
+    // The name returned by `type()` and used in error messages.
+    fn type_name(&self) -> &str;
+
+    // By default a native object is only equal to itself.
+    fn equals(&self, other: &dyn NativeObject) -> bool {
+        std::ptr::eq(
+            self as *const Self as *const (),
+            other as *const dyn NativeObject as *const (),
+        )
+    }
+
+    // `object[index]`, or `None` if the object has no index.
+    fn index(&self, _index: &Object) -> Option<Result<Object, Error>> {
+        None
+    }
+
+    // `object.name(args)`, or `None` if there is no such method.
+    fn call_method(&self, _name: &str, _args: &[Object]) -> Option<Result<Object, Error>> {
+        None
+    }
+}
+
+impl dyn NativeObject {
+    pub fn downcast_ref<T: NativeObject>(&self) -> Option<&T> {
+        (self as &dyn Any).downcast_ref()
+    }
+}
+
+impl PartialEq for dyn NativeObject {
+    fn eq(&self, other: &Self) -> bool {
+        self.equals(other)
+    }
+}
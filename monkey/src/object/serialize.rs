@@ -0,0 +1,548 @@
+use super::{Error, ErrorObject, IntoObject, Object};
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+use serde::ser::{self, Serialize};
+use std::fmt;
+
+// Converts any serializable value to an object. Monkey has no maps, so maps and structs become
+// lists of `[key, value]` pairs. Unit variants become their name, and other variants become a
+// `[name, value]` pair.
+pub fn to_object<T: Serialize + ?Sized>(val: &T) -> Result<Object, Error> {
+    val.serialize(Serializer)
+}
+
+// Converts an object to any deserializable value, with the same layout as `to_object`.
+pub fn from_object<T: DeserializeOwned>(object: &Object) -> Result<T, Error> {
+    T::deserialize(Deserializer(object))
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ErrorObject::new("ValueError", msg.to_string()).into()
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ErrorObject::new("ValueError", msg.to_string()).into()
+    }
+
+    fn invalid_type(unexpected: de::Unexpected, expected: &dyn de::Expected) -> Self {
+        ErrorObject::new(
+            "TypeError",
+            format!("expected {}, not {}", expected, unexpected),
+        )
+        .into()
+    }
+}
+
+fn pair(key: Object, val: Object) -> Object {
+    Object::List(vec![key, val])
+}
+
+struct Serializer;
+
+impl ser::Serializer for Serializer {
+    type Ok = Object;
+    type Error = Error;
+    type SerializeSeq = ListSerializer;
+    type SerializeTuple = ListSerializer;
+    type SerializeTupleStruct = ListSerializer;
+    type SerializeTupleVariant = ListSerializer;
+    type SerializeMap = PairsSerializer;
+    type SerializeStruct = PairsSerializer;
+    type SerializeStructVariant = PairsSerializer;
+
+    fn serialize_bool(self, val: bool) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_i8(self, val: i8) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_i16(self, val: i16) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_i32(self, val: i32) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_i64(self, val: i64) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_u8(self, val: u8) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_u16(self, val: u16) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_u32(self, val: u32) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_u64(self, val: u64) -> Result<Object, Error> {
+        Ok(val.into_object())
+    }
+
+    fn serialize_f32(self, val: f32) -> Result<Object, Error> {
+        Ok(Object::Float(val.into()))
+    }
+
+    fn serialize_f64(self, val: f64) -> Result<Object, Error> {
+        Ok(Object::Float(val))
+    }
+
+    fn serialize_char(self, val: char) -> Result<Object, Error> {
+        Ok(Object::Str(val.to_string()))
+    }
+
+    fn serialize_str(self, val: &str) -> Result<Object, Error> {
+        Ok(Object::from(val))
+    }
+
+    fn serialize_bytes(self, val: &[u8]) -> Result<Object, Error> {
+        Ok(val.to_vec().into_object())
+    }
+
+    fn serialize_none(self) -> Result<Object, Error> {
+        Ok(Object::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, val: &T) -> Result<Object, Error> {
+        val.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Object, Error> {
+        Ok(Object::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Object, Error> {
+        Ok(Object::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Object, Error> {
+        Ok(Object::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        val: &T,
+    ) -> Result<Object, Error> {
+        val.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        val: &T,
+    ) -> Result<Object, Error> {
+        Ok(pair(Object::from(variant), to_object(val)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer::new(None, len.unwrap_or(0)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer::new(None, len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer::new(None, len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ListSerializer, Error> {
+        Ok(ListSerializer::new(Some(variant), len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<PairsSerializer, Error> {
+        Ok(PairsSerializer::new(None))
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<PairsSerializer, Error> {
+        Ok(PairsSerializer::new(None))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<PairsSerializer, Error> {
+        Ok(PairsSerializer::new(Some(variant)))
+    }
+}
+
+struct ListSerializer {
+    variant: Option<&'static str>,
+    vals: Vec<Object>,
+}
+
+impl ListSerializer {
+    fn new(variant: Option<&'static str>, len: usize) -> Self {
+        Self {
+            variant,
+            vals: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        self.vals.push(to_object(val)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Object, Error> {
+        let list = Object::List(self.vals);
+        Ok(match self.variant {
+            Some(variant) => pair(Object::from(variant), list),
+            None => list,
+        })
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        self.push(val)
+    }
+
+    fn end(self) -> Result<Object, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        self.push(val)
+    }
+
+    fn end(self) -> Result<Object, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        self.push(val)
+    }
+
+    fn end(self) -> Result<Object, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for ListSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        self.push(val)
+    }
+
+    fn end(self) -> Result<Object, Error> {
+        self.finish()
+    }
+}
+
+struct PairsSerializer {
+    variant: Option<&'static str>,
+    pairs: Vec<Object>,
+    key: Option<Object>,
+}
+
+impl PairsSerializer {
+    fn new(variant: Option<&'static str>) -> Self {
+        Self {
+            variant,
+            pairs: Vec::new(),
+            key: None,
+        }
+    }
+
+    fn finish(self) -> Result<Object, Error> {
+        let pairs = Object::List(self.pairs);
+        Ok(match self.variant {
+            Some(variant) => pair(Object::from(variant), pairs),
+            None => pairs,
+        })
+    }
+}
+
+impl ser::SerializeMap for PairsSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(to_object(key)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, val: &T) -> Result<(), Error> {
+        let key = self.key.take().unwrap_or(Object::Null);
+        self.pairs.push(pair(key, to_object(val)?));
+        Ok(())
+    }
+
+    // Maps like `HashMap` have no order, so the pairs are sorted to keep the output stable.
+    fn end(mut self) -> Result<Object, Error> {
+        self.pairs.sort_by_key(|pair| match pair {
+            Object::List(pair) => pair[0].repr(),
+            _ => String::new(),
+        });
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for PairsSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        val: &T,
+    ) -> Result<(), Error> {
+        self.pairs.push(pair(Object::from(key), to_object(val)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Object, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for PairsSerializer {
+    type Ok = Object;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        val: &T,
+    ) -> Result<(), Error> {
+        self.pairs.push(pair(Object::from(key), to_object(val)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Object, Error> {
+        self.finish()
+    }
+}
+
+struct Deserializer<'a>(&'a Object);
+
+impl<'a> Deserializer<'a> {
+    fn expected(&self, expected: &str) -> Error {
+        Error::Conversion {
+            expected: expected.to_string(),
+            object: self.0.clone(),
+        }
+    }
+
+    fn pairs(&self) -> Result<Vec<(&'a Object, &'a Object)>, Error> {
+        let expected = || self.expected("list of [key, value] pairs");
+        match self.0 {
+            Object::List(pairs) => pairs
+                .iter()
+                .map(|pair| match pair {
+                    Object::List(pair) if pair.len() == 2 => Ok((&pair[0], &pair[1])),
+                    _ => Err(expected()),
+                })
+                .collect(),
+            _ => Err(expected()),
+        }
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Deserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Object::Null => visitor.visit_unit(),
+            Object::Boolean(val) => visitor.visit_bool(*val),
+            Object::Integer(val) => visitor.visit_i64(*val as i64),
+            Object::BigInt(_) => Err(Error::IntegerRange {
+                expected: "i64".to_string(),
+                object: self.0.clone(),
+            }),
+            Object::Float(val) => visitor.visit_f64(*val),
+            Object::Str(val) => visitor.visit_str(val),
+            Object::List(vals) => visitor.visit_seq(SeqAccess(vals.iter())),
+            _ => Err(self.expected("a value which isn't a function, module, error or native")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Object::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_map(MapAccess {
+            pairs: self.pairs()?.into_iter(),
+            val: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            Object::Str(variant) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            Object::List(pair) if pair.len() == 2 => visitor.visit_enum(EnumAccess {
+                variant: &pair[0],
+                val: &pair[1],
+            }),
+            _ => Err(self.expected("variant name or [variant, value] pair")),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a>(std::slice::Iter<'a, Object>);
+
+impl<'de, 'a> de::SeqAccess<'de> for SeqAccess<'a> {
+    type Error = Error;
+
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|val| seed.deserialize(Deserializer(val)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct MapAccess<'a> {
+    pairs: std::vec::IntoIter<(&'a Object, &'a Object)>,
+    val: Option<&'a Object>,
+}
+
+impl<'de, 'a> de::MapAccess<'de> for MapAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.pairs.next() {
+            Some((key, val)) => {
+                self.val = Some(val);
+                seed.deserialize(Deserializer(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        seed.deserialize(Deserializer(self.val.take().unwrap_or(&Object::Null)))
+    }
+}
+
+struct EnumAccess<'a> {
+    variant: &'a Object,
+    val: &'a Object,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for EnumAccess<'a> {
+    type Error = Error;
+    type Variant = Deserializer<'a>;
+
+    fn variant_seed<V: de::DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Deserializer<'a>), Error> {
+        Ok((
+            seed.deserialize(Deserializer(self.variant))?,
+            Deserializer(self.val),
+        ))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for Deserializer<'a> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
+use std::rc::Rc;
+
+#[cfg(test)]
+mod tests;
+
+// Where `print` writes to, as the program runs. Each call to `print` writes one line, without the
+// trailing newline.
+pub trait Output {
+    fn write_line(&mut self, line: &str);
+}
+
+// Writes to the stdout of the process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stdout;
+
+impl Output for Stdout {
+    fn write_line(&mut self, line: &str) {
+        println!("{}", line);
+    }
+}
+
+// Keeps the lines in memory until they are taken. Clones share the same lines, so a clone can be
+// kept to read what was written to the output.
+#[derive(Debug, Clone, Default)]
+pub struct Buffer {
+    lines: Rc<RefCell<Vec<String>>>,
+}
+
+impl Buffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn take(&self) -> Vec<String> {
+        mem::take(&mut self.lines.borrow_mut())
+    }
+}
+
+impl Output for Buffer {
+    fn write_line(&mut self, line: &str) {
+        self.lines.borrow_mut().push(line.to_string());
+    }
+}
+
+// Calls a function with each line, e.g. to show it in a UI.
+pub struct Callback<F: FnMut(&str)>(pub F);
+
+impl<F: FnMut(&str)> Output for Callback<F> {
+    fn write_line(&mut self, line: &str) {
+        (self.0)(line);
+    }
+}
+
+impl<F: FnMut(&str)> fmt::Debug for Callback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Callback")
+    }
+}
@@ -0,0 +1,22 @@
+use super::{Buffer, Callback, Output};
+
+#[test]
+fn test_buffer() {
+    let buffer = Buffer::new();
+    let mut output = buffer.clone();
+    output.write_line("a");
+    output.write_line("b");
+
+    assert_eq!(vec!["a", "b"], buffer.take());
+    assert_eq!(Vec::<String>::new(), buffer.take());
+}
+
+#[test]
+fn test_callback() {
+    let mut lines = Vec::new();
+    let mut output = Callback(|line: &str| lines.push(line.to_uppercase()));
+    output.write_line("a");
+    output.write_line("b");
+
+    assert_eq!(vec!["A", "B"], lines);
+}
@@ -9,9 +9,11 @@ pub struct Errors {
 
 impl std::error::Error for Errors {}
 
+// One error per line.
 impl fmt::Display for Errors {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self)
+        let errors: Vec<String> = self.errors.iter().map(|err| err.to_string()).collect();
+        write!(f, "{}", errors.join("\n"))
     }
 }
 
@@ -34,6 +36,21 @@ pub enum ErrorExpected {
     ParenthesisForFunctionBody,
     ParameterForFunction,
     SingleIndex,
+    ValidToken,
+    AssignmentTarget,
+    Pattern,
+    DefaultForParameter,
+    ParenthesisForMatchValue,
+    BraceForMatchArms,
+    ArrowForMatchArm,
+    CommaOrClosingBrace,
+    ClosingBraceForInterpolation,
+    ModulePath,
+    AsForImport,
+    LetAfterExport,
+    BraceForBlock,
+    CatchAfterTry,
+    ParenthesisForCatch,
 }
 
 #[derive(Debug, PartialEq)]
@@ -46,6 +63,11 @@ impl std::error::Error for Error {}
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Errors from the lexer already describe what went wrong.
+        if let Some(Token::Error(err)) = &self.received {
+            return write!(f, "Error: {}", err);
+        }
+
         let expected = match self.expected {
             Identifier => "identifier",
             Expression => "expression",
@@ -58,6 +80,21 @@ impl fmt::Display for Error {
             ParenthesisForFunctionBody => "parenthesis for function body",
             ParameterForFunction => "parameter for function",
             SingleIndex => "list index must be a single integer",
+            ValidToken => "valid token",
+            AssignmentTarget => "identifier or index expression before assignment",
+            Pattern => "identifier or list pattern",
+            DefaultForParameter => "default value for parameter after a parameter with a default",
+            ParenthesisForMatchValue => "parenthesis for match value",
+            BraceForMatchArms => "brace for match arms",
+            ArrowForMatchArm => "'=>' after match pattern",
+            CommaOrClosingBrace => "comma or closing brace",
+            ClosingBraceForInterpolation => "closing brace for string interpolation",
+            ModulePath => "string with the path of the module",
+            AsForImport => "'as' after the path of the module",
+            LetAfterExport => "let statement after export",
+            BraceForBlock => "brace for block",
+            CatchAfterTry => "catch after try block",
+            ParenthesisForCatch => "parenthesis for the name of the caught error",
         };
 
         let received = match &self.received {
@@ -25,8 +25,8 @@ impl<'a> Parser<'a> {
                             params: params,
                             body: body,
                         })
-                    },
-                    _ => panic!("The upstream parser should have determined that this can only be a identifier or function literal"),
+                    }
+                    function => CallFunctionExpression::Expression(Box::new(function)),
                 },
                 arguments: args,
             })
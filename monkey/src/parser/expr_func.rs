@@ -1,5 +1,6 @@
-use crate::ast::{Expression, Function, Statements};
+use crate::ast::{Expression, Function, Param, Statements};
 use crate::parser::Parser;
+use crate::parser::Precedence;
 use crate::parser::{Error, ErrorExpected};
 use crate::token::Token;
 
@@ -15,7 +16,7 @@ impl<'a> Parser<'a> {
             })
     }
 
-    fn parse_function_params(&mut self) -> Result<Vec<String>, Error> {
+    fn parse_function_params(&mut self) -> Result<Vec<Param>, Error> {
         self.lexer
             .next()
             .ok_or(Error {
@@ -41,20 +42,45 @@ impl<'a> Parser<'a> {
             })
     }
 
-    fn chomp_function_params(&mut self, mut prev: Vec<String>) -> Result<Vec<String>, Error> {
+    fn chomp_function_params(&mut self, mut prev: Vec<Param>) -> Result<Vec<Param>, Error> {
         match self.lexer.peek() {
             Some(Token::RParen) => Ok(prev),
-            Some(_) => match self.lexer.next() {
-                Some(Token::Comma) => self.chomp_function_params(prev),
-                Some(Token::Identifier(name)) => {
-                    prev.push(name);
-                    self.chomp_function_params(prev)
+            Some(Token::Comma) => {
+                self.lexer.next();
+                self.chomp_function_params(prev)
+            }
+            Some(Token::Ellipsis) => {
+                self.lexer.next();
+                match self.lexer.next() {
+                    // The closing parenthesis is checked by `parse_function_params`.
+                    Some(Token::Identifier(name)) => {
+                        prev.push(Param::Variadic(name));
+                        Ok(prev)
+                    }
+                    received => Err(Error {
+                        expected: ErrorExpected::ParameterForFunction,
+                        received,
+                    }),
                 }
-                x => Err(Error {
-                    expected: ErrorExpected::ParameterForFunction,
-                    received: x,
-                }),
-            },
+            }
+            Some(_) => {
+                let pattern = self.next_pattern(ErrorExpected::ParameterForFunction)?;
+
+                let param = if let Some(Token::Assign) = self.lexer.peek() {
+                    self.lexer.next();
+                    Param::Optional(pattern, self.next_expression(Precedence::Lowest)?)
+                } else if let Some(Param::Optional(..)) = prev.last() {
+                    return Err(Error {
+                        expected: ErrorExpected::DefaultForParameter,
+                        received: self.lexer.next(),
+                    });
+                } else {
+                    Param::Required(pattern)
+                };
+
+                prev.push(param);
+                self.chomp_function_params(prev)
+            }
             None => Err(Error {
                 expected: ErrorExpected::ClosingParenthesis,
                 received: None,
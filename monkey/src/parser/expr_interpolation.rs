@@ -0,0 +1,39 @@
+use crate::ast::{Expression, InterpolationPart};
+use crate::parser::Parser;
+use crate::parser::Precedence;
+use crate::parser::{Error, ErrorExpected};
+use crate::token::Token;
+
+impl<'a> Parser<'a> {
+    // Expects the `TemplateStart` to have been consumed. Empty strings between interpolations are
+    // left out of the parts.
+    pub fn parse_interpolation(&mut self, start: String) -> Result<Expression, Error> {
+        let mut parts = Vec::new();
+        push_str_part(&mut parts, start);
+
+        loop {
+            let expr = self.next_expression(Precedence::Lowest)?;
+            parts.push(InterpolationPart::Expression(expr));
+
+            match self.lexer.next() {
+                Some(Token::TemplateMiddle(string)) => push_str_part(&mut parts, string),
+                Some(Token::TemplateEnd(string)) => {
+                    push_str_part(&mut parts, string);
+                    return Ok(Expression::Interpolation(parts));
+                }
+                received => {
+                    return Err(Error {
+                        expected: ErrorExpected::ClosingBraceForInterpolation,
+                        received,
+                    })
+                }
+            }
+        }
+    }
+}
+
+fn push_str_part(parts: &mut Vec<InterpolationPart>, string: String) {
+    if !string.is_empty() {
+        parts.push(InterpolationPart::Str(string));
+    }
+}
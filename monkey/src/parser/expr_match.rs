@@ -0,0 +1,68 @@
+use crate::ast::{Expression, MatchArm};
+use crate::parser::Parser;
+use crate::parser::Precedence;
+use crate::parser::{Error, ErrorExpected};
+use crate::token::Token;
+
+impl<'a> Parser<'a> {
+    // Expects the `match` to have been consumed.
+    pub fn parse_match_expression(&mut self) -> Result<Expression, Error> {
+        self.expect_token(Token::LParen, ErrorExpected::ParenthesisForMatchValue)?;
+        let value = self.next_expression(Precedence::Lowest)?;
+        self.expect_token(Token::RParen, ErrorExpected::ClosingParenthesis)?;
+
+        self.expect_token(Token::LBrace, ErrorExpected::BraceForMatchArms)?;
+        let arms = self.parse_match_arms(Vec::new())?;
+
+        Ok(Expression::Match {
+            value: Box::new(value),
+            arms,
+        })
+    }
+
+    // Arms are separated by commas, with an optional trailing comma.
+    fn parse_match_arms(&mut self, mut arms: Vec<MatchArm>) -> Result<Vec<MatchArm>, Error> {
+        if let Some(Token::RBrace) = self.lexer.peek() {
+            self.lexer.next();
+            return Ok(arms);
+        }
+
+        arms.push(self.parse_match_arm()?);
+
+        match self.lexer.next() {
+            Some(Token::Comma) => self.parse_match_arms(arms),
+            Some(Token::RBrace) => Ok(arms),
+            received => Err(Error {
+                expected: ErrorExpected::CommaOrClosingBrace,
+                received,
+            }),
+        }
+    }
+
+    fn parse_match_arm(&mut self) -> Result<MatchArm, Error> {
+        let pattern = self.next_match_pattern()?;
+
+        let guard = if let Some(Token::If) = self.lexer.peek() {
+            self.lexer.next();
+            Some(self.next_expression(Precedence::Lowest)?)
+        } else {
+            None
+        };
+
+        self.expect_token(Token::FatArrow, ErrorExpected::ArrowForMatchArm)?;
+        let body = self.next_expression(Precedence::Lowest)?;
+
+        Ok(MatchArm {
+            pattern,
+            guard,
+            body,
+        })
+    }
+
+    fn expect_token(&mut self, token: Token, expected: ErrorExpected) -> Result<(), Error> {
+        match self.lexer.next() {
+            Some(received) if received == token => Ok(()),
+            received => Err(Error { expected, received }),
+        }
+    }
+}
@@ -43,12 +43,28 @@ impl<'a> Parser<'a> {
             Token::Minus => self.parse_infix_expr(precedence, prev, Operator::Minus),
             Token::Slash => self.parse_infix_expr(precedence, prev, Operator::Divide),
             Token::Asterisk => self.parse_infix_expr(precedence, prev, Operator::Multiply),
+            Token::Percent => self.parse_infix_expr(precedence, prev, Operator::Modulo),
             Token::Equal => self.parse_infix_expr(precedence, prev, Operator::Equal),
             Token::NotEqual => self.parse_infix_expr(precedence, prev, Operator::NotEqual),
             Token::LessThan => self.parse_infix_expr(precedence, prev, Operator::LessThan),
             Token::GreaterThan => self.parse_infix_expr(precedence, prev, Operator::GreaterThan),
+            Token::LessThanEqual => {
+                self.parse_infix_expr(precedence, prev, Operator::LessThanEqual)
+            }
+            Token::GreaterThanEqual => {
+                self.parse_infix_expr(precedence, prev, Operator::GreaterThanEqual)
+            }
+            Token::And => self.parse_infix_expr(precedence, prev, Operator::And),
+            Token::Or => self.parse_infix_expr(precedence, prev, Operator::Or),
+            Token::Assign => self.parse_assign_expr(prev, token, None),
+            Token::PlusAssign => self.parse_assign_expr(prev, token, Some(Operator::Plus)),
+            Token::MinusAssign => self.parse_assign_expr(prev, token, Some(Operator::Minus)),
+            Token::AsteriskAssign => self.parse_assign_expr(prev, token, Some(Operator::Multiply)),
+            Token::SlashAssign => self.parse_assign_expr(prev, token, Some(Operator::Divide)),
+            Token::PercentAssign => self.parse_assign_expr(prev, token, Some(Operator::Modulo)),
             Token::LParen => self.parse_call_expression(prev),
             Token::LBracket => self.parse_index_expression(prev),
+            Token::Dot => self.parse_member_expression(prev),
             token => Err(Error {
                 expected: ErrorExpected::Expression,
                 received: Some(token),
@@ -84,6 +100,19 @@ impl<'a> Parser<'a> {
             })
     }
 
+    fn parse_member_expression(&mut self, prev: Expression) -> Result<Expression, Error> {
+        match self.lexer.next() {
+            Some(Token::Identifier(name)) => Ok(Expression::Member {
+                object: Box::new(prev),
+                name,
+            }),
+            received => Err(Error {
+                expected: ErrorExpected::Identifier,
+                received,
+            }),
+        }
+    }
+
     fn parse_infix_expr(
         &mut self,
         precedence: Precedence,
@@ -98,11 +127,36 @@ impl<'a> Parser<'a> {
             })
     }
 
+    // Assignments are right associative, so `a = b = 1` assigns `1` to both.
+    fn parse_assign_expr(
+        &mut self,
+        target: Expression,
+        token: Token,
+        operator: Option<Operator>,
+    ) -> Result<Expression, Error> {
+        if !is_assignable(&target) {
+            return Err(Error {
+                expected: ErrorExpected::AssignmentTarget,
+                received: Some(token),
+            });
+        }
+
+        self.next_expression(Precedence::Lowest)
+            .map(|value| Expression::Assign {
+                target: Box::new(target),
+                operator,
+                value: Box::new(value),
+            })
+    }
+
     fn next_prefix_expression(&mut self, token: Token) -> Result<Expression, Error> {
         match token {
             Token::Identifier(name) => Ok(Expression::Identifier(name)),
             Token::Int(value) => Ok(Expression::IntegerLiteral(value)),
+            Token::BigInt(value) => Ok(Expression::BigIntegerLiteral(value)),
+            Token::Float(value) => Ok(Expression::FloatLiteral(value)),
             Token::Str(value) => Ok(Expression::StringLiteral(value)),
+            Token::TemplateStart(value) => self.parse_interpolation(value),
             Token::Bang => self.parse_prefix_expr(Operator::Not),
             Token::Minus => self.parse_prefix_expr(Operator::Minus),
             Token::Semicolon => Err(Error {
@@ -114,7 +168,13 @@ impl<'a> Parser<'a> {
             Token::LParen => self.parse_grouped_expression(),
             Token::LBracket => self.parse_list_expression(),
             Token::If => self.parse_if_expression(),
+            Token::Match => self.parse_match_expression(),
+            Token::Try => self.parse_try_expression(),
             Token::Function => self.parse_function_expression(),
+            Token::Error(err) => Err(Error {
+                expected: ErrorExpected::ValidToken,
+                received: Some(Token::Error(err)),
+            }),
             Token::Return => Err(Error {
                 expected: ErrorExpected::PrefixTokenOrExpression,
                 received: Some(Token::Return),
@@ -148,3 +208,11 @@ impl<'a> Parser<'a> {
             })
     }
 }
+
+fn is_assignable(expr: &Expression) -> bool {
+    match expr {
+        Expression::Identifier(_) => true,
+        Expression::Index { left, .. } => is_assignable(left),
+        _ => false,
+    }
+}
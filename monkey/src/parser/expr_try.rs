@@ -0,0 +1,51 @@
+use crate::ast::{Expression, Statements};
+use crate::parser::Parser;
+use crate::parser::{Error, ErrorExpected};
+use crate::token::Token;
+
+impl<'a> Parser<'a> {
+    // Expects the `try` to have been consumed.
+    pub fn parse_try_expression(&mut self) -> Result<Expression, Error> {
+        let body = self.parse_try_block()?;
+
+        match self.lexer.next() {
+            Some(Token::Catch) => (),
+            received => {
+                return Err(Error {
+                    expected: ErrorExpected::CatchAfterTry,
+                    received,
+                })
+            }
+        }
+
+        let name = match (self.lexer.next(), self.lexer.next(), self.lexer.next()) {
+            (Some(Token::LParen), Some(Token::Identifier(name)), Some(Token::RParen)) => name,
+            (Some(Token::LParen), Some(Token::Identifier(_)), received)
+            | (Some(Token::LParen), received, _)
+            | (received, _, _) => {
+                return Err(Error {
+                    expected: ErrorExpected::ParenthesisForCatch,
+                    received,
+                })
+            }
+        };
+
+        let handler = self.parse_try_block()?;
+
+        Ok(Expression::Try {
+            body,
+            name,
+            handler,
+        })
+    }
+
+    fn parse_try_block(&mut self) -> Result<Statements, Error> {
+        match self.lexer.next() {
+            Some(Token::LBrace) => self.parse_block_statements(Vec::new()),
+            received => Err(Error {
+                expected: ErrorExpected::BraceForBlock,
+                received,
+            }),
+        }
+    }
+}
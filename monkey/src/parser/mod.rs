@@ -3,13 +3,16 @@ mod error;
 mod expr_call;
 mod expr_func;
 mod expr_if;
+mod expr_interpolation;
+mod expr_match;
 mod expr_prefix_infix;
+mod expr_try;
+mod pattern;
 mod precedence;
 #[cfg(test)]
 mod tests;
 
-use self::error::ErrorExpected;
-pub use self::error::{Error, Errors};
+pub use self::error::{Error, ErrorExpected, Errors};
 use self::precedence::Precedence;
 use crate::ast::{Program, Statement, Statements};
 use crate::lexer::Lexer;
@@ -50,6 +53,18 @@ impl<'a> Parser<'a> {
                 self.lexer.next();
                 Some(self.next_return_statement())
             }
+            Some(Token::Throw) => {
+                self.lexer.next();
+                Some(self.next_throw_statement())
+            }
+            Some(Token::Import) => {
+                self.lexer.next();
+                Some(self.next_import_statement())
+            }
+            Some(Token::Export) => {
+                self.lexer.next();
+                Some(self.next_export_statement())
+            }
             _ => Some(self.next_expression_statement()),
         })
         .map(|result| {
@@ -62,12 +77,12 @@ impl<'a> Parser<'a> {
     }
 
     fn next_let_statement(&mut self) -> Result<Statement, Error> {
-        self.next_let_statement_identifier()
-            .and_then(|name| self.next_let_statement_assign().map(|_| name))
-            .and_then(|name| {
+        self.next_pattern(ErrorExpected::Identifier)
+            .and_then(|pattern| self.next_let_statement_assign().map(|_| pattern))
+            .and_then(|pattern| {
                 let result = self
                     .next_expression(Precedence::Lowest)
-                    .map(|expression| Statement::Let(name, expression));
+                    .map(|expression| Statement::Let(pattern, expression));
 
                 if let Some(Token::Semicolon) = self.lexer.peek() {
                     self.lexer.next();
@@ -76,22 +91,6 @@ impl<'a> Parser<'a> {
             })
     }
 
-    fn next_let_statement_identifier(&mut self) -> Result<String, Error> {
-        self.lexer
-            .next()
-            .ok_or(Error {
-                expected: ErrorExpected::Identifier,
-                received: None,
-            })
-            .and_then(|token| match token {
-                Token::Identifier(name) => Ok(name),
-                unexpected => Err(Error {
-                    expected: ErrorExpected::Identifier,
-                    received: Some(unexpected),
-                }),
-            })
-    }
-
     fn next_let_statement_assign(&mut self) -> Result<Token, Error> {
         self.lexer
             .next()
@@ -108,6 +107,69 @@ impl<'a> Parser<'a> {
             })
     }
 
+    fn next_throw_statement(&mut self) -> Result<Statement, Error> {
+        let result = self
+            .next_expression(Precedence::Lowest)
+            .map(Statement::Throw);
+
+        if let Some(Token::Semicolon) = self.lexer.peek() {
+            self.lexer.next();
+        }
+
+        result
+    }
+
+    fn next_import_statement(&mut self) -> Result<Statement, Error> {
+        let path = match self.lexer.next() {
+            Some(Token::Str(path)) => path,
+            received => {
+                return Err(Error {
+                    expected: ErrorExpected::ModulePath,
+                    received,
+                })
+            }
+        };
+
+        match self.lexer.next() {
+            Some(Token::As) => (),
+            received => {
+                return Err(Error {
+                    expected: ErrorExpected::AsForImport,
+                    received,
+                })
+            }
+        }
+
+        let name = match self.lexer.next() {
+            Some(Token::Identifier(name)) => name,
+            received => {
+                return Err(Error {
+                    expected: ErrorExpected::Identifier,
+                    received,
+                })
+            }
+        };
+
+        if let Some(Token::Semicolon) = self.lexer.peek() {
+            self.lexer.next();
+        }
+
+        Ok(Statement::Import { path, name })
+    }
+
+    fn next_export_statement(&mut self) -> Result<Statement, Error> {
+        match self.lexer.next() {
+            Some(Token::Let) => match self.next_let_statement()? {
+                Statement::Let(pattern, expression) => Ok(Statement::Export(pattern, expression)),
+                _ => unreachable!("let statements are parsed as Statement::Let"),
+            },
+            received => Err(Error {
+                expected: ErrorExpected::LetAfterExport,
+                received,
+            }),
+        }
+    }
+
     fn next_return_statement(&mut self) -> Result<Statement, Error> {
         let result = self
             .next_expression(Precedence::Lowest)
@@ -0,0 +1,123 @@
+use crate::ast::{Expression, Operator, Pattern};
+use crate::parser::Parser;
+use crate::parser::{Error, ErrorExpected};
+use crate::token::Token;
+
+impl<'a> Parser<'a> {
+    // `expected` is used in the error when the next token can't start a pattern.
+    pub fn next_pattern(&mut self, expected: ErrorExpected) -> Result<Pattern, Error> {
+        self.next_pattern_with_literals(expected, false)
+    }
+
+    // Patterns in match arms can also be literals, which don't have to match.
+    pub fn next_match_pattern(&mut self) -> Result<Pattern, Error> {
+        self.next_pattern_with_literals(ErrorExpected::Pattern, true)
+    }
+
+    fn next_pattern_with_literals(
+        &mut self,
+        expected: ErrorExpected,
+        literals: bool,
+    ) -> Result<Pattern, Error> {
+        match self.lexer.next() {
+            Some(Token::Identifier(name)) if name == "_" => Ok(Pattern::Wildcard),
+            Some(Token::Identifier(name)) => Ok(Pattern::Identifier(name)),
+            Some(Token::LBracket) => self.next_list_pattern(Vec::new(), literals),
+            Some(token) if literals => self.next_literal_pattern(token),
+            received => Err(Error { expected, received }),
+        }
+    }
+
+    fn next_literal_pattern(&mut self, token: Token) -> Result<Pattern, Error> {
+        let literal = match token {
+            Token::Int(value) => Expression::IntegerLiteral(value),
+            Token::BigInt(value) => Expression::BigIntegerLiteral(value),
+            Token::Float(value) => Expression::FloatLiteral(value),
+            Token::Str(value) => Expression::StringLiteral(value),
+            Token::True => Expression::Boolean(true),
+            Token::False => Expression::Boolean(false),
+            Token::Minus => match self.lexer.next() {
+                Some(token @ (Token::Int(_) | Token::BigInt(_) | Token::Float(_))) => {
+                    let right = match self.next_literal_pattern(token)? {
+                        Pattern::Literal(right) => right,
+                        _ => unreachable!("numbers are literal patterns"),
+                    };
+                    Expression::Prefix {
+                        operator: Operator::Minus,
+                        right: Box::new(right),
+                    }
+                }
+                received => {
+                    return Err(Error {
+                        expected: ErrorExpected::Pattern,
+                        received,
+                    })
+                }
+            },
+            token => {
+                return Err(Error {
+                    expected: ErrorExpected::Pattern,
+                    received: Some(token),
+                })
+            }
+        };
+
+        Ok(Pattern::Literal(literal))
+    }
+
+    // Expects the `[` to have been consumed.
+    fn next_list_pattern(
+        &mut self,
+        mut elements: Vec<Pattern>,
+        literals: bool,
+    ) -> Result<Pattern, Error> {
+        match self.lexer.peek() {
+            Some(Token::RBracket) => {
+                self.lexer.next();
+                return Ok(Pattern::List {
+                    elements,
+                    rest: None,
+                });
+            }
+            Some(Token::Ellipsis) => {
+                self.lexer.next();
+                let rest = self.next_identifier()?;
+
+                // The rest has to be last.
+                return match self.lexer.next() {
+                    Some(Token::RBracket) => Ok(Pattern::List {
+                        elements,
+                        rest: Some(rest),
+                    }),
+                    received => Err(Error {
+                        expected: ErrorExpected::ClosingBracket,
+                        received,
+                    }),
+                };
+            }
+            _ => elements.push(self.next_pattern_with_literals(ErrorExpected::Pattern, literals)?),
+        }
+
+        match self.lexer.next() {
+            Some(Token::Comma) => self.next_list_pattern(elements, literals),
+            Some(Token::RBracket) => Ok(Pattern::List {
+                elements,
+                rest: None,
+            }),
+            received => Err(Error {
+                expected: ErrorExpected::ClosingBracket,
+                received,
+            }),
+        }
+    }
+
+    fn next_identifier(&mut self) -> Result<String, Error> {
+        match self.lexer.next() {
+            Some(Token::Identifier(name)) => Ok(name),
+            received => Err(Error {
+                expected: ErrorExpected::Identifier,
+                received,
+            }),
+        }
+    }
+}
@@ -4,6 +4,9 @@ use std::convert::From;
 #[derive(PartialOrd, PartialEq, Debug)]
 pub enum Precedence {
     Lowest,
+    Assign,
+    Or,
+    And,
     Equals,
     LessGreater,
     Sum,
@@ -17,14 +20,26 @@ impl From<&Token> for Precedence {
         match token {
             Token::Equal => Precedence::Equals,
             Token::NotEqual => Precedence::Equals,
+            Token::Assign
+            | Token::PlusAssign
+            | Token::MinusAssign
+            | Token::AsteriskAssign
+            | Token::SlashAssign
+            | Token::PercentAssign => Precedence::Assign,
+            Token::Or => Precedence::Or,
+            Token::And => Precedence::And,
             Token::LessThan => Precedence::LessGreater,
             Token::GreaterThan => Precedence::LessGreater,
+            Token::LessThanEqual => Precedence::LessGreater,
+            Token::GreaterThanEqual => Precedence::LessGreater,
             Token::Plus => Precedence::Sum,
             Token::Minus => Precedence::Sum,
             Token::Slash => Precedence::Product,
             Token::Asterisk => Precedence::Product,
+            Token::Percent => Precedence::Product,
             Token::LParen => Precedence::Call,
             Token::LBracket => Precedence::Call,
+            Token::Dot => Precedence::Call,
             _ => Precedence::Lowest,
         }
     }
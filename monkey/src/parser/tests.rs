@@ -1,4 +1,8 @@
-use crate::ast::{Expression, Function, Operator, Statement, Statements};
+use crate::ast::{
+    CallFunctionExpression, Expression, Function, InterpolationPart, MatchArm, Operator, Param,
+    Pattern, Statement, Statements,
+};
+use crate::lexer;
 use crate::lexer::Lexer;
 use crate::parser::{Error, ErrorExpected, Parser};
 use crate::token::Token;
@@ -10,13 +14,102 @@ fn test_let_statements() {
 
     test_parser_success(
         vec![
-            Statement::Let("x".to_string(), Expression::IntegerLiteral(5)),
-            Statement::Let("y".to_string(), Expression::IntegerLiteral(10)),
+            Statement::Let(
+                Pattern::Identifier("x".to_string()),
+                Expression::IntegerLiteral(5),
+            ),
+            Statement::Let(
+                Pattern::Identifier("y".to_string()),
+                Expression::IntegerLiteral(10),
+            ),
         ],
         inp,
     );
 }
 
+#[test]
+fn test_let_pattern_statements() {
+    let identifier = |name: &str| Pattern::Identifier(name.to_string());
+
+    test_parser_success(
+        vec![
+            Statement::Let(
+                Pattern::List {
+                    elements: vec![identifier("a"), identifier("b")],
+                    rest: None,
+                },
+                Expression::Identifier("xs".to_string()),
+            ),
+            Statement::Let(
+                Pattern::List {
+                    elements: vec![
+                        identifier("a"),
+                        Pattern::List {
+                            elements: vec![identifier("b")],
+                            rest: Some("c".to_string()),
+                        },
+                    ],
+                    rest: Some("rest".to_string()),
+                },
+                Expression::Identifier("xs".to_string()),
+            ),
+            Statement::Let(
+                Pattern::List {
+                    elements: vec![],
+                    rest: None,
+                },
+                Expression::Identifier("xs".to_string()),
+            ),
+        ],
+        "let [a, b] = xs; let [a, [b, ...c], ...rest] = xs; let [] = xs;",
+    );
+}
+
+#[test]
+fn test_let_pattern_error() {
+    let cases = vec![
+        (
+            "let [1] = xs",
+            Error {
+                expected: ErrorExpected::Pattern,
+                received: Some(Token::Int(1)),
+            },
+        ),
+        (
+            "let [a b] = xs",
+            Error {
+                expected: ErrorExpected::ClosingBracket,
+                received: Some(Token::Identifier("b".to_string())),
+            },
+        ),
+        (
+            "let [...rest, a] = xs",
+            Error {
+                expected: ErrorExpected::ClosingBracket,
+                received: Some(Token::Comma),
+            },
+        ),
+        (
+            "let [...[a]] = xs",
+            Error {
+                expected: ErrorExpected::Identifier,
+                received: Some(Token::LBracket),
+            },
+        ),
+        (
+            "fn(1) {}",
+            Error {
+                expected: ErrorExpected::ParameterForFunction,
+                received: Some(Token::Int(1)),
+            },
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_parser_error(vec![expected], inp);
+    }
+}
+
 #[test]
 fn test_return_statements() {
     let inp = "return 5;
@@ -138,6 +231,34 @@ fn test_integer_literal_expression() {
     );
 }
 
+#[test]
+fn test_big_integer_literal_expression() {
+    let inp = "9223372036854775808;
+    18446744073709551616;";
+    test_parser_success(
+        vec![
+            Statement::Expression(Expression::IntegerLiteral(9223372036854775808)),
+            Statement::Expression(Expression::BigIntegerLiteral(
+                "18446744073709551616".parse().unwrap(),
+            )),
+        ],
+        inp,
+    );
+}
+
+#[test]
+fn test_float_literal_expression() {
+    let inp = "1.5;
+    2e3;";
+    test_parser_success(
+        vec![
+            Statement::Expression(Expression::FloatLiteral(1.5)),
+            Statement::Expression(Expression::FloatLiteral(2000.0)),
+        ],
+        inp,
+    );
+}
+
 #[test]
 fn test_string_literal_expression() {
     let inp = r#""foo";
@@ -151,6 +272,30 @@ fn test_string_literal_expression() {
     );
 }
 
+#[test]
+fn test_interpolation_expression() {
+    test_parser_success(
+        vec![Statement::Expression(Expression::Interpolation(vec![
+            InterpolationPart::Str("total: ".to_string()),
+            InterpolationPart::Expression(Expression::Infix {
+                operator: Operator::Plus,
+                left: Box::new(Expression::Identifier("a".to_string())),
+                right: Box::new(Expression::Identifier("b".to_string())),
+            }),
+            InterpolationPart::Expression(Expression::StringLiteral("!".to_string())),
+        ]))],
+        r#""total: ${a + b}${"!"}";"#,
+    );
+
+    test_parser_error(
+        vec![Error {
+            expected: ErrorExpected::ClosingBraceForInterpolation,
+            received: Some(Token::Identifier("b".to_string())),
+        }],
+        r#""${a b}""#,
+    );
+}
+
 #[test]
 fn test_list_literal_expression() {
     let inp = r#"[1, 2];
@@ -336,6 +481,29 @@ fn test_prefix_expressions_error() {
     }
 }
 
+#[test]
+fn test_lexer_error() {
+    let inp = "let a = \"abc";
+
+    let lexer = Lexer::new(inp);
+    let parser = Parser::new(lexer);
+
+    let err = parser.parse().expect_err("Expect parse errors");
+    assert_eq!(
+        vec![Error {
+            expected: ErrorExpected::ValidToken,
+            received: Some(Token::Error(lexer::Error::UnterminatedString {
+                location: lexer::Location { line: 1, column: 9 },
+            })),
+        }],
+        err.errors
+    );
+    assert_eq!(
+        "Error: unterminated string starting at line 1, column 9",
+        format!("{}", err.errors[0])
+    );
+}
+
 #[test]
 fn test_infix_expressions() {
     let cases = vec![
@@ -489,7 +657,10 @@ fn test_func_expression() {
             "fn(x, y) { x + y; }",
             vec![Statement::Expression(Expression::FunctionLiteral(
                 Function {
-                    params: vec!["x".to_string(), "y".to_string()],
+                    params: vec![
+                        Param::Required(Pattern::Identifier("x".to_string())),
+                        Param::Required(Pattern::Identifier("y".to_string())),
+                    ],
                     body: vec![Statement::Expression(Expression::Infix {
                         operator: Operator::Plus,
                         left: Box::new(Expression::Identifier("x".to_string())),
@@ -502,7 +673,10 @@ fn test_func_expression() {
             "fn(x, y) { x + y; true; }",
             vec![Statement::Expression(Expression::FunctionLiteral(
                 Function {
-                    params: vec!["x".to_string(), "y".to_string()],
+                    params: vec![
+                        Param::Required(Pattern::Identifier("x".to_string())),
+                        Param::Required(Pattern::Identifier("y".to_string())),
+                    ],
                     body: vec![
                         Statement::Expression(Expression::Infix {
                             operator: Operator::Plus,
@@ -521,6 +695,330 @@ fn test_func_expression() {
     }
 }
 
+#[test]
+fn test_func_pattern_params() {
+    test_parser_success(
+        vec![Statement::Expression(Expression::FunctionLiteral(
+            Function {
+                params: vec![
+                    Param::Required(Pattern::List {
+                        elements: vec![Pattern::Identifier("x".to_string())],
+                        rest: Some("xs".to_string()),
+                    }),
+                    Param::Required(Pattern::Identifier("y".to_string())),
+                ],
+                body: vec![],
+            },
+        ))],
+        "fn([x, ...xs], y) {}",
+    );
+}
+
+#[test]
+fn test_func_default_params() {
+    test_parser_success(
+        vec![Statement::Expression(Expression::FunctionLiteral(
+            Function {
+                params: vec![
+                    Param::Required(Pattern::Identifier("x".to_string())),
+                    Param::Optional(
+                        Pattern::Identifier("y".to_string()),
+                        Expression::Infix {
+                            operator: Operator::Plus,
+                            left: Box::new(Expression::Identifier("x".to_string())),
+                            right: Box::new(Expression::IntegerLiteral(1)),
+                        },
+                    ),
+                    Param::Variadic("rest".to_string()),
+                ],
+                body: vec![],
+            },
+        ))],
+        "fn(x, y = x + 1, ...rest) {}",
+    );
+}
+
+#[test]
+fn test_func_default_params_error() {
+    let cases = vec![
+        (
+            "fn(x = 1, y) {}",
+            Error {
+                expected: ErrorExpected::DefaultForParameter,
+                received: Some(Token::RParen),
+            },
+        ),
+        (
+            "fn(...rest, y) {}",
+            Error {
+                expected: ErrorExpected::ClosingParenthesis,
+                received: Some(Token::Comma),
+            },
+        ),
+        (
+            "fn(...[a]) {}",
+            Error {
+                expected: ErrorExpected::ParameterForFunction,
+                received: Some(Token::LBracket),
+            },
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_parser_error(vec![expected], inp);
+    }
+}
+
+#[test]
+fn test_match_expression() {
+    test_parser_success(
+        vec![Statement::Expression(Expression::Match {
+            value: Box::new(Expression::Identifier("xs".to_string())),
+            arms: vec![
+                MatchArm {
+                    pattern: Pattern::Literal(Expression::Prefix {
+                        operator: Operator::Minus,
+                        right: Box::new(Expression::IntegerLiteral(1)),
+                    }),
+                    guard: None,
+                    body: Expression::StringLiteral("minus one".to_string()),
+                },
+                MatchArm {
+                    pattern: Pattern::List {
+                        elements: vec![
+                            Pattern::Literal(Expression::StringLiteral("a".to_string())),
+                            Pattern::Wildcard,
+                        ],
+                        rest: Some("rest".to_string()),
+                    },
+                    guard: Some(Expression::Identifier("rest".to_string())),
+                    body: Expression::Identifier("rest".to_string()),
+                },
+                MatchArm {
+                    pattern: Pattern::Identifier("x".to_string()),
+                    guard: None,
+                    body: Expression::Infix {
+                        operator: Operator::Plus,
+                        left: Box::new(Expression::Identifier("x".to_string())),
+                        right: Box::new(Expression::IntegerLiteral(1)),
+                    },
+                },
+            ],
+        })],
+        r#"match (xs) { -1 => "minus one", ["a", _, ...rest] if rest => rest, x => x + 1, }"#,
+    );
+
+    test_parser_success(
+        vec![Statement::Expression(Expression::Match {
+            value: Box::new(Expression::Boolean(true)),
+            arms: vec![],
+        })],
+        "match (true) {}",
+    );
+}
+
+#[test]
+fn test_match_expression_error() {
+    let cases = vec![
+        (
+            "match x { _ => 1 }",
+            Error {
+                expected: ErrorExpected::ParenthesisForMatchValue,
+                received: Some(Token::Identifier("x".to_string())),
+            },
+        ),
+        (
+            "match (x) _ => 1",
+            Error {
+                expected: ErrorExpected::BraceForMatchArms,
+                received: Some(Token::Identifier("_".to_string())),
+            },
+        ),
+        (
+            "match (x) { 1 2 }",
+            Error {
+                expected: ErrorExpected::ArrowForMatchArm,
+                received: Some(Token::Int(2)),
+            },
+        ),
+        (
+            "match (x) { 1 => 1 2 => 2 }",
+            Error {
+                expected: ErrorExpected::CommaOrClosingBrace,
+                received: Some(Token::Int(2)),
+            },
+        ),
+        (
+            "match (x) { -a => 1 }",
+            Error {
+                expected: ErrorExpected::Pattern,
+                received: Some(Token::Identifier("a".to_string())),
+            },
+        ),
+        (
+            "let 1 = x",
+            Error {
+                expected: ErrorExpected::Identifier,
+                received: Some(Token::Int(1)),
+            },
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_parser_error(vec![expected], inp);
+    }
+}
+
+#[test]
+fn test_import_export_statements() {
+    test_parser_success(
+        vec![
+            Statement::Import {
+                path: "lib/util.mk".to_string(),
+                name: "util".to_string(),
+            },
+            Statement::Export(
+                Pattern::Identifier("a".to_string()),
+                Expression::IntegerLiteral(1),
+            ),
+            Statement::Expression(Expression::Call {
+                function: CallFunctionExpression::Expression(Box::new(Expression::Member {
+                    object: Box::new(Expression::Identifier("util".to_string())),
+                    name: "helper".to_string(),
+                })),
+                arguments: vec![Expression::IntegerLiteral(1)],
+            }),
+        ],
+        r#"import "lib/util.mk" as util; export let a = 1; util.helper(1);"#,
+    );
+
+    let cases = vec![
+        ("a.b.c + 1", "(((a.b).c) + 1)"),
+        ("a.b[0]", "((a.b)[0])"),
+        ("f(1)(2)", "f(1)(2)"),
+    ];
+    for (inp, expected) in cases {
+        let program = Parser::new(Lexer::new(inp)).parse().unwrap();
+        assert_eq!(expected, program.statements[0].to_string());
+    }
+}
+
+#[test]
+fn test_import_export_statements_error() {
+    let cases = vec![
+        (
+            "import util as util",
+            Error {
+                expected: ErrorExpected::ModulePath,
+                received: Some(Token::Identifier("util".to_string())),
+            },
+        ),
+        (
+            r#"import "util.mk" util"#,
+            Error {
+                expected: ErrorExpected::AsForImport,
+                received: Some(Token::Identifier("util".to_string())),
+            },
+        ),
+        (
+            r#"import "util.mk" as "#,
+            Error {
+                expected: ErrorExpected::Identifier,
+                received: None,
+            },
+        ),
+        (
+            "export fn() {}",
+            Error {
+                expected: ErrorExpected::LetAfterExport,
+                received: Some(Token::Function),
+            },
+        ),
+        (
+            "a.1",
+            Error {
+                expected: ErrorExpected::Identifier,
+                received: Some(Token::Int(1)),
+            },
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_parser_error(vec![expected], inp);
+    }
+}
+
+#[test]
+fn test_try_expression_and_throw_statement() {
+    test_parser_success(
+        vec![Statement::Expression(Expression::Try {
+            body: vec![Statement::Throw(Expression::Call {
+                function: CallFunctionExpression::Identifier("Error".to_string()),
+                arguments: vec![Expression::StringLiteral("oops".to_string())],
+            })],
+            name: "e".to_string(),
+            handler: vec![Statement::Expression(Expression::Member {
+                object: Box::new(Expression::Identifier("e".to_string())),
+                name: "message".to_string(),
+            })],
+        })],
+        r#"try { throw Error("oops"); } catch (e) { e.message }"#,
+    );
+
+    let program = Parser::new(Lexer::new("let x = try { 1 } catch (e) { 2 };"))
+        .parse()
+        .unwrap();
+    assert_eq!(
+        "let x = try { 1 } catch (e) { 2 }",
+        program.statements[0].to_string()
+    );
+}
+
+#[test]
+fn test_try_expression_error() {
+    let cases = vec![
+        (
+            "try 1",
+            Error {
+                expected: ErrorExpected::BraceForBlock,
+                received: Some(Token::Int(1)),
+            },
+        ),
+        (
+            "try { 1 } 2",
+            Error {
+                expected: ErrorExpected::CatchAfterTry,
+                received: Some(Token::Int(2)),
+            },
+        ),
+        (
+            "try { 1 } catch e { 2 }",
+            Error {
+                expected: ErrorExpected::ParenthesisForCatch,
+                received: Some(Token::Identifier("e".to_string())),
+            },
+        ),
+        (
+            "try { 1 } catch (e { 2 }",
+            Error {
+                expected: ErrorExpected::ParenthesisForCatch,
+                received: Some(Token::LBrace),
+            },
+        ),
+        (
+            "try { 1 } catch (e) 2",
+            Error {
+                expected: ErrorExpected::BraceForBlock,
+                received: Some(Token::Int(2)),
+            },
+        ),
+    ];
+
+    for (inp, expected) in cases {
+        test_parser_error(vec![expected], inp);
+    }
+}
+
 #[test]
 fn test_operator_precedence_expression() {
     let cases = vec![
@@ -535,6 +1033,12 @@ fn test_operator_precedence_expression() {
         ("a + b * c + d / e - f", "(((a + (b * c)) + (d / e)) - f)"),
         ("5 > 4 == 3 < 4", "((5 > 4) == (3 < 4))"),
         ("5 < 4 != 3 > 4", "((5 < 4) != (3 > 4))"),
+        ("5 <= 4 == 3 >= 4", "((5 <= 4) == (3 >= 4))"),
+        ("a + b % c * d", "(a + ((b % c) * d))"),
+        ("a || b && c", "(a || (b && c))"),
+        ("a && b || c && d", "((a && b) || (c && d))"),
+        ("a == b && c != d", "((a == b) && (c != d))"),
+        ("!a || b", "((!a) || b)"),
         (
             "3 + 4 * 5 == 3 * 1 + 4 * 5",
             "((3 + (4 * 5)) == ((3 * 1) + (4 * 5)))",
@@ -587,6 +1091,53 @@ fn test_boolean_expression() {
     );
 }
 
+#[test]
+fn test_assign_expression() {
+    let cases = vec![
+        ("x = 1", "(x = 1)"),
+        ("x = y = 1 + 2", "(x = (y = (1 + 2)))"),
+        ("x += 1 * 2", "(x += (1 * 2))"),
+        ("x -= 1", "(x -= 1)"),
+        ("x *= 1", "(x *= 1)"),
+        ("x /= 1", "(x /= 1)"),
+        ("x %= 1", "(x %= 1)"),
+        ("xs[0] = a || b", "((xs[0]) = (a || b))"),
+        ("xs[i][j] += 1", "(((xs[i])[j]) += 1)"),
+    ];
+
+    for (inp, expected) in cases {
+        test_parser_success_with_str(expected, inp);
+    }
+
+    test_parser_success(
+        vec![Statement::Expression(Expression::Assign {
+            target: Box::new(Expression::Identifier("x".to_string())),
+            operator: Some(Operator::Plus),
+            value: Box::new(Expression::IntegerLiteral(1)),
+        })],
+        "x += 1;",
+    );
+}
+
+#[test]
+fn test_assign_expression_error() {
+    let cases = vec![
+        ("1 = 2", Token::Assign),
+        ("x + y = 2", Token::Assign),
+        ("f() += 1", Token::PlusAssign),
+    ];
+
+    for (inp, received) in cases {
+        test_parser_error(
+            vec![Error {
+                expected: ErrorExpected::AssignmentTarget,
+                received: Some(received),
+            }],
+            inp,
+        );
+    }
+}
+
 fn test_parser_success(expected: Statements, inp: &str) {
     let lexer = Lexer::new(inp);
     let parser = Parser::new(lexer);
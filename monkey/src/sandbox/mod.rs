@@ -0,0 +1,49 @@
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+// Joins `path` to `root`, making sure the result can't be used to reach anything outside of it.
+// Absolute paths, `..` and symlinks leading out of the root fail with `PermissionDenied`. This
+// includes dangling symlinks, since writing to one would create a file wherever it points.
+pub fn resolve(root: &Path, path: &str) -> io::Result<PathBuf> {
+    let outside = || {
+        io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("'{}' is outside of the allowed directory", path),
+        )
+    };
+
+    // Reject `..` and absolute paths before touching the filesystem.
+    let components = Path::new(path)
+        .components()
+        .map(|component| match component {
+            Component::Normal(name) => Ok(Some(name)),
+            Component::CurDir => Ok(None),
+            _ => Err(outside()),
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    // Follow the path one component at a time, so that every symlink along the way is checked.
+    // Once a component doesn't exist, the rest of the path can't contain symlinks either.
+    let canonical_root = root.canonicalize()?;
+    let mut resolved = canonical_root.clone();
+    let mut names = components.into_iter().flatten();
+    for name in names.by_ref() {
+        resolved.push(name);
+        match fs::symlink_metadata(&resolved) {
+            Ok(metadata) if metadata.file_type().is_symlink() => match resolved.canonicalize() {
+                Ok(target) if target.starts_with(&canonical_root) => resolved = target,
+                _ => return Err(outside()),
+            },
+            Ok(_) => (),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => break,
+            Err(err) => return Err(err),
+        }
+    }
+    resolved.extend(names);
+
+    Ok(resolved)
+}
@@ -0,0 +1,57 @@
+use super::resolve;
+use std::env;
+use std::fs;
+use std::io;
+
+#[test]
+fn test_resolve() {
+    let root = env::temp_dir().join(format!("monkey-sandbox-test-{}", std::process::id()));
+    fs::create_dir_all(root.join("dir")).unwrap();
+    let canonical_root = root.canonicalize().unwrap();
+
+    let tests = vec![
+        ("dir", Some("dir")),
+        ("./dir/a.txt", Some("dir/a.txt")),
+        ("missing/a.txt", Some("missing/a.txt")),
+        ("../a.txt", None),
+        ("dir/../a.txt", None),
+        ("/etc", None),
+    ];
+    let results: Vec<_> = tests.iter().map(|(path, _)| resolve(&root, path)).collect();
+
+    // Symlinks are followed as long as they stay inside the root.
+    #[cfg(unix)]
+    let symlink_results = {
+        use std::os::unix::fs::symlink;
+        symlink(root.join("dir"), root.join("inside")).unwrap();
+        symlink(env::temp_dir(), root.join("outside")).unwrap();
+        symlink(
+            env::temp_dir().join("monkey-sandbox-missing"),
+            root.join("dangling"),
+        )
+        .unwrap();
+        vec![
+            resolve(&root, "inside/a.txt").ok(),
+            resolve(&root, "outside/a.txt").ok(),
+            resolve(&root, "dangling").ok(),
+        ]
+    };
+    fs::remove_dir_all(&root).unwrap();
+
+    for ((path, expected), result) in tests.into_iter().zip(results) {
+        match expected {
+            Some(expected) => assert_eq!(canonical_root.join(expected), result.unwrap()),
+            None => assert_eq!(
+                io::ErrorKind::PermissionDenied,
+                result.unwrap_err().kind(),
+                "{}",
+                path
+            ),
+        }
+    }
+    #[cfg(unix)]
+    assert_eq!(
+        vec![Some(canonical_root.join("dir/a.txt")), None, None],
+        symlink_results
+    );
+}
@@ -1,15 +1,30 @@
 use self::Token::*;
+use crate::bigint::BigInt as BigInteger;
+use crate::lexer::Error as LexerError;
 use std::convert::From;
 use std::fmt;
 
 #[derive(PartialEq, Debug)]
 pub enum Token {
     Illegal(String),
+    Error(LexerError),
     Identifier(String),
     Int(usize),
+    // Integer literals which are too large for `Int`.
+    BigInt(BigInteger),
+    Float(f64),
     Assign,
+    // `=>` between the pattern and the body of a match arm.
+    FatArrow,
+    PlusAssign,
+    MinusAssign,
+    AsteriskAssign,
+    SlashAssign,
+    PercentAssign,
     Plus,
     Comma,
+    Dot,
+    Ellipsis,
     Semicolon,
     LParen,
     RParen,
@@ -23,6 +38,11 @@ pub enum Token {
     Slash,
     LessThan,
     GreaterThan,
+    LessThanEqual,
+    GreaterThanEqual,
+    Percent,
+    And,
+    Or,
     Equal,
     NotEqual,
     Function,
@@ -32,7 +52,35 @@ pub enum Token {
     If,
     Else,
     Return,
+    Match,
+    Import,
+    Export,
+    As,
+    Throw,
+    Try,
+    Catch,
     Str(String),
+    // Parts of a string with `${}` interpolations, e.g. `"a${b}c${d}e"` is lexed as
+    // `TemplateStart("a")`, `b`, `TemplateMiddle("c")`, `d`, `TemplateEnd("e")`.
+    TemplateStart(String),
+    TemplateMiddle(String),
+    TemplateEnd(String),
+}
+
+// Source text which doesn't affect parsing, but should be preserved for tools like formatters.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Trivia {
+    // Text after `//` until the end of the line.
+    LineComment(String),
+    // Text between `/*` and `*/`, including any nested block comments.
+    BlockComment(String),
+}
+
+// A token together with the trivia that comes before it.
+#[derive(PartialEq, Debug)]
+pub struct TokenWithTrivia {
+    pub token: Token,
+    pub leading_trivia: Vec<Trivia>,
 }
 
 impl From<String> for Token {
@@ -45,6 +93,13 @@ impl From<String> for Token {
             "if" => Token::If,
             "else" => Token::Else,
             "return" => Token::Return,
+            "match" => Token::Match,
+            "import" => Token::Import,
+            "export" => Token::Export,
+            "as" => Token::As,
+            "throw" => Token::Throw,
+            "try" => Token::Try,
+            "catch" => Token::Catch,
             _ => Token::Identifier(literal),
         }
     }
@@ -57,12 +112,26 @@ impl fmt::Display for Token {
             "{}",
             match self {
                 Illegal(string) => format!("illegal: {}", &string),
+                Error(err) => format!("{}", err),
                 Identifier(string) => format!("identifier: {}", &string),
                 Int(val) => format!("{}", &val),
+                BigInt(val) => format!("{}", &val),
+                Float(val) => format!("{:?}", &val),
                 Str(string) => format!("\"{}\"", &string),
+                TemplateStart(string) => format!("\"{}${{", &string),
+                TemplateMiddle(string) => format!("}}{}${{", &string),
+                TemplateEnd(string) => format!("}}{}\"", &string),
                 Assign => "=".to_string(),
+                FatArrow => "=>".to_string(),
+                PlusAssign => "+=".to_string(),
+                MinusAssign => "-=".to_string(),
+                AsteriskAssign => "*=".to_string(),
+                SlashAssign => "/=".to_string(),
+                PercentAssign => "%=".to_string(),
                 Plus => "+".to_string(),
                 Comma => ",".to_string(),
+                Dot => ".".to_string(),
+                Ellipsis => "...".to_string(),
                 Semicolon => ";".to_string(),
                 LParen => "(".to_string(),
                 RParen => ")".to_string(),
@@ -76,6 +145,11 @@ impl fmt::Display for Token {
                 Slash => "/".to_string(),
                 LessThan => "<".to_string(),
                 GreaterThan => ">".to_string(),
+                LessThanEqual => "<=".to_string(),
+                GreaterThanEqual => ">=".to_string(),
+                Percent => "%".to_string(),
+                And => "&&".to_string(),
+                Or => "||".to_string(),
                 Equal => "==".to_string(),
                 NotEqual => "!=".to_string(),
                 Function => "fn".to_string(),
@@ -85,6 +159,13 @@ impl fmt::Display for Token {
                 If => "if".to_string(),
                 Else => "else".to_string(),
                 Return => "return".to_string(),
+                Match => "match".to_string(),
+                Import => "import".to_string(),
+                Export => "export".to_string(),
+                As => "as".to_string(),
+                Throw => "throw".to_string(),
+                Try => "try".to_string(),
+                Catch => "catch".to_string(),
             }
         )
     }
@@ -1,16 +1,31 @@
+use crate::eval;
 use crate::object;
+use crate::object::ErrorObject;
 use std::fmt;
 
 #[derive(Debug)]
 pub enum Error {
     StackOutOfRange,
-    ObjectError(object::Error),
+    // The offset doesn't point to the start of an instruction.
+    InvalidJump(u16),
+    // An error of the program, the same as `eval` would fail with.
+    Runtime(eval::Error),
+}
+
+impl Error {
+    // The error object a handler receives, or `None` for errors which can't be caught.
+    pub fn to_catchable(&self) -> Option<ErrorObject> {
+        match self {
+            Error::Runtime(err) => err.to_catchable(),
+            Error::StackOutOfRange | Error::InvalidJump(_) => None,
+        }
+    }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Error::ObjectError(err) => write!(f, "VM error: {}", err),
+            Error::Runtime(err) => write!(f, "VM error: {}", err),
             _ => write!(f, "VM error: {:?}", self),
         }
     }
@@ -18,8 +33,14 @@ impl fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
+impl From<eval::Error> for Error {
+    fn from(err: eval::Error) -> Self {
+        Error::Runtime(err)
+    }
+}
+
 impl From<object::Error> for Error {
     fn from(err: object::Error) -> Self {
-        Error::ObjectError(err)
+        Error::Runtime(err.into())
     }
 }
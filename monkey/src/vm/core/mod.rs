@@ -1,7 +1,12 @@
-use crate::ast::Operator;
+use crate::ast::{Operator, Param, Pattern};
 use crate::bytecode::Instruction;
 use crate::compiler;
-use crate::object::{Object, FALSE, TRUE};
+use crate::compiler::TableKey;
+use crate::eval;
+use crate::object;
+use crate::object::{Closure, Env, Globals, Locals, Object, FALSE, NULL, TRUE};
+use std::cell::RefCell;
+use std::rc::Rc;
 
 pub use error::Error;
 mod error;
@@ -13,158 +18,627 @@ pub struct Vm {
     // For testing.
     // TODO: Put this into the Stack object.
     pub last_popped: Option<Object>,
+    // Where built-ins and host functions are looked up, and what they run with.
+    pub env: Env,
+}
+
+// The code being run, and the bindings it can access.
+struct Frame<'a> {
+    code: &'a compiler::Output,
+    globals: &'a Rc<Globals>,
+    // The locals of the function being run, or `None` at the top level.
+    locals: Option<&'a Rc<Locals>>,
+    // The number of arguments the function was called with.
+    num_args: usize,
 }
 
 // TODO: Make this a proper struct.
 pub type Stack = Vec<Object>;
 
-// struct OrderedInstructions {
-//     instructions: Vec<Instruction>,
-//     index: usize,
-// }
-
-// impl Iterator for OrderedInstructions {
-//     type Item = &Instruction;
-
-//     fn next(&mut self) -> Option<Self::Item> {
-//         match self.instructions.get(self.index) {
-//             None => None,
-//             // Some(Instruction::OpJump(index)) | Some(Instruction::OpJump(index)) => todo!(),
-//             x => x,
-//         }
-//     }
-// }
-
 impl Vm {
-    pub fn new() -> Self {
-        Self { last_popped: None }
+    pub fn new(env: Env) -> Self {
+        Self {
+            last_popped: None,
+            env,
+        }
     }
 
-    pub fn run(&mut self, stack: Stack, compiled: compiler::Output) -> Result<Stack, Error> {
-        let compiler::Output {
-            constants,
-            instructions,
-            ..
-        } = compiled;
-
-        instructions
-            .into_iter()
-            // TOOD make the stack a field.
-            // TODO use try_fold instead.
-            .fold(Ok(stack), |result, instruction| {
-                // todo
-                result.and_then(|mut stack| match instruction {
-                    Instruction::OpConstant(i) => {
-                        ith_object(&constants, i as usize).map(|constant| {
-                            stack.push(constant);
-                            stack
-                        })
-                    }
-                    Instruction::OpPop => {
-                        let top = top_object(&stack)?;
-
-                        self.last_popped = Some(top);
-                        Ok(stack)
-                    }
-                    Instruction::OpAdd => {
-                        let (left, right) = top_pair_object(&mut stack)?;
+    pub fn run(&mut self, stack: Stack, mut compiled: compiler::Output) -> Result<Stack, Error> {
+        self.env.reset_memory();
+
+        let globals = Rc::new(Globals::new(
+            std::mem::take(&mut compiled.names),
+            std::mem::take(&mut compiled.scope),
+        ));
+        let frame = Frame {
+            code: &compiled,
+            globals: &globals,
+            locals: None,
+            num_args: 0,
+        };
+
+        // TOOD make the stack a field.
+        let mut stack = stack;
+        // A return at the top level ends the program with its value.
+        if let Some(returned) = self.run_frame(&frame, &mut stack)? {
+            self.last_popped = Some(returned);
+        }
+
+        Ok(stack)
+    }
 
-                        let evaluated = left.apply_operator(Operator::Plus, right)?;
+    pub fn call_closure(
+        &mut self,
+        closure: &Closure,
+        arguments: Vec<Object>,
+    ) -> Result<Object, Error> {
+        self.env.enter_call()?;
+        let result = self.run_closure(closure, arguments);
+        self.env.exit_call();
+        result
+    }
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    Instruction::OpSub => {
-                        let (left, right) = top_pair_object(&mut stack)?;
+    fn run_closure(&mut self, closure: &Closure, arguments: Vec<Object>) -> Result<Object, Error> {
+        let func = &closure.func;
+        let arguments = eval::check_arity(&func.params, arguments)?;
+        let num_args = arguments.len();
+
+        // The arguments are the first locals, and a variadic parameter gets the remaining ones.
+        let locals = Rc::new(Locals::new(Rc::clone(func), closure.parent.clone()));
+        let mut arguments = arguments.into_iter();
+        for (index, param) in func.params.iter().enumerate() {
+            let argument = match param {
+                Param::Variadic(_) => {
+                    let rest = Object::List(arguments.by_ref().collect());
+                    self.env.allocate(&rest)?;
+                    Some(rest)
+                }
+                _ => arguments.next(),
+            };
+            locals.slots.borrow_mut()[index] = argument;
+        }
+
+        let frame = Frame {
+            code: &func.output,
+            globals: &closure.globals,
+            locals: Some(&locals),
+            num_args,
+        };
+        Ok(self.run_frame(&frame, &mut Stack::new())?.unwrap_or(NULL))
+    }
 
-                        let evaluated = left.apply_operator(Operator::Minus, right)?;
+    fn call(&mut self, func: Object, arguments: Vec<Object>) -> Result<Object, Error> {
+        match func {
+            Object::Closure(closure) => self.call_closure(&closure, arguments),
+            func => Ok(eval::call(self.env.clone(), func, arguments)?),
+        }
+    }
 
-                        stack.push(evaluated);
-                        Ok(stack)
+    // Runs the code of the frame to its end, or until it returns a value.
+    fn run_frame(&mut self, frame: &Frame, stack: &mut Stack) -> Result<Option<Object>, Error> {
+        let offsets = &frame.code.offsets;
+        // The stack of the frame starts empty, except at the top level where it is given.
+        let base = stack.len();
+        let mut ip = 0;
+
+        while let Some(instruction) = frame.code.instructions.get(ip) {
+            let offset = offsets[ip];
+            ip += 1;
+
+            let result = match instruction {
+                Instruction::OpReturnValue => return pop_object(stack).map(Some),
+                // Calls don't go through `execute`, so that its large frame isn't on the stack of
+                // every call in a recursion.
+                Instruction::OpCall(_) | Instruction::OpCallMethod(..) => {
+                    self.execute_call(instruction, frame, stack)
+                }
+                instruction => self.execute(instruction, frame, stack, &mut ip),
+            };
+
+            if let Err(err) = result {
+                // Errors in a try block continue at its handler, with the stack as it was when the
+                // block started. The innermost block is the last one containing the instruction.
+                let handler = frame
+                    .code
+                    .handlers
+                    .iter()
+                    .rev()
+                    .find(|handler| handler.start <= offset && offset < handler.end);
+
+                match (err.to_catchable(), handler) {
+                    (Some(error), Some(handler)) => {
+                        stack.truncate(base + handler.depth);
+                        stack.push(Object::from(error));
+                        ip = jump_index(offsets, offsets.len(), handler.end)?;
                     }
-                    Instruction::OpMul => {
-                        let (left, right) = top_pair_object(&mut stack)?;
+                    _ => return Err(err),
+                }
+            }
+        }
 
-                        let evaluated = left.apply_operator(Operator::Multiply, right)?;
+        Ok(None)
+    }
 
-                        stack.push(evaluated);
-                        Ok(stack)
+    fn execute_call(
+        &mut self,
+        instruction: &Instruction,
+        frame: &Frame,
+        stack: &mut Stack,
+    ) -> Result<(), Error> {
+        let constants = &frame.code.constants;
+
+        match *instruction {
+            Instruction::OpCall(len) => {
+                let arguments = top_objects(stack, len as usize)?;
+                let func = pop_object(stack)?;
+
+                let result = self.call(func, arguments)?;
+
+                stack.push(result);
+                Ok(())
+            }
+            Instruction::OpCallMethod(i, len) => {
+                let name = ith_object(constants, i as usize)?.to_string();
+                let arguments = top_objects(stack, len as usize)?;
+
+                let result = match pop_object(stack)? {
+                    Object::Native(native) => {
+                        eval::call_method(&self.env, native, name, &arguments)?
                     }
-                    Instruction::OpDiv => {
-                        let (left, right) = top_pair_object(&mut stack)?;
-
-                        let evaluated = left.apply_operator(Operator::Divide, right)?;
+                    object => self.call(eval::eval_member(object, name)?, arguments)?,
+                };
+
+                stack.push(result);
+                Ok(())
+            }
+            _ => Err(Error::StackOutOfRange),
+        }
+    }
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    Instruction::OpTrue => {
-                        stack.push(TRUE);
-                        Ok(stack)
+    fn execute(
+        &mut self,
+        instruction: &Instruction,
+        frame: &Frame,
+        stack: &mut Stack,
+        ip: &mut usize,
+    ) -> Result<(), Error> {
+        let constants = &frame.code.constants;
+        let offsets = &frame.code.offsets;
+
+        match *instruction {
+            Instruction::OpConstant(i) => {
+                let constant = ith_object(constants, i as usize)?;
+
+                stack.push(constant);
+                Ok(())
+            }
+            Instruction::OpPop => {
+                let top = pop_object(stack)?;
+
+                // Only the value of the program is kept, not those of statements in functions.
+                if frame.locals.is_none() {
+                    self.last_popped = Some(top);
+                }
+                Ok(())
+            }
+            Instruction::OpAdd => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::Plus, right)?;
+                self.env.allocate(&evaluated)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpSub => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::Minus, right)?;
+                self.env.allocate(&evaluated)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpMul => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::Multiply, right)?;
+                self.env.allocate(&evaluated)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpDiv => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::Divide, right)?;
+                self.env.allocate(&evaluated)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpMod => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::Modulo, right)?;
+                self.env.allocate(&evaluated)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpTrue => {
+                stack.push(TRUE);
+                Ok(())
+            }
+            Instruction::OpFalse => {
+                stack.push(FALSE);
+                Ok(())
+            }
+            Instruction::OpGreaterThan => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::GreaterThan, right)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpLessThan => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::LessThan, right)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpGreaterThanEqual => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::GreaterThanEqual, right)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpLessThanEqual => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::LessThanEqual, right)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpEqual => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::Equal, right)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpNotEqual => {
+                let (left, right) = top_pair_object(stack)?;
+
+                let evaluated = left.apply_operator(Operator::NotEqual, right)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpNeg => {
+                let top = pop_object(stack)?;
+
+                let evaluated = top.apply_prefix_operator(Operator::Minus)?;
+                self.env.allocate(&evaluated)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpNot => {
+                let top = pop_object(stack)?;
+
+                let evaluated = top.apply_prefix_operator(Operator::Not)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpJump(offset) => {
+                *ip = jump_index(offsets, offsets.len(), offset)?;
+                Ok(())
+            }
+            Instruction::OpJumpNotTruthy(offset) => {
+                let condition = pop_object(stack)?;
+
+                if !condition.is_truthy() {
+                    *ip = jump_index(offsets, offsets.len(), offset)?;
+                }
+                Ok(())
+            }
+            Instruction::OpNull => {
+                stack.push(NULL);
+                Ok(())
+            }
+            Instruction::OpGetGlobal(i) => {
+                let global = get_slot(&frame.globals.slots, &frame.globals.names, i)?;
+
+                stack.push(global);
+                Ok(())
+            }
+            Instruction::OpSetGlobal(i) => {
+                let value = pop_object(stack)?;
+
+                set_slot(&frame.globals.slots, i, value)
+            }
+            Instruction::OpGetLocal(i) => {
+                let locals = frame.locals.ok_or(Error::StackOutOfRange)?;
+                let local = get_slot(&locals.slots, &locals.func.output.names, i)?;
+
+                stack.push(local);
+                Ok(())
+            }
+            Instruction::OpSetLocal(i) => {
+                let value = pop_object(stack)?;
+
+                let locals = frame.locals.ok_or(Error::StackOutOfRange)?;
+                set_slot(&locals.slots, i, value)
+            }
+            Instruction::OpGetOuter(depth, i) => {
+                let locals = outer_locals(frame, depth)?;
+                let local = get_slot(&locals.slots, &locals.func.output.names, i)?;
+
+                stack.push(local);
+                Ok(())
+            }
+            Instruction::OpSetOuter(depth, i) => {
+                let value = pop_object(stack)?;
+
+                let locals = outer_locals(frame, depth)?;
+                set_slot(&locals.slots, i, value)
+            }
+            Instruction::OpGetName(i) => {
+                let name = ith_object(constants, i as usize)?.to_string();
+
+                // A global defined after the function using it, or else a built-in.
+                let global = frame.globals.scope.get(&name).and_then(|&i| {
+                    frame
+                        .globals
+                        .slots
+                        .borrow()
+                        .get(i as usize)
+                        .cloned()
+                        .flatten()
+                });
+                let object = global
+                    .or_else(|| self.env.get(&name))
+                    .ok_or(eval::Error::IdentifierNotFound { name })?;
+
+                stack.push(object);
+                Ok(())
+            }
+            Instruction::OpClosure(i) => {
+                let func = frame
+                    .code
+                    .functions
+                    .get(i as usize)
+                    .ok_or(Error::StackOutOfRange)?;
+
+                stack.push(Object::Closure(Rc::new(Closure {
+                    func: Rc::clone(func),
+                    parent: frame.locals.cloned(),
+                    globals: Rc::clone(frame.globals),
+                })));
+                Ok(())
+            }
+            Instruction::OpCall(_) | Instruction::OpCallMethod(..) => {
+                self.execute_call(instruction, frame, stack)
+            }
+            Instruction::OpJumpArgGiven(index, offset) => {
+                if frame.num_args > index as usize {
+                    *ip = jump_index(offsets, offsets.len(), offset)?;
+                }
+                Ok(())
+            }
+            Instruction::OpReturnValue => Err(Error::StackOutOfRange),
+            Instruction::OpArray(len) => {
+                let elements = top_objects(stack, len as usize)?;
+
+                let list = Object::List(elements);
+                self.env.allocate(&list)?;
+
+                stack.push(list);
+                Ok(())
+            }
+            Instruction::OpIndex => {
+                let (left, index) = top_pair_object(stack)?;
+
+                let evaluated = left.get_index(&index)?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpPeekIndex(depth) => {
+                // Leave the object and the indices on the stack for the `OpSetIndex` after it.
+                let start = stack
+                    .len()
+                    .checked_sub(depth as usize + 1)
+                    .ok_or(Error::StackOutOfRange)?;
+
+                let evaluated = stack[start + 1..]
+                    .iter()
+                    .try_fold(stack[start].clone(), |object, index| {
+                        object.get_index(index)
+                    })?;
+
+                stack.push(evaluated);
+                Ok(())
+            }
+            Instruction::OpSetIndex(depth) => {
+                let value = pop_object(stack)?;
+                let indices = top_objects(stack, depth as usize)?;
+                let object = pop_object(stack)?;
+
+                let updated = object.set_index_path(&indices, value.clone())?;
+                self.env.allocate(&updated)?;
+
+                stack.push(value);
+                stack.push(updated);
+                Ok(())
+            }
+            Instruction::OpInterpolate(len) => {
+                let parts = top_objects(stack, len as usize)?;
+
+                let string = Object::Str(parts.iter().map(|part| part.to_string()).collect());
+                self.env.allocate(&string)?;
+
+                stack.push(string);
+                Ok(())
+            }
+            Instruction::OpThrow => match pop_object(stack)? {
+                Object::Error(error) => Err(eval::Error::Thrown {
+                    error: error.as_ref().clone(),
+                }
+                .into()),
+                object => Err(object::Error::ThrowType { object }.into()),
+            },
+            Instruction::OpGetMember(i) => {
+                let name = ith_object(constants, i as usize)?.to_string();
+                let object = pop_object(stack)?;
+
+                stack.push(eval::eval_member(object, name)?);
+                Ok(())
+            }
+            Instruction::OpJumpTable(i) => {
+                let value = pop_object(stack)?;
+                let table = frame
+                    .code
+                    .tables
+                    .get(i as usize)
+                    .ok_or(Error::StackOutOfRange)?;
+
+                let target = match TableKey::new(&value) {
+                    Some(key) => table.targets.get(&key).copied(),
+                    // Values like floats can equal literals of another type, so they are compared
+                    // with each literal like in `eval`.
+                    None => table
+                        .arms
+                        .iter()
+                        .find(|(literal, _)| {
+                            literal
+                                .clone()
+                                .apply_operator(Operator::Equal, value.clone())
+                                .is_ok_and(|equal| equal.is_truthy())
+                        })
+                        .map(|&(_, target)| target),
+                };
+
+                *ip = jump_index(offsets, offsets.len(), target.unwrap_or(table.default))?;
+                Ok(())
+            }
+            Instruction::OpMatchList(i) => {
+                let value = pop_object(stack)?;
+                let pattern = ith_pattern(frame, i)?;
+
+                stack.push(Object::from(eval::matches_list(pattern, &value)));
+                Ok(())
+            }
+            Instruction::OpCheckList(i) => {
+                let value = pop_object(stack)?;
+                let pattern = ith_pattern(frame, i)?;
+
+                if eval::matches_list(pattern, &value) {
+                    Ok(())
+                } else {
+                    Err(eval::Error::PatternMismatch {
+                        pattern: pattern.clone(),
+                        value,
                     }
-                    Instruction::OpFalse => {
-                        stack.push(FALSE);
-                        Ok(stack)
+                    .into())
+                }
+            }
+            Instruction::OpSliceFrom(start) => {
+                let rest = match pop_object(stack)? {
+                    Object::List(mut values) if values.len() >= start as usize => {
+                        Object::List(values.split_off(start as usize))
                     }
-                    Instruction::OpGreaterThan => {
-                        let (left, right) = top_pair_object(&mut stack)?;
-
-                        let evaluated = left.apply_operator(Operator::GreaterThan, right)?;
+                    _ => return Err(Error::StackOutOfRange),
+                };
+                self.env.allocate(&rest)?;
+
+                stack.push(rest);
+                Ok(())
+            }
+            Instruction::OpNoMatch => {
+                let value = pop_object(stack)?;
+
+                Err(eval::Error::NonExhaustiveMatch { value }.into())
+            }
+        }
+    }
+}
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    Instruction::OpEqual => {
-                        let (left, right) = top_pair_object(&mut stack)?;
+// TODO extract stack operations into a struct.
 
-                        let evaluated = left.apply_operator(Operator::Equal, right)?;
+fn ith_object(stack: &[Object], i: usize) -> Result<Object, Error> {
+    stack.get(i).cloned().ok_or(Error::StackOutOfRange)
+}
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    Instruction::OpNotEqual => {
-                        let (left, right) = top_pair_object(&mut stack)?;
+fn ith_pattern<'a>(frame: &Frame<'a>, i: u16) -> Result<&'a Pattern, Error> {
+    frame
+        .code
+        .patterns
+        .get(i as usize)
+        .ok_or(Error::StackOutOfRange)
+}
 
-                        let evaluated = left.apply_operator(Operator::NotEqual, right)?;
+// Reading a binding before it is assigned fails like in `eval`, e.g. after `if (false) { let x = 1 }`.
+fn get_slot(
+    slots: &RefCell<Vec<Option<Object>>>,
+    names: &[String],
+    i: u16,
+) -> Result<Object, Error> {
+    match slots.borrow().get(i as usize) {
+        Some(Some(object)) => Ok(object.clone()),
+        Some(None) => Err(eval::Error::IdentifierNotFound {
+            name: names[i as usize].clone(),
+        }
+        .into()),
+        None => Err(Error::StackOutOfRange),
+    }
+}
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    Instruction::OpNeg => {
-                        let top = top_object(&stack)?;
+fn set_slot(slots: &RefCell<Vec<Option<Object>>>, i: u16, value: Object) -> Result<(), Error> {
+    let mut slots = slots.borrow_mut();
+    let slot = slots.get_mut(i as usize).ok_or(Error::StackOutOfRange)?;
 
-                        let evaluated = top.apply_prefix_operator(Operator::Minus)?;
+    *slot = Some(value);
+    Ok(())
+}
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    Instruction::OpNot => {
-                        let top = top_object(&stack)?;
+// The locals of the function `depth` functions out from the one being run.
+fn outer_locals<'a>(frame: &Frame<'a>, depth: u16) -> Result<&'a Rc<Locals>, Error> {
+    let mut locals = frame.locals.ok_or(Error::StackOutOfRange)?;
+    for _ in 0..depth {
+        locals = locals.parent.as_ref().ok_or(Error::StackOutOfRange)?;
+    }
 
-                        let evaluated = top.apply_prefix_operator(Operator::Not)?;
+    Ok(locals)
+}
 
-                        stack.push(evaluated);
-                        Ok(stack)
-                    }
-                    _ => todo!(),
-                })
-            })
+// Jumping to the offset just past the last instruction ends the program.
+fn jump_index(offsets: &[u16], len: usize, offset: u16) -> Result<usize, Error> {
+    match offsets.binary_search(&offset) {
+        Ok(i) => Ok(i),
+        Err(i) if i == len => Ok(len),
+        Err(_) => Err(Error::InvalidJump(offset)),
     }
 }
 
-// TODO extract stack operations into a struct.
-
-fn ith_object(stack: &Vec<Object>, i: usize) -> Result<Object, Error> {
-    stack.get(i).cloned().ok_or(Error::StackOutOfRange)
+fn pop_object(stack: &mut Vec<Object>) -> Result<Object, Error> {
+    stack.pop().ok_or(Error::StackOutOfRange)
 }
 
-fn top_object(stack: &Vec<Object>) -> Result<Object, Error> {
-    if stack.len() == 0 {
-        Err(Error::StackOutOfRange)
-    } else {
-        let len = stack.len() - 1;
-        ith_object(stack, len)
-    }
+// Pops `len` objects, keeping the order in which they were pushed.
+fn top_objects(stack: &mut Vec<Object>, len: usize) -> Result<Vec<Object>, Error> {
+    let start = stack.len().checked_sub(len).ok_or(Error::StackOutOfRange)?;
+
+    Ok(stack.split_off(start))
 }
 
 fn top_pair_object(stack: &mut Vec<Object>) -> Result<(Object, Object), Error> {
@@ -12,7 +12,10 @@ pub enum Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "VM error: {:?}", self)
+        match self {
+            Error::Internal(err) => write!(f, "{}", err),
+            _ => write!(f, "VM error: {:?}", self),
+        }
     }
 }
 
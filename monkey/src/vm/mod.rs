@@ -1,6 +1,7 @@
 use crate::compiler;
+use crate::eval;
 use crate::lexer::Lexer;
-use crate::object::Object;
+use crate::object::{Closure, Env, Object};
 use crate::parser::Parser;
 
 pub use self::core::Stack;
@@ -18,8 +19,13 @@ pub struct Vm {
 
 impl Vm {
     pub fn new() -> Self {
+        Self::with_env(Env::new())
+    }
+
+    // Runs with the built-ins, host functions, input and output of the env.
+    pub(crate) fn with_env(env: Env) -> Self {
         Self {
-            vm: core::Vm::new(),
+            vm: core::Vm::new(env),
         }
     }
 
@@ -34,7 +40,45 @@ impl Vm {
         self.vm.run(stack, compiled).map_err(|e| e.into())
     }
 
+    // Runs bytecode which was already compiled, e.g. with `monkey::compile`.
+    pub fn run_compiled(&mut self, compiled: compiler::Output) -> Result<Stack, Error> {
+        self.vm.run(Stack::new(), compiled).map_err(|e| e.into())
+    }
+
+    // Sets a budget for the approximate number of bytes allocated for lists and strings in a single
+    // call to `run`, or `None` to remove it. Memory isn't given back when values are dropped, so
+    // this bounds the total allocated rather than the live heap. There is no limit by default.
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.vm.env.set_memory_limit(limit);
+    }
+
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.vm.env.set_recursion_limit(limit);
+    }
+
     pub fn last_popped(&self) -> Option<&Object> {
         self.vm.last_popped.as_ref()
     }
 }
+
+// Calls a closure from outside of the VM, e.g. when it is passed to `map` and `eval` calls it.
+pub(crate) fn call(
+    env: Env,
+    closure: &Closure,
+    arguments: Vec<Object>,
+) -> Result<Object, eval::Error> {
+    match core::Vm::new(env).call_closure(closure, arguments) {
+        Ok(object) => Ok(object),
+        Err(core::Error::Runtime(err)) => Err(err),
+        // The other errors only come from invalid bytecode, which the compiler doesn't produce.
+        Err(err) => Err(eval::Error::InvalidBytecode {
+            message: format!("{:?}", err),
+        }),
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,7 @@
 use super::*;
-use crate::object::Object;
+use crate::eval;
+use crate::object;
+use crate::object::{ErrorObject, Object};
 
 #[test]
 fn test_integer_arithmetic() {
@@ -16,13 +18,15 @@ fn test_integer_arithmetic() {
         ("5 * 2 + 10", 20),
         ("5 + 2 * 10", 25),
         ("5 * (2 + 10)", 60),
-        // ("if (true) { 10 }", 10),
-        // ("if (true) { 10 } else { 20 }", 10),
-        // ("if (false) { 10 } else { 20 } ", 20),
-        // ("if (1) { 10 }", 10),
-        // ("if (1 < 2) { 10 }", 10),
-        // ("if (1 < 2) { 10 } else { 20 }", 10),
-        // ("if (1 > 2) { 10 } else { 20 }", 20),
+        ("7 % 3", 1),
+        ("if (true) { 10 }", 10),
+        ("if (true) { 10 } else { 20 }", 10),
+        ("if (false) { 10 } else { 20 } ", 20),
+        ("if (1) { 10 }", 10),
+        ("if (1 < 2) { 10 }", 10),
+        ("if (1 < 2) { 10 } else { 20 }", 10),
+        ("if (1 > 2) { 10 } else { 20 }", 20),
+        ("if (1 + 1 < 3 && 2 >= 2) { 10 } else { 20 }", 10),
     ];
 
     for (inp, expected) in tests {
@@ -55,6 +59,24 @@ fn test_boolean() {
         ("(1 < 2) == false", false),
         ("(1 > 2) == true", false),
         ("(1 > 2) == false", true),
+        ("1 + 2 < 4", true),
+        ("4 < 1 + 2", false),
+        ("1 <= 1", true),
+        ("2 <= 1", false),
+        ("1 >= 1", true),
+        ("1 >= 2", false),
+        ("true && true", true),
+        ("true && false", false),
+        ("false && true", false),
+        ("1 && 2", true),
+        ("false || true", true),
+        ("false || false", false),
+        ("true || false", true),
+        ("false && true || true", true),
+        ("true || true && false", true),
+        // The right side would fail if it were evaluated.
+        ("false && 1 / 0", false),
+        ("true || 1 / 0", true),
     ];
 
     for (inp, expected) in tests {
@@ -64,3 +86,493 @@ fn test_boolean() {
         assert_eq!(&Object::from(expected), vm.last_popped().unwrap());
     }
 }
+
+#[test]
+fn test_globals_and_assignment() {
+    let tests = vec![
+        ("let a = 1; a", Object::from(1)),
+        ("let a = 1; let b = a + 1; b", Object::from(2)),
+        ("let a = 1; a = 2; a", Object::from(2)),
+        ("let a = 1; a = a + 1", Object::from(2)),
+        ("let a = 1; let b = 1; a = b = 5; a + b", Object::from(10)),
+        (
+            "let a = 10; a += 5; a -= 1; a *= 2; a /= 4; a %= 4",
+            Object::from(3),
+        ),
+        ("let a = 1; if (true) { a = 2 }; a", Object::from(2)),
+        ("let xs = [1, 2, 3]; xs[1]", Object::from(2)),
+        ("let xs = [1, 2, 3]; xs[5]", Object::Null),
+        (r#"let s = "héllo"; s[1]"#, Object::from("é")),
+        (r#""héllo"[5]"#, Object::Null),
+        (
+            "let xs = [1, 2, 3]; xs[0] = -5; xs",
+            Object::List(vec![(-5).into(), 2.into(), 3.into()]),
+        ),
+        ("let xs = [1]; xs[0] = 2", Object::from(2)),
+        (
+            "let xs = [1, [2, 3]]; xs[1][0] += 10; xs",
+            Object::List(vec![1.into(), Object::List(vec![12.into(), 3.into()])]),
+        ),
+        (
+            "let xs = [1]; let ys = xs; xs[0] = 2; ys",
+            Object::List(vec![1.into()]),
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap());
+    }
+
+    // Built-ins aren't variables, so they can't be assigned to, like in `eval`.
+    for inp in ["len = 3", "let f = fn() { len += 1 }; f()"] {
+        let mut vm = Vm::new();
+
+        match vm.run(Vec::new(), inp) {
+            Err(Error::Compile(compiler::Error::UndefinedVariable(name))) => {
+                assert_eq!("len", name)
+            }
+            received => panic!("Expected undefined variable, received {:?}", received),
+        }
+    }
+}
+
+#[test]
+fn test_index_assignment_error() {
+    let tests = vec![
+        ("let xs = [1]; xs[1] = 2", object::Error::IndexOutOfRange),
+        (
+            "let xs = [1]; xs[-1] = 2",
+            object::Error::NegativeIndex {
+                object: Object::List(vec![1.into()]),
+            },
+        ),
+        (
+            "let a = 1; a[0] = 2",
+            object::Error::ItemAssignment { object: 1.into() },
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+
+        match vm.run(Vec::new(), inp) {
+            Err(Error::Internal(super::core::Error::Runtime(eval::Error::ObjectError(err)))) => {
+                assert_eq!(expected, err)
+            }
+            received => panic!("Expected error {:?}, received {:?}", expected, received),
+        }
+    }
+}
+
+#[test]
+fn test_interpolation() {
+    let tests = vec![
+        (
+            r#"let a = 1; let b = 2; "total: ${a + b}""#,
+            Object::from("total: 3"),
+        ),
+        (
+            r#""${[1, "b"]} ${1.5} ${1 > 2}""#,
+            Object::from("[1, b] 1.5 false"),
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap());
+    }
+}
+
+#[test]
+fn test_match() {
+    let tests = vec![
+        ("match (2) { 1 => 10, 2 => 20, _ => 30 }", Object::from(20)),
+        ("match (3) { 1 => 10, 2 => 20, _ => 30 }", Object::from(30)),
+        (
+            "match (5) { n if n > 10 => 1, n if n > 1 => n * 2 }",
+            Object::from(10),
+        ),
+        ("let x = match (1) { 1 => 2 }; x + 1", Object::from(3)),
+        // The bindings of an arm don't replace those around the match.
+        ("let x = 1; match (2) { x => x }; x", Object::from(1)),
+        // Jump tables.
+        (
+            r#"match ("b") { "a" => 1, "b" => 2, "b" => 3 }"#,
+            Object::from(2),
+        ),
+        ("match (-1) { 1 => 1, -1 => 2, _ => 3 }", Object::from(2)),
+        ("match (true) { false => 1, true => 2 }", Object::from(2)),
+        ("match (2.0) { 1 => 1, 2 => 2, _ => 3 }", Object::from(2)),
+        ("match ([]) { 1 => 1, 2 => 2, _ => 3 }", Object::from(3)),
+        // List patterns.
+        (
+            "match ([1, 2, 3]) { [a, ...r] => r }",
+            Object::List(vec![Object::from(2), Object::from(3)]),
+        ),
+        (
+            "match ([1, [2, 3]]) { [1, [a]] => 1, [1, [2, b]] => b, _ => 0 }",
+            Object::from(3),
+        ),
+        ("match ([]) { [_, ...r] => 1, [] => 2 }", Object::from(2)),
+        ("match (1) { [x] => x, x => x + 1 }", Object::from(2)),
+        // Destructuring.
+        (
+            "let [a, [b, ...c]] = [1, [2, 3]]; a + b + c[0]",
+            Object::from(6),
+        ),
+        (
+            "let f = fn([a, b], c = [a]) { c }; f([1, 2])[0]",
+            Object::from(1),
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap());
+    }
+
+    let mut vm = Vm::new();
+    match vm.run(Vec::new(), "match (1) { 2 => 2 }") {
+        Err(Error::Internal(super::core::Error::Runtime(eval::Error::NonExhaustiveMatch {
+            value,
+        }))) => {
+            assert_eq!(Object::from(1), value)
+        }
+        received => panic!("Expected non-exhaustive match, received {:?}", received),
+    }
+
+    let mut vm = Vm::new();
+    match vm.run(Vec::new(), "let [a, [b]] = [1, 2]") {
+        Err(Error::Internal(super::core::Error::Runtime(eval::Error::PatternMismatch {
+            value,
+            ..
+        }))) => {
+            assert_eq!(Object::from(2), value)
+        }
+        received => panic!("Expected pattern mismatch, received {:?}", received),
+    }
+}
+
+#[test]
+fn test_try_catch() {
+    let tests = vec![
+        ("try { 1 } catch (e) { 2 }", Object::from(1)),
+        (
+            "try { 1 / 0 } catch (e) { e }",
+            Object::from(ErrorObject::new("ZeroDivisionError", "division by zero")),
+        ),
+        (
+            "try { throw 1 } catch (e) { e }",
+            Object::from(ErrorObject::new(
+                "TypeError",
+                "exceptions must be errors, not int",
+            )),
+        ),
+        (
+            "try { match (1) { 2 => 2 } } catch (e) { e }",
+            Object::from(ErrorObject::new("ValueError", "no match arm for int '1'")),
+        ),
+        // The stack from the expression around the try block is kept.
+        ("1 + try { 2 * (1 / 0) } catch (e) { 10 }", Object::from(11)),
+        (
+            "let x = try { try { 1 / 0 } catch (e) { throw 1 } } catch (e) { 5 }; x",
+            Object::from(5),
+        ),
+        ("try { 1 } catch (e) { 2 }; 1 + 1", Object::from(2)),
+        // The binding of the handler and those of the body are only in scope there.
+        (
+            "let e = 5; try { 1 / 0 } catch (e) { 0 }; e",
+            Object::from(5),
+        ),
+        (
+            "let y = 1; try { let y = 2; 1 / 0 } catch (e) { y }",
+            Object::from(1),
+        ),
+        (
+            "try { 1 / 0 } catch (e) { e.kind }",
+            Object::from("ZeroDivisionError"),
+        ),
+        (
+            "[0, try { match (2) { 1 => 1, 2 => 1 / 0 } } catch (e) { 5 }]",
+            Object::List(vec![Object::from(0), Object::from(5)]),
+        ),
+        (r#"Error("bad").message"#, Object::from("bad")),
+        // Errors in a call continue at the handler around the call, with the stack of its frame.
+        (
+            "let f = fn(x) { [x, 1 / x] }; [1, try { f(0) } catch (e) { e.message }]",
+            Object::List(vec![Object::from(1), Object::from("division by zero")]),
+        ),
+        (
+            "let f = fn() { 1 + try { throw Error(\"a\") } catch (e) { 2 } }; [3, f()]",
+            Object::List(vec![Object::from(3), Object::from(3)]),
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap());
+    }
+
+    let mut vm = Vm::new();
+    match vm.run(Vec::new(), "try { 1 } catch (e) { 2 }; throw 1") {
+        Err(Error::Internal(super::core::Error::Runtime(eval::Error::ObjectError(
+            object::Error::ThrowType { object },
+        )))) => assert_eq!(Object::from(1), object),
+        received => panic!("Expected uncaught throw, received {:?}", received),
+    }
+
+    let mut vm = Vm::new();
+    match vm.run(Vec::new(), "try { let y = 1 } catch (e) { 0 }; y") {
+        Err(Error::Internal(super::core::Error::Runtime(eval::Error::IdentifierNotFound {
+            name,
+        }))) => assert_eq!("y", name),
+        received => panic!("Expected name error, received {:?}", received),
+    }
+}
+
+#[test]
+fn test_arithmetic_error() {
+    let tests = vec![
+        ("1 / 0", object::Error::ZeroDivision),
+        ("18446744073709551616 / 0", object::Error::ZeroDivision),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+
+        match vm.run(Vec::new(), inp) {
+            Err(Error::Internal(super::core::Error::Runtime(eval::Error::ObjectError(err)))) => {
+                assert_eq!(expected, err)
+            }
+            received => panic!("Expected error {:?}, received {:?}", expected, received),
+        }
+    }
+}
+
+#[test]
+fn test_big_integer_arithmetic() {
+    let tests = vec![
+        ("9223372036854775807 + 1", "9223372036854775808"),
+        ("-(-9223372036854775807 - 1)", "9223372036854775808"),
+        ("18446744073709551616 * 2", "36893488147419103232"),
+        ("18446744073709551616 - 18446744073709551615", "1"),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        let expected: object::BigInt = expected.parse().unwrap();
+        assert_eq!(&Object::from(expected), vm.last_popped().unwrap());
+    }
+
+    let tests = vec![
+        ("9007199254740993 == 9007199254740992", false),
+        ("9007199254740993 > 9007199254740992", true),
+        ("18446744073709551617 == 18446744073709551616", false),
+        ("18446744073709551616 < 18446744073709551617", true),
+        ("18446744073709551616 == 18446744073709551616", true),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&Object::from(expected), vm.last_popped().unwrap());
+    }
+}
+
+#[test]
+fn test_memory_limit() {
+    let tests = vec![
+        (r#"let a = "aaaa"; a + a + a"#, 16),
+        ("len(range(100))", 1024),
+        // Big integers from every arithmetic instruction are counted.
+        ("18446744073709551616 + 1", 8),
+        ("340282366920938463463374607431768211456 - 1", 8),
+        ("18446744073709551616 * 2", 8),
+        ("340282366920938463463374607431768211456 / 2", 8),
+        (
+            "340282366920938463463374607431768211461 % 340282366920938463463374607431768211463",
+            8,
+        ),
+        ("-340282366920938463463374607431768211456", 16),
+        // Running out of memory can't be caught.
+        (
+            "try { -340282366920938463463374607431768211456 } catch (e) { 1 }",
+            16,
+        ),
+    ];
+
+    for (inp, limit) in tests {
+        let mut vm = Vm::new();
+        vm.set_memory_limit(Some(limit));
+
+        match vm.run(Vec::new(), inp) {
+            Err(Error::Internal(super::core::Error::Runtime(eval::Error::ObjectError(err)))) => {
+                assert_eq!(object::Error::MemoryLimitExceeded { limit }, err, "{}", inp)
+            }
+            received => panic!("Expected memory error for {}, received {:?}", inp, received),
+        }
+
+        // There is no limit by default.
+        let mut vm = Vm::new();
+        assert!(vm.run(Vec::new(), inp).is_ok(), "{}", inp);
+    }
+}
+
+#[test]
+fn test_float_arithmetic() {
+    let tests = vec![
+        ("1.5", Object::Float(1.5)),
+        ("1.5 + 1", Object::Float(2.5)),
+        ("-1.5 * 2", Object::Float(-3.0)),
+        ("1 / 4.0", Object::Float(0.25)),
+        ("1.5 > 1", Object::Boolean(true)),
+        ("1.0 == 1", Object::Boolean(true)),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap());
+    }
+}
+
+#[test]
+fn test_functions() {
+    let tests = vec![
+        ("let add = fn(a, b) { a + b }; add(1, 2)", Object::from(3)),
+        ("fn(x) { x * 2 }(5)", Object::from(10)),
+        ("let f = fn() { return 1; 2 }; f()", Object::from(1)),
+        ("let f = fn() { let x = 1; }; f()", Object::Null),
+        (
+            "let fact = fn(n) { if (n < 2) { 1 } else { n * fact(n - 1) } }; fact(5)",
+            Object::from(120),
+        ),
+        // Functions see globals defined after them.
+        (
+            "let even = fn(n) { if (n == 0) { true } else { odd(n - 1) } }; \
+             let odd = fn(n) { if (n == 0) { false } else { even(n - 1) } }; even(10)",
+            Object::from(true),
+        ),
+        (
+            "let adder = fn(a) { fn(b) { a + b } }; adder(1)(2)",
+            Object::from(3),
+        ),
+        // Closures share the variables they capture.
+        (
+            "let counter = fn() { let n = 0; fn() { n += 1 } }; let c = counter(); c(); c()",
+            Object::from(2),
+        ),
+        (
+            "let f = fn(a) { let g = fn() { fn() { a } }; g()() }; f(4)",
+            Object::from(4),
+        ),
+        ("let f = fn(a, b = a + 1) { b }; f(1)", Object::from(2)),
+        ("let f = fn(a, b = a + 1) { b }; f(1, 5)", Object::from(5)),
+        (
+            "let f = fn(a, ...rest) { rest }; f(1, 2, 3)",
+            Object::List(vec![2.into(), 3.into()]),
+        ),
+        ("let x = 1; let f = fn() { x = 2 }; f(); x", Object::from(2)),
+        ("let f = fn() { let x = 1; }; f(); 1", Object::from(1)),
+        (
+            "map([1, 2], fn(x) { x * 10 })",
+            Object::List(vec![10.into(), 20.into()]),
+        ),
+        ("reduce([1, 2, 3], fn(acc, x) { acc + x })", Object::from(6)),
+        (r#"len("héllo")"#, Object::from(5)),
+        ("let len = fn(x) { 0 }; len([1])", Object::from(0)),
+        (
+            "try { fn(x) { x }() } catch (e) { e }",
+            Object::from(ErrorObject::new(
+                "TypeError",
+                "function takes 1 positional argument but 0 were given",
+            )),
+        ),
+        (
+            "try { map([0], fn(x) { 1 / x }) } catch (e) { e }",
+            Object::from(ErrorObject::new("ZeroDivisionError", "division by zero")),
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap(), "{}", inp);
+    }
+
+    let tests = vec![
+        ("x", "x"),
+        ("let f = fn() { y }; f(); let y = 1", "y"),
+        ("if (false) { let z = 1 }; z", "z"),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+
+        match vm.run(Vec::new(), inp) {
+            Err(Error::Internal(super::core::Error::Runtime(
+                eval::Error::IdentifierNotFound { name },
+            ))) => assert_eq!(expected, name),
+            received => panic!("Expected name error, received {:?}", received),
+        }
+    }
+}
+
+#[test]
+fn test_recursion_limit() {
+    let tests = vec![
+        (
+            "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(10)",
+            Object::from(10),
+        ),
+        (
+            "let f = fn(n) { f(n + 1) }; try { f(0) } catch (e) { e.kind }",
+            Object::from("RecursionError"),
+        ),
+        // Calls through built-ins count too.
+        (
+            "let f = fn(n) { map([n], fn(x) { f(x + 1) }) }; try { f(0) } catch (e) { e.kind }",
+            Object::from("RecursionError"),
+        ),
+        // The calls which failed don't count anymore after the error is caught.
+        (
+            "let f = fn(n) { f(n + 1) }; try { f(0) } catch (e) { 0 }; \
+             let g = fn(n) { if (n == 0) { 0 } else { g(n - 1) } }; g(15)",
+            Object::from(0),
+        ),
+    ];
+
+    for (inp, expected) in tests {
+        let mut vm = Vm::new();
+        vm.set_recursion_limit(20);
+        let _ = vm.run(Vec::new(), inp).unwrap();
+
+        assert_eq!(&expected, vm.last_popped().unwrap(), "{}", inp);
+    }
+
+    let mut vm = Vm::new();
+    vm.set_recursion_limit(20);
+    match vm.run(
+        Vec::new(),
+        "let f = fn(n) { if (n == 0) { 0 } else { 1 + f(n - 1) } }; f(30)",
+    ) {
+        Err(Error::Internal(super::core::Error::Runtime(eval::Error::ObjectError(err)))) => {
+            assert_eq!(object::Error::RecursionLimitExceeded { limit: 20 }, err)
+        }
+        received => panic!("Expected recursion error, received {:?}", received),
+    }
+}
@@ -1,7 +1,17 @@
 extern crate monkey;
 
-use monkey::object::Object;
+use monkey::input;
+use monkey::input::{Input, Lines};
+use monkey::object;
+use monkey::object::{ErrorObject, FromObject, IntoObject, NativeObject, Object};
+use monkey::output::Callback;
 use monkey::vm::Vm;
+use monkey::{EvalError, Expression, Interpreter, Operator, Statement, Token, Trivia};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::rc::Rc;
 
 #[test]
 fn vm_smoke_test() {
@@ -9,3 +19,489 @@ fn vm_smoke_test() {
     let _ = vm.run(Vec::new(), "1 + 2").unwrap();
     assert_eq!(&Object::Integer(3), vm.last_popped().unwrap());
 }
+
+#[test]
+fn pipeline_test() {
+    let tokens = monkey::tokenize("let x = 1;");
+    assert_eq!(5, tokens.len());
+    assert_eq!("let", tokens[0].to_string());
+
+    let program = monkey::parse("let x = 1 + 2; x * 2").unwrap();
+    let bytecode = monkey::compile(program).unwrap();
+    assert_eq!(Object::Integer(6), monkey::run(bytecode).unwrap());
+
+    let err = monkey::parse("let = 1;").unwrap_err();
+    assert!(matches!(err, monkey::Error::Parse(_)));
+    // Errors work with `?` in functions returning `Box<dyn Error>`.
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert!(err.source().is_some());
+    assert!(!err.to_string().is_empty());
+
+    let err = monkey::run(monkey::compile(monkey::parse("1 / 0").unwrap()).unwrap()).unwrap_err();
+    assert!(matches!(err, monkey::Error::Run(_)));
+
+    let mut interpreter = Interpreter::default();
+    assert_eq!("3", evaluate(&mut interpreter, "1 + 2"));
+}
+
+// Tools can name the types of the tokens, syntax trees and errors.
+#[test]
+fn public_types_test() {
+    let (tokens, trailing) = monkey::tokenize_with_trivia("// a\nlet x = 1; /* b */");
+    assert_eq!(
+        vec![Trivia::LineComment(" a".to_string())],
+        tokens[0].leading_trivia
+    );
+    assert_eq!(Token::Let, tokens[0].token);
+    assert_eq!(vec![Trivia::BlockComment(" b ".to_string())], trailing);
+
+    let program = monkey::parse("1 + x").unwrap();
+    match &program.statements[..] {
+        [Statement::Expression(Expression::Infix { operator, .. })] => {
+            assert_eq!(&Operator::Plus, operator)
+        }
+        statements => panic!("Expected an infix expression, received {:?}", statements),
+    }
+
+    let mut interpreter = Interpreter::new();
+    match interpreter.evaluate("1 + true").result {
+        Err(monkey::Error::Eval(EvalError::ObjectError(object::Error::TypeMismatch {
+            operator,
+            ..
+        }))) => assert_eq!(Operator::Plus, operator),
+        received => panic!("Expected a type mismatch, received {:?}", received),
+    }
+}
+
+#[test]
+fn output_test() {
+    let mut interpreter = Interpreter::new();
+    let result = interpreter.evaluate("print(1); print(2, 3)");
+    assert_eq!("1\n2 3", result.stdout);
+
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let mut interpreter = Interpreter::new();
+    let written = Rc::clone(&lines);
+    interpreter.set_output(Callback(move |line: &str| {
+        written.borrow_mut().push(line.to_string())
+    }));
+    // Lines are written as the program runs, before `evaluate` returns.
+    let seen = Rc::clone(&lines);
+    interpreter.register_typed_function("lines_written", move |()| Ok(seen.borrow().len()));
+
+    let result = interpreter.evaluate("print(\"a\"); print(\"b\"); lines_written()");
+    assert_eq!("2", result.result.unwrap().to_string());
+    assert_eq!("", result.stdout);
+    assert_eq!(vec!["a", "b"], *lines.borrow());
+}
+
+struct FailingInput;
+
+impl Input for FailingInput {
+    fn read_line(&mut self, _prompt: Option<&str>) -> io::Result<Option<String>> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+}
+
+#[test]
+fn input_test() {
+    let mut interpreter = Interpreter::new();
+    assert_eq!("null", evaluate(&mut interpreter, "input()"));
+
+    interpreter.set_input(Lines::new(vec!["3", "4"]));
+    assert_eq!(
+        "7",
+        evaluate(&mut interpreter, "int(input()) + int(input())")
+    );
+    interpreter.set_input(Lines::new(vec!["3", "4"]));
+    assert_eq!("[3, 4]", run(&mut interpreter, "map(read_lines(), int)"));
+
+    interpreter.set_input(FailingInput);
+    assert_eq!(
+        "IOError: cannot read input: closed",
+        evaluate(&mut interpreter, "read_lines()")
+    );
+    assert_eq!(
+        "IOError",
+        evaluate(&mut interpreter, "try { input() } catch (e) { e.kind }")
+    );
+
+    // The prompt is given to the input rather than printed.
+    let prompts = Rc::new(RefCell::new(Vec::new()));
+    let seen = Rc::clone(&prompts);
+    interpreter.set_input(input::Callback(move |prompt: Option<&str>| {
+        seen.borrow_mut().push(prompt.map(str::to_string));
+        Some("x".to_string())
+    }));
+    let result = interpreter.evaluate(r#"input("name? ") + read_line()"#);
+    assert_eq!("xx", result.result.unwrap().to_string());
+    assert_eq!("", result.stdout);
+    assert_eq!(vec![Some("name? ".to_string()), None], *prompts.borrow());
+}
+
+#[test]
+fn filesystem_test() {
+    let root = std::env::temp_dir().join(format!("monkey-integration-fs-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let mut interpreter = Interpreter::new();
+    let disabled = evaluate(&mut interpreter, r#"write_file("a.txt", "a")"#);
+
+    interpreter.set_filesystem_root(Some(root.clone()));
+    let written = evaluate(
+        &mut interpreter,
+        r#"write_file("a.txt", "a"); read_file("a.txt")"#,
+    );
+    let exists = root.join("a.txt").exists();
+
+    interpreter.set_filesystem_root(None);
+    let disabled_again = evaluate(&mut interpreter, r#"read_file("a.txt")"#);
+    std::fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!("PermissionError: filesystem access is disabled", disabled);
+    assert_eq!("a", written);
+    assert!(exists);
+    assert_eq!(disabled, disabled_again);
+}
+
+#[test]
+fn host_function_test() {
+    let mut interpreter = Interpreter::new();
+    interpreter.register_function("add", 2, |args| match args {
+        [Object::Integer(a), Object::Integer(b)] => Ok(Object::Integer(a + b)),
+        _ => Err(ErrorObject::new("TypeError", "add() takes integers").into()),
+    });
+
+    let cases = vec![
+        ("add(1, 2)", "3"),
+        ("map([1, 2], fn(x) { add(x, 10) })", "[11, 12]"),
+        ("type(add)", "builtin"),
+        ("add", "<built-in function add>"),
+        (
+            r#"try { add(1, "a") } catch (e) { e }"#,
+            "TypeError: add() takes integers",
+        ),
+        ("let add = fn(a, b) { a - b }; add(1, 2)", "-1"),
+    ];
+    for (inp, expected) in cases {
+        assert_eq!(expected, evaluate(&mut interpreter, inp));
+        assert_eq!(expected, run(&mut interpreter, inp));
+    }
+
+    let mut interpreter = Interpreter::new();
+    interpreter.register_function("one", 0, |_| Ok(Object::Integer(1)));
+    assert_eq!(
+        "TypeError: one() takes exactly 0 arguments (1 given)",
+        evaluate(&mut interpreter, "one(1)")
+    );
+}
+
+#[test]
+fn remove_built_ins_test() {
+    let mut interpreter = Interpreter::new();
+    interpreter.register_function("double", 1, |args| match args {
+        [Object::Integer(a)] => Ok(Object::Integer(a * 2)),
+        _ => Ok(Object::Null),
+    });
+    interpreter.remove_built_ins();
+
+    assert_eq!("4", evaluate(&mut interpreter, "double(2)"));
+    assert_eq!(
+        "NameError: name 'print' is not defined",
+        evaluate(&mut interpreter, "print(1)")
+    );
+    assert_eq!("4", run(&mut interpreter, "double(2)"));
+    assert_eq!(
+        "VM error: NameError: name 'print' is not defined",
+        run(&mut interpreter, "print(1)")
+    );
+    // Indexing doesn't go through the built-ins in the env.
+    assert_eq!("2", evaluate(&mut interpreter, "[1, 2][1]"));
+}
+
+// Programs give the same result on both backends.
+#[test]
+fn backend_parity_test() {
+    let tests = [
+        "let e = 5; try { 1 / 0 } catch (e) { 0 }; e",
+        "try { let y = 1; } catch (e) { 0 }; try { y } catch (e) { e }",
+        "let x = 1; try { x = 2; 1 / 0 } catch (e) { x }",
+        r#"try { throw Error("a") } catch (e) { [e.kind, e.message] }"#,
+        "let f = fn(n) { if (n == 0) { 1 / n } else { f(n - 1) } }; try { f(3) } catch (e) { e }",
+        "let add = fn(a, b = 2) { a + b }; [add(1), add(1, 1)]",
+        "map([1, 2, 3], fn(x) { x * 2 })",
+        "let x = 1; [match (2) { x => x }, x]",
+        "match ([1, 2, 3]) { [a, ...r] => [a, r] }",
+        r#"map([1, 2.0, "a", 4], fn(x) { match (x) { 1 => "one", 2 => "two", "a" => "A", _ => x } })"#,
+        "try { let [a, [b]] = [1, 2] } catch (e) { e }",
+        "let f = fn([a, ...b]) { b }; try { f([]) } catch (e) { e }",
+        r#"try { Error("a").message() } catch (e) { e }"#,
+    ];
+
+    for inp in tests {
+        let expected = evaluate(&mut Interpreter::new(), inp);
+        assert_eq!(expected, run(&mut Interpreter::new(), inp), "{}", inp);
+    }
+}
+
+#[derive(Debug)]
+struct Counter {
+    name: String,
+    count: Cell<isize>,
+}
+
+impl fmt::Display for Counter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<counter {}>", self.name)
+    }
+}
+
+impl NativeObject for Counter {
+    fn type_name(&self) -> &str {
+        "counter"
+    }
+
+    fn index(&self, index: &Object) -> Option<Result<Object, object::Error>> {
+        match index {
+            Object::Str(key) if key == "name" => Some(Ok(Object::Str(self.name.clone()))),
+            _ => None,
+        }
+    }
+
+    fn call_method(&self, name: &str, args: &[Object]) -> Option<Result<Object, object::Error>> {
+        match (name, args) {
+            ("increment", [Object::Integer(by)]) => {
+                self.count.set(self.count.get() + by);
+                Some(Ok(Object::Null))
+            }
+            ("increment", _) => Some(Err(ErrorObject::new(
+                "TypeError",
+                "increment() takes an integer",
+            )
+            .into())),
+            ("get", []) => Some(Ok(Object::Integer(self.count.get()))),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn native_object_test() {
+    let counter = Rc::new(Counter {
+        name: "clicks".to_string(),
+        count: Cell::new(0),
+    });
+    let mut interpreter = Interpreter::new();
+    let native: Rc<dyn NativeObject> = counter.clone();
+    interpreter.register_function("counter", 0, move |_| Ok(Object::Native(native.clone())));
+
+    let cases = vec![
+        (
+            "let c = counter(); c.increment(2); c.increment(3); c.get()",
+            "5",
+        ),
+        ("type(counter())", "counter"),
+        ("counter()", "<counter clicks>"),
+        (r#"counter()["name"]"#, "clicks"),
+        ("counter() == counter()", "true"),
+        ("counter() == 1", "false"),
+        (
+            r#"try { counter().increment("a") } catch (e) { e }"#,
+            "TypeError: increment() takes an integer",
+        ),
+        (
+            "counter().reset()",
+            "AttributeError: 'counter' object has no attribute 'reset'",
+        ),
+        (
+            "counter().get",
+            "AttributeError: 'counter' object has no attribute 'get'",
+        ),
+        (
+            "counter()[0]",
+            "TypeError: object of type 'counter' has no index",
+        ),
+    ];
+    for (inp, expected) in &cases {
+        assert_eq!(*expected, evaluate(&mut interpreter, inp));
+    }
+
+    // The script changed the host's value rather than a copy.
+    assert_eq!(5, counter.count.get());
+    let native: Rc<dyn NativeObject> = counter.clone();
+    assert_eq!(
+        Some(5),
+        native.downcast_ref::<Counter>().map(|c| c.count.get())
+    );
+
+    // The VM indexes native objects and calls their methods the same way.
+    counter.count.set(0);
+    for (inp, expected) in &cases {
+        let received = run(&mut interpreter, inp);
+        assert_eq!(
+            *expected,
+            received.trim_start_matches("VM error: "),
+            "{}",
+            inp
+        );
+    }
+    assert_eq!(5, counter.count.get());
+}
+
+#[test]
+fn conversion_test() {
+    assert_eq!(Object::Integer(1), 1u8.into_object());
+    assert_eq!("18446744073709551615", u64::MAX.into_object().to_string());
+    assert_eq!(u64::MAX, u64::from_object(&u64::MAX.into_object()).unwrap());
+    assert_eq!(
+        usize::MAX,
+        usize::from_object(&usize::MAX.into_object()).unwrap()
+    );
+    assert!(i64::from_object(&u64::MAX.into_object()).is_err());
+    assert_eq!(
+        Object::List(vec![Object::from("a"), Object::Null]),
+        vec![Some("a"), None].into_object()
+    );
+    assert_eq!(
+        "[[a, 1], [b, 2]]",
+        HashMap::from([("b".to_string(), 2), ("a".to_string(), 1)])
+            .into_object()
+            .to_string()
+    );
+
+    let object = (1, "a", 1.5).into_object();
+    assert_eq!(
+        (1, "a".to_string(), 1.5),
+        <(i32, String, f64)>::from_object(&object).unwrap()
+    );
+    assert_eq!(
+        Some(vec![1, 2]),
+        Option::<Vec<isize>>::from_object(&vec![1, 2].into_object()).unwrap()
+    );
+    let pairs = HashMap::from([("a".to_string(), true)]);
+    assert_eq!(
+        pairs,
+        HashMap::<String, bool>::from_object(&pairs.clone().into_object()).unwrap()
+    );
+
+    let errors = vec![
+        (
+            String::from_object(&Object::Integer(1)),
+            "TypeError: expected string, not 'int'",
+        ),
+        (
+            String::from_object(&vec![1].into_object()),
+            "TypeError: expected string, not 'list'",
+        ),
+    ];
+    for (result, expected) in errors {
+        assert_eq!(expected, result.unwrap_err().to_string());
+    }
+    assert_eq!(
+        "ValueError: 300 is out of range for u8",
+        u8::from_object(&Object::Integer(300))
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "TypeError: expected list of length 2, not 'list'",
+        <(bool, bool)>::from_object(&vec![true].into_object())
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+#[test]
+fn typed_host_function_test() {
+    let mut interpreter = Interpreter::new();
+    interpreter.register_typed_function("add", |(a, b): (isize, isize)| Ok(a + b));
+    interpreter.register_typed_function("greet", |(name,): (Option<String>,)| {
+        Ok(format!(
+            "hello {}",
+            name.unwrap_or_else(|| "world".to_string())
+        ))
+    });
+    interpreter
+        .register_typed_function("total", |(vals,): (Vec<f64>,)| Ok(vals.iter().sum::<f64>()));
+    interpreter.register_typed_function("nothing", |()| Ok(()));
+
+    let cases = vec![
+        ("add(1, 2)", "3"),
+        ("greet(\"monkey\")", "hello monkey"),
+        ("greet(if (false) { 1 })", "hello world"),
+        ("total([1, 2.5])", "3.5"),
+        ("nothing()", "null"),
+        (
+            "add(1, \"2\")",
+            "TypeError: add() argument 2 must be int, not 'string'",
+        ),
+        (
+            "total([1, \"2\"])",
+            "TypeError: total() argument 1 must be float, not 'string'",
+        ),
+        ("try { add(true, 1) } catch (e) { e.kind }", "TypeError"),
+        (
+            "add(1)",
+            "TypeError: add() takes exactly 2 arguments (1 given)",
+        ),
+    ];
+    for (inp, expected) in cases {
+        assert_eq!(expected, evaluate(&mut interpreter, inp));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    use monkey::object::{from_object, to_object};
+    use std::collections::BTreeMap;
+
+    let vals = BTreeMap::from([
+        ("a".to_string(), vec![Some(1)]),
+        ("b".to_string(), vec![None]),
+    ]);
+    let object = to_object(&vals).unwrap();
+    assert_eq!("[[a, [1]], [b, [null]]]", object.to_string());
+    assert_eq!(
+        vals,
+        from_object::<BTreeMap<String, Vec<Option<i32>>>>(&object).unwrap()
+    );
+
+    // `Result` is an enum with newtype variants.
+    let result: Result<i32, String> = Err("failed".to_string());
+    let object = to_object(&result).unwrap();
+    assert_eq!("[Err, failed]", object.to_string());
+    assert_eq!(result, from_object(&object).unwrap());
+
+    let tuple = ('c', 2u64, "s".to_string(), 0.5f32);
+    assert_eq!(tuple, from_object(&to_object(&tuple).unwrap()).unwrap());
+
+    assert_eq!(
+        "TypeError: expected a string, not integer `1`",
+        from_object::<String>(&Object::Integer(1))
+            .unwrap_err()
+            .to_string()
+    );
+    assert_eq!(
+        "ValueError: invalid value: integer `300`, expected u8",
+        from_object::<u8>(&Object::Integer(300))
+            .unwrap_err()
+            .to_string()
+    );
+}
+
+fn evaluate(interpreter: &mut Interpreter, inp: &str) -> String {
+    match interpreter.evaluate(inp).result {
+        Ok(object) => object.to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+// Like `evaluate`, but compiles the program and runs it on the VM.
+fn run(interpreter: &mut Interpreter, inp: &str) -> String {
+    let bytecode = monkey::compile(monkey::parse(inp).unwrap()).unwrap();
+    match interpreter.run(bytecode).result {
+        Ok(object) => object.to_string(),
+        Err(err) => err.to_string(),
+    }
+}